@@ -13,46 +13,563 @@ extern crate libc;
 use crate::core as core_abi;
 use crate::util;
 use connor::cond;
-use libc::c_char;
+use connor::fuzzy;
+use connor::geo;
+use connor::json;
+use connor::limits;
+use connor::operator;
+use connor::parallel;
+use connor::prepared;
+use connor::time;
+use connor::vector;
+use libc::{c_char, c_void};
 use serde_json;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::ffi::CString;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
 
+// Every export reads its inputs as UTF-8 strings given by a pointer and a length, checks its
+// pointers and returns an `ErrorCode`. On failure, the error message is written to the error
+// buffer unless it is null, and the outputs are left unchanged.
+
+/// Matches the document, or a single field, against the condition, both given as JSON, and
+/// writes whether it matches to `result`.
+///
+/// # Safety
+///
+/// Each input must point to `len` readable bytes, or be null if its length is 0. `result`
+/// must be writable, failing with `NullPointer` if null, and `error` must be null or point to a
+/// valid `StringBuffer`.
 #[no_mangle]
-pub extern "C" fn match_conditions(
+pub unsafe extern "C" fn match_conditions(
     cond_json: *const c_char,
+    cond_len: usize,
     doc_json: *const c_char,
+    doc_len: usize,
+    result: *mut bool,
     error: *mut util::StringBuffer,
-) -> bool {
-    let cond_str = unsafe { util::const_char_to_str(cond_json) };
-    let doc_str = unsafe { util::const_char_to_str(doc_json) };
-    match match_conditions_with(cond_str, doc_str) {
-        Ok(result) => result,
-        Err(e) => {
-            unsafe {
-                (*error).fill(&e);
-            }
-            false
+) -> util::ErrorCode {
+    util::run(error, || {
+        let result = util::out_ref(result, "result")?;
+        let cond_str = util::read_str(cond_json, cond_len, "condition")?;
+        let doc_str = util::read_str(doc_json, doc_len, "document")?;
+        *result = match_conditions_with(cond_str, doc_str)?;
+        Ok(())
+    })
+}
+
+pub fn match_conditions_with(cond_str: &str, doc_str: &str) -> Result<bool, util::Error> {
+    let cond = deserialize_condition(cond_str)?;
+    let doc = deserialize_doc_field(doc_str)?;
+    cond::match_conditions(&cond, &doc).map_err(match_failed)
+}
+
+fn match_failed(err: connor::error::Error) -> util::Error {
+    util::Error::new(util::ErrorCode::MatchFailed, err)
+}
+
+// Registered conditions are parsed and prepared once and shared between threads until freed.
+// Handles are never reused, so a freed handle can't be mistaken for a newer condition.
+static NEXT_HANDLE: AtomicU64 = AtomicU64::new(1);
+
+fn registry() -> &'static RwLock<HashMap<u64, Arc<prepared::PreparedCondition>>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<u64, Arc<prepared::PreparedCondition>>>> =
+        OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Parses the condition and writes a handle to it to `handle`. Handles are never 0.
+/// This is the same as `prepare_condition`.
+///
+/// # Safety
+///
+/// `cond_json` must point to `cond_len` readable bytes, or be null if `cond_len` is 0.
+/// `handle` must be writable, failing with `NullPointer` if null, and `error` must be null or
+/// point to a valid `StringBuffer`.
+#[no_mangle]
+pub unsafe extern "C" fn register_condition(
+    cond_json: *const c_char,
+    cond_len: usize,
+    handle: *mut u64,
+    error: *mut util::StringBuffer,
+) -> util::ErrorCode {
+    util::run(error, || {
+        let handle = util::out_ref(handle, "handle")?;
+        let cond_str = util::read_str(cond_json, cond_len, "condition")?;
+        *handle = register_condition_with(cond_str)?;
+        Ok(())
+    })
+}
+
+/// Matches the document against the registered condition and writes whether it matches to
+/// `result`. Conditions with params are matched with `execute_prepared` instead.
+///
+/// # Safety
+///
+/// `doc_json` must point to `doc_len` readable bytes, or be null if `doc_len` is 0. `result`
+/// must be writable, failing with `NullPointer` if null, and `error` must be null or point to a
+/// valid `StringBuffer`.
+#[no_mangle]
+pub unsafe extern "C" fn match_registered_condition(
+    handle: u64,
+    doc_json: *const c_char,
+    doc_len: usize,
+    result: *mut bool,
+    error: *mut util::StringBuffer,
+) -> util::ErrorCode {
+    util::run(error, || {
+        let result = util::out_ref(result, "result")?;
+        let doc_str = util::read_str(doc_json, doc_len, "document")?;
+        *result = match_registered_condition_with(handle, doc_str)?;
+        Ok(())
+    })
+}
+
+/// Parses the condition, which may compare against named params such as
+/// `{"EQ": {"Param": "name"}}`, and writes a handle to it to `handle`. Handles are never 0
+/// and are freed with `free_condition`.
+///
+/// # Safety
+///
+/// `cond_json` must point to `cond_len` readable bytes, or be null if `cond_len` is 0.
+/// `handle` must be writable, failing with `NullPointer` if null, and `error` must be null or
+/// point to a valid `StringBuffer`.
+#[no_mangle]
+pub unsafe extern "C" fn prepare_condition(
+    cond_json: *const c_char,
+    cond_len: usize,
+    handle: *mut u64,
+    error: *mut util::StringBuffer,
+) -> util::ErrorCode {
+    util::run(error, || {
+        let handle = util::out_ref(handle, "handle")?;
+        let cond_str = util::read_str(cond_json, cond_len, "condition")?;
+        *handle = prepare_condition_with(cond_str)?;
+        Ok(())
+    })
+}
+
+/// Matches the document against the prepared condition with the param values given as a
+/// JSON object mapping each name to a field, and writes whether it matches to `result`.
+///
+/// # Safety
+///
+/// Each input must point to `len` readable bytes, or be null if its length is 0. `result`
+/// must be writable, failing with `NullPointer` if null, and `error` must be null or point to a
+/// valid `StringBuffer`.
+#[no_mangle]
+pub unsafe extern "C" fn execute_prepared(
+    handle: u64,
+    params_json: *const c_char,
+    params_len: usize,
+    doc_json: *const c_char,
+    doc_len: usize,
+    result: *mut bool,
+    error: *mut util::StringBuffer,
+) -> util::ErrorCode {
+    util::run(error, || {
+        let result = util::out_ref(result, "result")?;
+        let params_str = util::read_str(params_json, params_len, "params")?;
+        let doc_str = util::read_str(doc_json, doc_len, "document")?;
+        *result = execute_prepared_with(handle, params_str, doc_str)?;
+        Ok(())
+    })
+}
+
+/// Frees the registered condition. Returns `NotFound` if the handle is unknown.
+/// Matches already running against the condition are not affected.
+#[no_mangle]
+pub extern "C" fn free_condition(handle: u64) -> util::ErrorCode {
+    let mut conditions = registry().write().unwrap_or_else(|e| e.into_inner());
+    match conditions.remove(&handle) {
+        Some(_) => util::ErrorCode::Ok,
+        None => util::ErrorCode::NotFound,
+    }
+}
+
+pub fn register_condition_with(cond_str: &str) -> Result<u64, util::Error> {
+    prepare_condition_with(cond_str)
+}
+
+pub fn prepare_condition_with(cond_str: &str) -> Result<u64, util::Error> {
+    let cond = deserialize_condition(cond_str)?;
+    let prepared = prepared::PreparedCondition::prepare(cond).map_err(|e| e.to_string())?;
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+    registry()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(handle, Arc::new(prepared));
+    Ok(handle)
+}
+
+fn registered(handle: u64) -> Result<Arc<prepared::PreparedCondition>, util::Error> {
+    registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&handle)
+        .cloned()
+        .ok_or_else(|| {
+            util::Error::new(
+                util::ErrorCode::NotFound,
+                format!("Unknown condition handle: {}", handle),
+            )
+        })
+}
+
+pub fn match_registered_condition_with(handle: u64, doc_str: &str) -> Result<bool, util::Error> {
+    let prepared = registered(handle)?;
+    let doc = deserialize_doc_field(doc_str)?;
+    prepared
+        .execute(&HashMap::new(), &doc)
+        .map_err(execution_failed)
+}
+
+pub fn execute_prepared_with(
+    handle: u64,
+    params_str: &str,
+    doc_str: &str,
+) -> Result<bool, util::Error> {
+    let prepared = registered(handle)?;
+    let params = deserialize_params(params_str)?;
+    let doc = deserialize_doc_field(doc_str)?;
+    prepared.execute(&params, &doc).map_err(execution_failed)
+}
+
+// Param values that don't fit the condition are invalid inputs rather than failed matches.
+fn execution_failed(err: connor::error::Error) -> util::Error {
+    match err {
+        connor::error::Error::UnboundParam(_)
+        | connor::error::Error::UnknownParam(_)
+        | connor::error::Error::ParamTypeMismatch(_) => {
+            util::Error::new(util::ErrorCode::InvalidInput, err)
+        }
+        err => match_failed(err),
+    }
+}
+
+/// Matches the condition against every document of a JSON array on `threads` threads, or on
+/// as many as the system runs in parallel if 0. Writes the indexes of the first `limit`
+/// matching documents, or of every match if `limit` is 0, to `indexes` in ascending order, up
+/// to `indexes_cap` of them, and the number of matches, which may exceed `indexes_cap`, to
+/// `count`.
+///
+/// # Safety
+///
+/// Each input must point to `len` readable bytes, or be null if its length is 0. `indexes`
+/// must point to `indexes_cap` writable integers, or be null if `indexes_cap` is 0. `count`
+/// must be writable, failing with `NullPointer` if null, and `error` must be null or point to a
+/// valid `StringBuffer`.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn filter_docs(
+    cond_json: *const c_char,
+    cond_len: usize,
+    docs_json: *const c_char,
+    docs_len: usize,
+    threads: usize,
+    limit: usize,
+    indexes: *mut u64,
+    indexes_cap: usize,
+    count: *mut u64,
+    error: *mut util::StringBuffer,
+) -> util::ErrorCode {
+    util::run(error, || {
+        let count = util::out_ref(count, "count")?;
+        if indexes.is_null() && indexes_cap > 0 {
+            return Err(util::Error::new(
+                util::ErrorCode::NullPointer,
+                "indexes is null",
+            ));
         }
+        let cond_str = util::read_str(cond_json, cond_len, "condition")?;
+        let docs_str = util::read_str(docs_json, docs_len, "documents")?;
+        let matches = filter_docs_with(cond_str, docs_str, threads, limit)?;
+        for (i, index) in matches.iter().take(indexes_cap).enumerate() {
+            *indexes.add(i) = *index as u64;
+        }
+        *count = matches.len() as u64;
+        Ok(())
+    })
+}
+
+pub fn filter_docs_with(
+    cond_str: &str,
+    docs_str: &str,
+    threads: usize,
+    limit: usize,
+) -> Result<Vec<usize>, util::Error> {
+    let cond = deserialize_condition(cond_str)?;
+    let docs = core_abi::deserialize_docs(docs_str)?;
+    let limits = current_limits();
+    for doc in &docs {
+        limits.check_doc(doc).map_err(|e| e.to_string())?;
     }
+    let docs = docs
+        .into_iter()
+        .map(core::doc::Field::Doc)
+        .collect::<Vec<_>>();
+    let filter = match threads {
+        0 => parallel::ParallelFilter::default(),
+        threads => parallel::ParallelFilter::new(threads),
+    };
+    let result = match limit {
+        0 => filter.filter(&cond, &docs),
+        limit => filter.first_n(&cond, &docs, limit),
+    };
+    result.map_err(match_failed)
 }
 
-pub fn match_conditions_with(cond_str: &str, doc_str: &str) -> Result<bool, String> {
-    let cond = deserialize_condition(cond_str).map_err(|e| e.to_string())?;
-    match core_abi::deserialize_doc(doc_str).map_err(|e| e.to_string()) {
-        Ok(doc) => {
-            cond::match_conditions(&cond, &core::doc::Field::Doc(doc)).map_err(|e| e.to_string())
+// The keys of the built-in conditions, which can't name custom operators. The condition
+// parser only handles the keys listed here, so a built-in missing from the list can't be
+// parsed rather than silently being shadowed by a custom operator.
+pub(crate) const BUILTIN_KEYS: &[&str] = &[
+    "EQ",
+    "NE",
+    "GT",
+    "GE",
+    "LT",
+    "LE",
+    "IN",
+    "NIN",
+    "LIKE",
+    "NLIKE",
+    "ILIKE",
+    "NILIKE",
+    "MATCH",
+    "PREFIX",
+    "CONTAINS",
+    "OVERLAPS",
+    "SUBSET_OF",
+    "LEN_EQ",
+    "LEN_NE",
+    "LEN_GT",
+    "LEN_GE",
+    "LEN_LT",
+    "LEN_LE",
+    "BETWEEN",
+    "PROP",
+    "WITHIN_RADIUS",
+    "WITHIN_BBOX",
+    "WITHIN_POLYGON",
+    "SIMILAR",
+    "FUZZY",
+    "META",
+    "JSON_PATH",
+    "JSON_EXISTS",
+    "AND",
+    "OR",
+    "NOT",
+];
+
+// OperatorCallback matches a value against a target, both given as JSON fields such as
+// `{"String":"10.0.0.0/8"}`, along with the user data given at registration. It returns 1 if
+// the value matches, 0 if it doesn't and a negative number if matching failed. It may be
+// called from several threads at once.
+pub type OperatorCallback = extern "C" fn(
+    target_json: *const c_char,
+    value_json: *const c_char,
+    user_data: *mut c_void,
+) -> i32;
+
+struct CallbackOperator {
+    name: String,
+    callback: OperatorCallback,
+    user_data: *mut c_void,
+}
+
+// The registering side guarantees that the callback and its user data can be used from any
+// thread.
+unsafe impl Send for CallbackOperator {}
+unsafe impl Sync for CallbackOperator {}
+
+impl operator::Operator for CallbackOperator {
+    fn matches(
+        &self,
+        target: &core::doc::Field,
+        value: &core::doc::Field,
+    ) -> connor::error::Result<bool> {
+        let failed = || connor::error::Error::OperatorFailed(self.name.clone());
+        let target_json = CString::new(core_abi::serialize_field(target)).map_err(|_| failed())?;
+        let value_json = CString::new(core_abi::serialize_field(value)).map_err(|_| failed())?;
+        match (self.callback)(target_json.as_ptr(), value_json.as_ptr(), self.user_data) {
+            0 => Ok(false),
+            result if result > 0 => Ok(true),
+            _ => Err(failed()),
         }
-        Err(_) => match core_abi::deserialize_field(doc_str).map_err(|e| e.to_string()) {
-            Ok(field) => cond::match_conditions(&cond, &field).map_err(|e| e.to_string()),
-            Err(e) => Err(e),
-        },
     }
 }
 
+/// Registers the callback as the operator with the given name, which conditions then use as a
+/// key like `{"IP_IN_RANGE": {"String": "10.0.0.0/8"}}`. Fails with `InvalidInput` if the
+/// name is empty, reserved or taken.
+///
+/// # Safety
+///
+/// `name` must point to `name_len` readable bytes, or be null if `name_len` is 0. The
+/// callback must be safe to call with the user data from any thread until the operator is
+/// unregistered, and `error` must be null or point to a valid `StringBuffer`.
+#[no_mangle]
+pub unsafe extern "C" fn register_operator(
+    name: *const c_char,
+    name_len: usize,
+    callback: Option<OperatorCallback>,
+    user_data: *mut c_void,
+    error: *mut util::StringBuffer,
+) -> util::ErrorCode {
+    util::run(error, || {
+        let callback = callback
+            .ok_or_else(|| util::Error::new(util::ErrorCode::NullPointer, "callback is null"))?;
+        let name = util::read_str(name, name_len, "name")?;
+        let operator = CallbackOperator {
+            name: name.to_string(),
+            callback,
+            user_data,
+        };
+        register_operator_with(name, Arc::new(operator))
+    })
+}
+
+/// Unregisters the operator. Fails with `NotFound` if no operator has the name.
+///
+/// # Safety
+///
+/// `name` must point to `name_len` readable bytes, or be null if `name_len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn unregister_operator(
+    name: *const c_char,
+    name_len: usize,
+) -> util::ErrorCode {
+    util::run(std::ptr::null_mut(), || {
+        let name = util::read_str(name, name_len, "name")?;
+        if !operator::unregister(name) {
+            return Err(util::Error::new(
+                util::ErrorCode::NotFound,
+                format!("Operator {} is not registered", name),
+            ));
+        }
+        Ok(())
+    })
+}
+
+pub fn register_operator_with(
+    name: &str,
+    operator: Arc<dyn operator::Operator>,
+) -> Result<(), util::Error> {
+    if BUILTIN_KEYS.contains(&name) {
+        return Err(format!("Operator name {} is reserved", name).into());
+    }
+    operator::register(name, operator).map_err(|e| e.to_string().into())
+}
+
+// The limits on the conditions, documents and params parsed by the entry points, which
+// receive them from untrusted clients.
+fn limits_registry() -> &'static RwLock<limits::Limits> {
+    static LIMITS: OnceLock<RwLock<limits::Limits>> = OnceLock::new();
+    LIMITS.get_or_init(|| RwLock::new(limits::Limits::default()))
+}
+
+fn current_limits() -> limits::Limits {
+    limits_registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Sets the limits on the conditions and documents parsed by the entry points. A limit of 0
+/// keeps its default.
+#[no_mangle]
+pub extern "C" fn set_limits(
+    max_condition_depth: usize,
+    max_condition_nodes: usize,
+    max_array_len: usize,
+    max_doc_depth: usize,
+    max_string_len: usize,
+) {
+    let mut limits = limits::Limits::default();
+    if max_condition_depth > 0 {
+        limits = limits.with_max_condition_depth(max_condition_depth);
+    }
+    if max_condition_nodes > 0 {
+        limits = limits.with_max_condition_nodes(max_condition_nodes);
+    }
+    if max_array_len > 0 {
+        limits = limits.with_max_array_len(max_array_len);
+    }
+    if max_doc_depth > 0 {
+        limits = limits.with_max_doc_depth(max_doc_depth);
+    }
+    if max_string_len > 0 {
+        limits = limits.with_max_string_len(max_string_len);
+    }
+    set_limits_with(limits);
+}
+
+pub fn set_limits_with(limits: limits::Limits) {
+    *limits_registry().write().unwrap_or_else(|e| e.into_inner()) = limits;
+}
+
+// Deserializes either a document or a single field.
+fn deserialize_doc_field(doc_str: &str) -> Result<core::doc::Field, String> {
+    deserialize_doc_field_with_limits(doc_str, &current_limits())
+}
+
+pub fn deserialize_doc_field_with_limits(
+    doc_str: &str,
+    limits: &limits::Limits,
+) -> Result<core::doc::Field, String> {
+    let field = match core_abi::deserialize_doc(doc_str) {
+        Ok(doc) => core::doc::Field::Doc(doc),
+        Err(_) => core_abi::deserialize_field(doc_str)?,
+    };
+    limits.check_field(&field).map_err(|e| e.to_string())?;
+    Ok(field)
+}
+
+// Parses and prepares the condition for a single match. Conditions matched repeatedly are
+// prepared once with `prepare_condition_with` instead.
+pub fn match_conditions_with_params(
+    cond_str: &str,
+    params_str: &str,
+    doc_str: &str,
+) -> Result<bool, util::Error> {
+    let cond = deserialize_condition(cond_str)?;
+    let params = deserialize_params(params_str)?;
+    let prepared = prepared::PreparedCondition::prepare(cond).map_err(|e| e.to_string())?;
+    let doc = deserialize_doc_field(doc_str)?;
+    prepared.execute(&params, &doc).map_err(execution_failed)
+}
+
+// Deserializes param values given as a JSON object mapping each name to a field.
+pub fn deserialize_params(json_str: &str) -> Result<HashMap<String, core::doc::Field>, String> {
+    let limits = current_limits();
+    let v: Value = serde_json::from_str(json_str).map_err(|e| e.to_string())?;
+    v.as_object()
+        .ok_or("Expected a JSON object for params")?
+        .iter()
+        .map(|(name, value)| {
+            let field = core_abi::FieldWrapper::try_from(value.clone())?;
+            limits.check_field(&field.0).map_err(|e| e.to_string())?;
+            Ok((name.clone(), field.0))
+        })
+        .collect()
+}
+
 pub fn deserialize_condition(json_str: &str) -> Result<cond::Condition, String> {
+    deserialize_condition_with_limits(json_str, &current_limits())
+}
+
+// Deserializes the condition and checks it against the limits. JSON nested deeper than the
+// parser's recursion limit is rejected before the condition is built.
+pub fn deserialize_condition_with_limits(
+    json_str: &str,
+    limits: &limits::Limits,
+) -> Result<cond::Condition, String> {
     let v: Value = serde_json::from_str(json_str).map_err(|e| e.to_string())?;
     let cond = ConditionWrapper::try_from(v).map_err(|e| e.to_string())?;
+    limits.check_condition(&cond.0).map_err(|e| e.to_string())?;
     Ok(cond.0)
 }
 
@@ -67,28 +584,106 @@ impl TryFrom<Value> for ConditionWrapper {
             Value::Object(map) => {
                 for (key, value) in map.iter() {
                     match key.as_str() {
-                        "EQ" | "NE" | "GT" | "GE" | "LT" | "LE" | "IN" | "NIN" | "LIKE"
-                        | "NLIKE" | "ILIKE" | "NILIKE" => {
+                        name if !BUILTIN_KEYS.contains(&name) => {
+                            if operator::get(name).is_none() {
+                                return Err(format!("Invalid key in Condition: {}", key));
+                            }
                             let field = core_abi::FieldWrapper::try_from(value.clone())?;
-                            return Ok(ConditionWrapper(cond::Condition::Op(
-                                match key.as_str() {
-                                    "EQ" => cond::Op::EQ,
-                                    "NE" => cond::Op::NE,
-                                    "GT" => cond::Op::GT,
-                                    "GE" => cond::Op::GE,
-                                    "LT" => cond::Op::LT,
-                                    "LE" => cond::Op::LE,
-                                    "IN" => cond::Op::IN,
-                                    "NIN" => cond::Op::NIN,
-                                    "LIKE" => cond::Op::LIKE,
-                                    "NLIKE" => cond::Op::NLIKE,
-                                    "ILIKE" => cond::Op::ILIKE,
-                                    "NILIKE" => cond::Op::NILIKE,
-                                    _ => unreachable!(),
-                                },
+                            return Ok(ConditionWrapper(cond::Condition::Custom(
+                                name.to_string(),
                                 field.0,
                             )));
                         }
+                        "EQ" | "NE" | "GT" | "GE" | "LT" | "LE" | "IN" | "NIN" | "LIKE"
+                        | "NLIKE" | "ILIKE" | "NILIKE" | "MATCH" | "PREFIX" => {
+                            let op = match key.as_str() {
+                                "EQ" => cond::Op::EQ,
+                                "NE" => cond::Op::NE,
+                                "GT" => cond::Op::GT,
+                                "GE" => cond::Op::GE,
+                                "LT" => cond::Op::LT,
+                                "LE" => cond::Op::LE,
+                                "IN" => cond::Op::IN,
+                                "NIN" => cond::Op::NIN,
+                                "LIKE" => cond::Op::LIKE,
+                                "NLIKE" => cond::Op::NLIKE,
+                                "ILIKE" => cond::Op::ILIKE,
+                                "NILIKE" => cond::Op::NILIKE,
+                                "MATCH" => cond::Op::MATCH,
+                                "PREFIX" => cond::Op::PREFIX,
+                                _ => unreachable!(),
+                            };
+                            if let Some(ref_val) = value.get("FieldRef") {
+                                let field_ref = deserialize_field_ref(ref_val)?;
+                                return Ok(ConditionWrapper(cond::Condition::FieldOp(
+                                    op, field_ref,
+                                )));
+                            }
+                            if let Some(name_val) = value.get("Param") {
+                                let name = name_val
+                                    .as_str()
+                                    .ok_or("Expected a string for Param name")?;
+                                return Ok(ConditionWrapper(cond::Condition::ParamOp(
+                                    op,
+                                    name.to_string(),
+                                )));
+                            }
+                            if let Some(time_val) = value.get("Now") {
+                                let time = deserialize_relative_time(time_val)?;
+                                return Ok(ConditionWrapper(cond::Condition::TimeOp(op, time)));
+                            }
+                            let field = core_abi::FieldWrapper::try_from(value.clone())?;
+                            return Ok(ConditionWrapper(cond::Condition::Op(op, field.0)));
+                        }
+                        "CONTAINS" | "OVERLAPS" | "SUBSET_OF" | "LEN_EQ" | "LEN_NE" | "LEN_GT"
+                        | "LEN_GE" | "LEN_LT" | "LEN_LE" => {
+                            let op = match key.as_str() {
+                                "CONTAINS" => cond::ArrayOp::CONTAINS,
+                                "OVERLAPS" => cond::ArrayOp::OVERLAPS,
+                                "SUBSET_OF" => cond::ArrayOp::SUBSET_OF,
+                                "LEN_EQ" => cond::ArrayOp::LEN_EQ,
+                                "LEN_NE" => cond::ArrayOp::LEN_NE,
+                                "LEN_GT" => cond::ArrayOp::LEN_GT,
+                                "LEN_GE" => cond::ArrayOp::LEN_GE,
+                                "LEN_LT" => cond::ArrayOp::LEN_LT,
+                                "LEN_LE" => cond::ArrayOp::LEN_LE,
+                                _ => unreachable!(),
+                            };
+                            let field = core_abi::FieldWrapper::try_from(value.clone())?;
+                            return Ok(ConditionWrapper(cond::Condition::ArrayOp(op, field.0)));
+                        }
+                        "BETWEEN" => {
+                            let range_map = value
+                                .as_object()
+                                .ok_or("Expected a JSON object for Between")?;
+                            let target = |key: &str| -> Result<cond::Target, String> {
+                                let target_val = range_map
+                                    .get(key)
+                                    .ok_or(format!("{} missing in Between", key))?;
+                                match target_val.get("Now") {
+                                    Some(time_val) => {
+                                        Ok(cond::Target::Time(deserialize_relative_time(time_val)?))
+                                    }
+                                    None => Ok(cond::Target::Value(
+                                        core_abi::FieldWrapper::try_from(target_val.clone())?.0,
+                                    )),
+                                }
+                            };
+                            let inclusive = |key: &str| -> Result<bool, String> {
+                                match range_map.get(key) {
+                                    Some(inclusive_val) => inclusive_val
+                                        .as_bool()
+                                        .ok_or(format!("{} is not a bool in Between", key)),
+                                    None => Ok(true),
+                                }
+                            };
+                            return Ok(ConditionWrapper(cond::Condition::Between(cond::Range {
+                                start: target("start")?,
+                                end: target("end")?,
+                                start_inclusive: inclusive("startInclusive")?,
+                                end_inclusive: inclusive("endInclusive")?,
+                            })));
+                        }
                         "PROP" => {
                             if let Value::Object(prop_map) = value {
                                 let index = prop_map
@@ -106,6 +701,158 @@ impl TryFrom<Value> for ConditionWrapper {
                                 )));
                             }
                         }
+                        "WITHIN_RADIUS" => {
+                            let center = value
+                                .get("center")
+                                .ok_or("Center missing in WithinRadius")?;
+                            let meters = value
+                                .get("meters")
+                                .and_then(Value::as_f64)
+                                .filter(|meters| *meters >= 0.0)
+                                .ok_or("Meters missing or not a positive number in WithinRadius")?;
+                            return Ok(ConditionWrapper(cond::Condition::Within(
+                                geo::Shape::Radius(
+                                    core_abi::deserialize_geo_point(center)?,
+                                    meters,
+                                ),
+                            )));
+                        }
+                        "WITHIN_BBOX" => {
+                            let south_west = value
+                                .get("southWest")
+                                .ok_or("southWest missing in WithinBBox")?;
+                            let north_east = value
+                                .get("northEast")
+                                .ok_or("northEast missing in WithinBBox")?;
+                            return Ok(ConditionWrapper(cond::Condition::Within(
+                                geo::Shape::BBox(
+                                    core_abi::deserialize_geo_point(south_west)?,
+                                    core_abi::deserialize_geo_point(north_east)?,
+                                ),
+                            )));
+                        }
+                        "WITHIN_POLYGON" => {
+                            let vertices = value
+                                .as_array()
+                                .ok_or("Expected array for WithinPolygon")?
+                                .iter()
+                                .map(core_abi::deserialize_geo_point)
+                                .collect::<Result<Vec<_>, String>>()?;
+                            if vertices.len() < 3 {
+                                return Err("WithinPolygon needs at least 3 points".to_string());
+                            }
+                            return Ok(ConditionWrapper(cond::Condition::Within(
+                                geo::Shape::Polygon(vertices),
+                            )));
+                        }
+                        "SIMILAR" => {
+                            let vector = value.get("vector").ok_or("Vector missing in Similar")?;
+                            let metric =
+                                match value.get("metric").and_then(Value::as_str) {
+                                    Some("cosine") => vector::Metric::Cosine,
+                                    Some("dot") => vector::Metric::Dot,
+                                    Some("l2") => vector::Metric::L2,
+                                    _ => return Err(
+                                        "Metric missing or not one of cosine, dot or l2 in Similar"
+                                            .to_string(),
+                                    ),
+                                };
+                            let threshold = value
+                                .get("threshold")
+                                .and_then(Value::as_f64)
+                                .ok_or("Threshold missing or not a number in Similar")?;
+                            return Ok(ConditionWrapper(cond::Condition::Similar(
+                                vector::Threshold {
+                                    vector: core_abi::deserialize_vector(vector)?,
+                                    metric,
+                                    threshold,
+                                },
+                            )));
+                        }
+                        "FUZZY" => {
+                            let fuzzy_map = value
+                                .as_object()
+                                .ok_or("Expected a JSON object for Fuzzy")?;
+                            let fuzzy_value = fuzzy_map
+                                .get("value")
+                                .and_then(Value::as_str)
+                                .ok_or("Value missing or not a string in Fuzzy")?;
+                            let similarity = match (
+                                fuzzy_map.get("maxDistance"),
+                                fuzzy_map.get("minSimilarity"),
+                            ) {
+                                (Some(distance_val), None) => fuzzy::Similarity::Levenshtein(
+                                    distance_val
+                                        .as_u64()
+                                        .ok_or("maxDistance is not a number in Fuzzy")?
+                                        as usize,
+                                ),
+                                (None, Some(similarity_val)) => fuzzy::Similarity::Trigram(
+                                    similarity_val
+                                        .as_f64()
+                                        .ok_or("minSimilarity is not a number in Fuzzy")?,
+                                ),
+                                _ => {
+                                    return Err("Expected either maxDistance or minSimilarity \
+                                                in Fuzzy"
+                                        .to_string())
+                                }
+                            };
+                            let case_insensitive = match fuzzy_map.get("caseInsensitive") {
+                                Some(case_val) => case_val
+                                    .as_bool()
+                                    .ok_or("caseInsensitive is not a bool in Fuzzy")?,
+                                None => false,
+                            };
+                            return Ok(ConditionWrapper(cond::Condition::Fuzzy(fuzzy::Fuzzy {
+                                value: fuzzy_value.to_string(),
+                                similarity,
+                                case_insensitive,
+                            })));
+                        }
+                        "META" => {
+                            let meta_map =
+                                value.as_object().ok_or("Expected a JSON object for Meta")?;
+                            let meta = match meta_map.get("key").and_then(Value::as_str) {
+                                Some("status") => cond::Meta::Status,
+                                Some("hidden") => cond::Meta::Hidden,
+                                Some("schema_version_id") => cond::Meta::SchemaVersionId,
+                                _ => return Err("Key missing or invalid in Meta".to_string()),
+                            };
+                            let condition_val = meta_map
+                                .get("condition")
+                                .ok_or("Condition missing in Meta")?;
+                            let condition = ConditionWrapper::try_from(condition_val.clone())?;
+                            return Ok(ConditionWrapper(cond::Condition::Meta(
+                                meta,
+                                Box::new(condition.0),
+                            )));
+                        }
+                        "JSON_PATH" => {
+                            let path_map = value
+                                .as_object()
+                                .ok_or("Expected a JSON object for JsonPath")?;
+                            let path = path_map
+                                .get("path")
+                                .and_then(Value::as_str)
+                                .ok_or("Path missing or not a string in JsonPath")?;
+                            let condition_val = path_map
+                                .get("condition")
+                                .ok_or("Condition missing in JsonPath")?;
+                            let condition = ConditionWrapper::try_from(condition_val.clone())?;
+                            return Ok(ConditionWrapper(cond::Condition::JsonPath(
+                                path.parse::<json::JsonPath>().map_err(|e| e.to_string())?,
+                                Box::new(condition.0),
+                            )));
+                        }
+                        "JSON_EXISTS" => {
+                            let path = value
+                                .as_str()
+                                .ok_or("Expected a string path for JsonExists")?;
+                            return Ok(ConditionWrapper(cond::Condition::JsonExists(
+                                path.parse::<json::JsonPath>().map_err(|e| e.to_string())?,
+                            )));
+                        }
                         "AND" | "OR" => {
                             let conditions = value
                                 .as_array()
@@ -130,7 +877,7 @@ impl TryFrom<Value> for ConditionWrapper {
                             )));
                         }
                         _ => {
-                            return Err(format!("Invalid key in Condition: {}", key));
+                            return Err(format!("Unsupported key in Condition: {}", key));
                         }
                     }
                 }
@@ -140,3 +887,39 @@ impl TryFrom<Value> for ConditionWrapper {
         }
     }
 }
+
+fn deserialize_relative_time(value: &Value) -> Result<time::RelativeTime, String> {
+    value
+        .as_str()
+        .ok_or("Expected a string for Now")?
+        .parse()
+        .map_err(|e: connor::error::Error| e.to_string())
+}
+
+fn deserialize_field_ref(value: &Value) -> Result<cond::FieldRef, String> {
+    let ref_map = value
+        .as_object()
+        .ok_or("Expected a JSON object for FieldRef")?;
+    let parent = match ref_map.get("parent") {
+        Some(parent_val) => parent_val
+            .as_u64()
+            .ok_or("Parent is not a number in FieldRef")? as usize,
+        None => 0,
+    };
+    let path = ref_map
+        .get("path")
+        .and_then(Value::as_array)
+        .ok_or("Path missing or not an array in FieldRef")?
+        .iter()
+        .map(|index| {
+            index
+                .as_u64()
+                .map(|index| index as usize)
+                .ok_or("Expected an index in FieldRef path".to_string())
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    if path.is_empty() {
+        return Err("Path in FieldRef must not be empty".to_string());
+    }
+    Ok(cond::FieldRef { parent, path })
+}
@@ -18,6 +18,7 @@ use crate::connor::*;
 use crate::util;
 use connor::*;
 use libc::c_char;
+use std::ffi::CString;
 
 const SIMPLE_EQ_COND_JSON: &str = r#"
 {
@@ -220,17 +221,23 @@ fn assert_no_error(data: *const c_char) {
 fn match_conditions_through_abi_no_match() {
     let mut out = util::StringBuffer::new();
 
-    let cond_str = SIMPLE_EQ_COND_JSON.to_string();
-    let doc_str = AUTHOR_DOC_WITH_PROPS_JSON.to_string();
+    let cond_str = CString::new(SIMPLE_EQ_COND_JSON).unwrap();
+    let doc_str = CString::new(AUTHOR_DOC_WITH_PROPS_JSON).unwrap();
 
-    let result = match_conditions(
-        cond_str.as_ptr() as *const c_char,
-        doc_str.as_ptr() as *const c_char,
-        &mut out,
-    );
+    let mut result = true;
+    let code = unsafe {
+        match_conditions(
+            cond_str.as_ptr(),
+            SIMPLE_EQ_COND_JSON.len(),
+            doc_str.as_ptr(),
+            AUTHOR_DOC_WITH_PROPS_JSON.len(),
+            &mut result,
+            &mut out,
+        )
+    };
 
     assert_no_error(out.data());
-
+    assert_eq!(code, util::ErrorCode::Ok);
     assert!(!result);
 }
 
@@ -278,17 +285,23 @@ const DOC_WITH_WITH_2_STRINGS_JSON: &str = r#"{
 fn match_conditions_through_abi_match() {
     let mut out = util::StringBuffer::new();
 
-    let cond_str = EQ_AND_NE_COND_JSON.to_string();
-    let doc_str = DOC_WITH_WITH_2_STRINGS_JSON.to_string();
+    let cond_str = CString::new(EQ_AND_NE_COND_JSON).unwrap();
+    let doc_str = CString::new(DOC_WITH_WITH_2_STRINGS_JSON).unwrap();
 
-    let result = match_conditions(
-        cond_str.as_ptr() as *const c_char,
-        doc_str.as_ptr() as *const c_char,
-        &mut out,
-    );
+    let mut result = false;
+    let code = unsafe {
+        match_conditions(
+            cond_str.as_ptr(),
+            EQ_AND_NE_COND_JSON.len(),
+            doc_str.as_ptr(),
+            DOC_WITH_WITH_2_STRINGS_JSON.len(),
+            &mut result,
+            &mut out,
+        )
+    };
 
     assert_no_error(out.data());
-
+    assert_eq!(code, util::ErrorCode::Ok);
     assert!(result);
 }
 
@@ -323,3 +336,1328 @@ fn match_conditions_with_null_op() {
         assert!(result.is_err(), "Expected error for op: {}", op);
     }
 }
+
+const FIELD_REF_COND_JSON: &str = r#"{
+    "PROP": {
+        "index": 2,
+        "condition": {
+            "GT": {
+                "FieldRef": {
+                    "path": [1]
+                }
+            }
+        }
+    }
+}"#;
+
+const DOC_WITH_TWO_DATES_JSON: &str = r#"{
+    "fields":[
+       {
+          "String":"bae-3bfe0092-e31f-5ebe-a3ba-fa18fac448a6"
+       },
+       {
+          "DateTime":"2024-04-07T12:34:56Z"
+       },
+       {
+          "OptionalDateTime":"2024-04-08T12:34:56+02:00"
+       }
+    ]
+ }"#;
+
+#[test]
+fn deserialize_cond_with_field_ref() {
+    let cond = deserialize_condition(FIELD_REF_COND_JSON).unwrap();
+
+    match cond {
+        cond::Condition::Prop(index, condition) => {
+            assert_eq!(index, 2);
+            match *condition {
+                cond::Condition::FieldOp(op, field_ref) => {
+                    assert_eq!(op, cond::Op::GT);
+                    assert_eq!(field_ref.parent, 0);
+                    assert_eq!(field_ref.path, vec![1]);
+                }
+                _ => panic!("Expected FieldOp"),
+            }
+        }
+        _ => panic!("Expected Prop"),
+    }
+}
+
+#[test]
+fn match_doc_against_field_ref_cond() {
+    let result = match_conditions_with(FIELD_REF_COND_JSON, DOC_WITH_TWO_DATES_JSON);
+
+    assert!(result.is_ok());
+    assert!(result.unwrap());
+}
+
+#[test]
+fn deserialize_invalid_field_ref() {
+    for field_ref in [
+        r#"{"path": []}"#,
+        r#"{"path": [-1]}"#,
+        r#"{"parent": "up", "path": [0]}"#,
+        r#"[0]"#,
+    ] {
+        let cond_str = format!(r#"{{"EQ": {{"FieldRef": {}}}}}"#, field_ref);
+        let result = deserialize_condition(&cond_str);
+
+        assert!(
+            result.is_err(),
+            "Expected error for FieldRef: {}",
+            field_ref
+        );
+    }
+}
+
+const PARAM_COND_JSON: &str = r#"{
+    "PROP": {
+        "index": 0,
+        "condition": {
+            "EQ": {
+                "Param": "userId"
+            }
+        }
+    }
+}"#;
+
+#[test]
+fn deserialize_cond_with_param() {
+    let cond = deserialize_condition(PARAM_COND_JSON).unwrap();
+
+    match cond {
+        cond::Condition::Prop(0, condition) => match *condition {
+            cond::Condition::ParamOp(op, name) => {
+                assert_eq!(op, cond::Op::EQ);
+                assert_eq!(name, "userId");
+            }
+            _ => panic!("Expected ParamOp"),
+        },
+        _ => panic!("Expected Prop"),
+    }
+}
+
+#[test]
+fn match_doc_against_param_cond() {
+    let params = r#"{"userId": {"String": "bae-3bfe0092-e31f-5ebe-a3ba-fa18fac448a6"}}"#;
+    let result = match_conditions_with_params(PARAM_COND_JSON, params, DOC_WITH_TWO_DATES_JSON);
+    assert!(result.unwrap());
+
+    let params = r#"{"userId": {"String": "bae-other"}}"#;
+    let result = match_conditions_with_params(PARAM_COND_JSON, params, DOC_WITH_TWO_DATES_JSON);
+    assert!(!result.unwrap());
+}
+
+#[test]
+fn match_param_cond_with_invalid_params() {
+    for params in [
+        r#"{}"#,
+        r#"{"userId": {"String": "x"}, "other": {"Int": 1}}"#,
+        r#"{"userId": {"Doc": {"fields": []}}}"#,
+        r#"["userId"]"#,
+    ] {
+        let result = match_conditions_with_params(PARAM_COND_JSON, params, DOC_WITH_TWO_DATES_JSON);
+
+        assert!(result.is_err(), "Expected error for params: {}", params);
+    }
+
+    let result = match_conditions_with(PARAM_COND_JSON, DOC_WITH_TWO_DATES_JSON);
+    assert!(result.is_err());
+}
+
+#[test]
+fn execute_prepared_through_abi() {
+    let mut out = util::StringBuffer::new();
+    let cond = PARAM_COND_JSON;
+    let mut handle = 0;
+    let code =
+        unsafe { prepare_condition(cond.as_ptr().cast(), cond.len(), &mut handle, &mut out) };
+    assert_no_error(out.data());
+    assert_eq!(code, util::ErrorCode::Ok);
+
+    let doc = DOC_WITH_TWO_DATES_JSON;
+    let execute = |params: &str, result: &mut bool, out: &mut util::StringBuffer| unsafe {
+        execute_prepared(
+            handle,
+            params.as_ptr().cast(),
+            params.len(),
+            doc.as_ptr().cast(),
+            doc.len(),
+            result,
+            out,
+        )
+    };
+    let mut result = false;
+    let params = r#"{"userId": {"String": "bae-3bfe0092-e31f-5ebe-a3ba-fa18fac448a6"}}"#;
+    assert_eq!(execute(params, &mut result, &mut out), util::ErrorCode::Ok);
+    assert_no_error(out.data());
+    assert!(result);
+    let params = r#"{"userId": {"String": "bae-other"}}"#;
+    assert_eq!(execute(params, &mut result, &mut out), util::ErrorCode::Ok);
+    assert!(!result);
+
+    assert_eq!(
+        execute("{}", &mut result, &mut out),
+        util::ErrorCode::InvalidInput
+    );
+    assert_eq!(
+        execute(
+            r#"{"userId": {"Int": 1}, "x": {"Int": 1}}"#,
+            &mut result,
+            &mut out
+        ),
+        util::ErrorCode::InvalidInput
+    );
+    let code = unsafe {
+        execute_prepared(
+            handle,
+            std::ptr::null(),
+            1,
+            doc.as_ptr().cast(),
+            doc.len(),
+            &mut result,
+            &mut out,
+        )
+    };
+    assert_eq!(code, util::ErrorCode::NullPointer);
+    let code = unsafe {
+        execute_prepared(
+            handle,
+            INVALID_UTF8.as_ptr().cast(),
+            INVALID_UTF8.len(),
+            doc.as_ptr().cast(),
+            doc.len(),
+            &mut result,
+            &mut out,
+        )
+    };
+    assert_eq!(code, util::ErrorCode::InvalidUtf8);
+    let params = "{}";
+    let code = unsafe {
+        execute_prepared(
+            handle,
+            params.as_ptr().cast(),
+            params.len(),
+            doc.as_ptr().cast(),
+            doc.len(),
+            std::ptr::null_mut(),
+            &mut out,
+        )
+    };
+    assert_eq!(code, util::ErrorCode::NullPointer);
+
+    // matching without param values leaves the param unbound
+    let result = match_registered_condition_with(handle, doc);
+    assert_eq!(result.unwrap_err().code, util::ErrorCode::InvalidInput);
+
+    assert_eq!(free_condition(handle), util::ErrorCode::Ok);
+    let params = r#"{"userId": {"String": "bae-other"}}"#;
+    let mut result = false;
+    assert_eq!(
+        execute(params, &mut result, &mut out),
+        util::ErrorCode::NotFound
+    );
+
+    let code = unsafe { prepare_condition(std::ptr::null(), 1, &mut handle, std::ptr::null_mut()) };
+    assert_eq!(code, util::ErrorCode::NullPointer);
+}
+
+#[test]
+fn match_registered_condition_through_abi() {
+    let mut out = util::StringBuffer::new();
+
+    let cond = EQ_AND_NE_COND_JSON;
+    let mut handle = 0;
+    let code =
+        unsafe { register_condition(cond.as_ptr().cast(), cond.len(), &mut handle, &mut out) };
+    assert_no_error(out.data());
+    assert_eq!(code, util::ErrorCode::Ok);
+    assert_ne!(handle, 0);
+
+    let mut result = false;
+    for (doc, expected) in [
+        (DOC_WITH_WITH_2_STRINGS_JSON, true),
+        (AUTHOR_DOC_WITH_PROPS_JSON, false),
+    ] {
+        let code = unsafe {
+            match_registered_condition(
+                handle,
+                doc.as_ptr().cast(),
+                doc.len(),
+                &mut result,
+                &mut out,
+            )
+        };
+        assert_no_error(out.data());
+        assert_eq!(code, util::ErrorCode::Ok);
+        assert_eq!(result, expected);
+    }
+
+    assert_eq!(free_condition(handle), util::ErrorCode::Ok);
+    assert_eq!(free_condition(handle), util::ErrorCode::NotFound);
+}
+
+#[test]
+fn register_invalid_condition() {
+    let result = register_condition_with(r#"{"XOR": []}"#);
+    assert!(result.is_err());
+
+    let mut out = util::StringBuffer::new();
+    let cond = r#"{"XOR": []}"#;
+    let mut handle = 0;
+    let code =
+        unsafe { register_condition(cond.as_ptr().cast(), cond.len(), &mut handle, &mut out) };
+    assert_eq!(code, util::ErrorCode::InvalidInput);
+    assert_eq!(handle, 0);
+}
+
+#[test]
+fn match_freed_condition() {
+    let handle = register_condition_with(SIMPLE_EQ_COND_JSON).unwrap();
+    assert_eq!(free_condition(handle), util::ErrorCode::Ok);
+
+    let result = match_registered_condition_with(handle, AUTHOR_DOC_WITH_PROPS_JSON);
+    assert_eq!(result.unwrap_err().code, util::ErrorCode::NotFound);
+}
+
+#[test]
+fn match_registered_condition_from_many_threads() {
+    let handle = register_condition_with(EQ_AND_NE_COND_JSON).unwrap();
+
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            std::thread::spawn(move || {
+                for _ in 0..100 {
+                    let result =
+                        match_registered_condition_with(handle, DOC_WITH_WITH_2_STRINGS_JSON);
+                    assert!(result.unwrap());
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    assert_eq!(free_condition(handle), util::ErrorCode::Ok);
+}
+
+const BETWEEN_COND_JSON: &str = r#"{
+    "PROP": {
+        "index": 1,
+        "condition": {
+            "BETWEEN": {
+                "start": {"DateTime": "2024-04-01T00:00:00Z"},
+                "end": {"Now": "now"},
+                "endInclusive": false
+            }
+        }
+    }
+}"#;
+
+#[test]
+fn deserialize_cond_with_between() {
+    let cond = deserialize_condition(BETWEEN_COND_JSON).unwrap();
+
+    match cond {
+        cond::Condition::Prop(1, condition) => match *condition {
+            cond::Condition::Between(range) => {
+                assert!(matches!(range.start, cond::Target::Value(_)));
+                assert!(matches!(range.end, cond::Target::Time(_)));
+                assert!(range.start_inclusive);
+                assert!(!range.end_inclusive);
+            }
+            _ => panic!("Expected Between"),
+        },
+        _ => panic!("Expected Prop"),
+    }
+}
+
+#[test]
+fn match_doc_against_between_cond() {
+    let result = match_conditions_with(BETWEEN_COND_JSON, DOC_WITH_TWO_DATES_JSON);
+
+    assert!(result.unwrap());
+}
+
+#[test]
+fn match_doc_against_relative_time_cond() {
+    let cond_str = r#"{"PROP": {"index": 1, "condition": {"LT": {"Now": "now-1d/d"}}}}"#;
+    let result = match_conditions_with(cond_str, DOC_WITH_TWO_DATES_JSON);
+
+    assert!(result.unwrap());
+}
+
+#[test]
+fn deserialize_invalid_relative_time() {
+    for time in [r#""yesterday""#, r#""now-7""#, r#""now/x""#, r#"7"#] {
+        let cond_str = format!(r#"{{"GT": {{"Now": {}}}}}"#, time);
+        let result = deserialize_condition(&cond_str);
+
+        assert!(result.is_err(), "Expected error for Now: {}", time);
+    }
+}
+
+const DOC_WITH_TAGS_JSON: &str = r#"{
+    "fields":[
+       {
+          "StringArray":["rust", "go", "c"]
+       },
+       {
+          "OptionalIntArray":[1, null, 3]
+       }
+    ]
+ }"#;
+
+#[test]
+fn match_doc_against_array_conds() {
+    for (cond_str, expected) in [
+        (
+            r#"{"PROP": {"index": 0, "condition": {"CONTAINS": {"StringArray": ["go", "rust"]}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"CONTAINS": {"StringArray": ["go", "java"]}}}}"#,
+            false,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"OVERLAPS": {"StringArray": ["go", "java"]}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 1, "condition": {"SUBSET_OF": {"OptionalIntArray": [1, 2, 3, null]}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 1, "condition": {"CONTAINS": {"OptionalIntArray": [null]}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"LEN_GT": {"Int": 2}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 1, "condition": {"LEN_LT": {"Int": 3}}}}"#,
+            false,
+        ),
+    ] {
+        let result = match_conditions_with(cond_str, DOC_WITH_TAGS_JSON);
+
+        assert_eq!(
+            result.unwrap(),
+            expected,
+            "Unexpected result for {}",
+            cond_str
+        );
+    }
+}
+
+const DELETED_DOC_JSON: &str = r#"{
+    "fields":[
+       {
+          "String":"bae-3bfe0092-e31f-5ebe-a3ba-fa18fac448a6"
+       }
+    ],
+    "hidden": false,
+    "status": "Deleted",
+    "schema_version_id": "bafkreia2"
+ }"#;
+
+#[test]
+fn match_doc_against_meta_conds() {
+    for (cond_str, expected) in [
+        (
+            r#"{"META": {"key": "status", "condition": {"EQ": {"String": "Deleted"}}}}"#,
+            true,
+        ),
+        (
+            r#"{"META": {"key": "hidden", "condition": {"EQ": {"Bool": true}}}}"#,
+            false,
+        ),
+        (
+            r#"{"META": {"key": "schema_version_id", "condition": {"LIKE": {"String": "bafk%"}}}}"#,
+            true,
+        ),
+    ] {
+        let result = match_conditions_with(cond_str, DELETED_DOC_JSON);
+
+        assert_eq!(
+            result.unwrap(),
+            expected,
+            "Unexpected result for {}",
+            cond_str
+        );
+    }
+}
+
+#[test]
+fn deserialize_invalid_meta_cond() {
+    for cond_str in [
+        r#"{"META": {"key": "version", "condition": {"EQ": {"String": "x"}}}}"#,
+        r#"{"META": {"key": "status"}}"#,
+        r#"{"META": "status"}"#,
+    ] {
+        let result = deserialize_condition(cond_str);
+
+        assert!(result.is_err(), "Expected error for {}", cond_str);
+    }
+}
+
+#[test]
+fn match_doc_against_text_match_cond() {
+    let cond_str = r#"{"PROP": {"index": 0, "condition": {"MATCH": {"String": "\"go rust\" "}}}}"#;
+    let result = match_conditions_with(cond_str, DOC_WITH_TAGS_JSON);
+    assert!(!result.unwrap());
+
+    let cond_str = r#"{"PROP": {"index": 0, "condition": {"MATCH": {"String": "Go OR java"}}}}"#;
+    let result = match_conditions_with(cond_str, DOC_WITH_TAGS_JSON);
+    assert!(result.unwrap());
+}
+
+#[test]
+fn match_doc_against_fuzzy_conds() {
+    for (cond_str, expected) in [
+        (
+            r#"{"PROP": {"index": 0, "condition": {"FUZZY": {"value": "rusty", "maxDistance": 1}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"FUZZY": {"value": "RUST", "maxDistance": 0}}}}"#,
+            false,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"FUZZY": {"value": "RUST", "maxDistance": 0, "caseInsensitive": true}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"FUZZY": {"value": "rst", "minSimilarity": 0.9}}}}"#,
+            false,
+        ),
+    ] {
+        let result = match_conditions_with(cond_str, DOC_WITH_TAGS_JSON);
+
+        assert_eq!(
+            result.unwrap(),
+            expected,
+            "Unexpected result for {}",
+            cond_str
+        );
+    }
+}
+
+#[test]
+fn deserialize_invalid_fuzzy_cond() {
+    for cond_str in [
+        r#"{"FUZZY": {"value": "x"}}"#,
+        r#"{"FUZZY": {"value": "x", "maxDistance": 1, "minSimilarity": 0.5}}"#,
+        r#"{"FUZZY": {"value": "x", "maxDistance": -1}}"#,
+        r#"{"FUZZY": {"maxDistance": 1}}"#,
+    ] {
+        let result = deserialize_condition(cond_str);
+
+        assert!(result.is_err(), "Expected error for {}", cond_str);
+    }
+}
+
+const DOC_WITH_LOCATION_JSON: &str = r#"{
+    "fields":[
+       {
+          "GeoPoint":{"lat": 48.8566, "lon": 2.3522}
+       },
+       {
+          "OptionalGeoPointArray":[null, {"lat": 51.5074, "lon": -0.1278}]
+       }
+    ]
+ }"#;
+
+#[test]
+fn match_doc_against_geo_conds() {
+    for (cond_str, expected) in [
+        (
+            r#"{"PROP": {"index": 0, "condition": {"WITHIN_RADIUS": {"center": {"lat": 51.5074, "lon": -0.1278}, "meters": 350000}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"WITHIN_RADIUS": {"center": {"lat": 51.5074, "lon": -0.1278}, "meters": 340000}}}}"#,
+            false,
+        ),
+        (
+            r#"{"PROP": {"index": 1, "condition": {"WITHIN_BBOX": {"southWest": {"lat": 50, "lon": -1}, "northEast": {"lat": 52, "lon": 0}}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"WITHIN_POLYGON": [{"lat": 48, "lon": 2}, {"lat": 49, "lon": 2}, {"lat": 49, "lon": 3}]}}}"#,
+            true,
+        ),
+    ] {
+        let result = match_conditions_with(cond_str, DOC_WITH_LOCATION_JSON);
+
+        assert_eq!(
+            result.unwrap(),
+            expected,
+            "Unexpected result for {}",
+            cond_str
+        );
+    }
+}
+
+#[test]
+fn deserialize_invalid_geo_conds() {
+    for cond_str in [
+        r#"{"WITHIN_RADIUS": {"center": {"lat": 91, "lon": 0}, "meters": 1}}"#,
+        r#"{"WITHIN_RADIUS": {"center": {"lat": 0, "lon": 0}, "meters": -1}}"#,
+        r#"{"WITHIN_BBOX": {"southWest": {"lat": 0, "lon": 0}}}"#,
+        r#"{"WITHIN_POLYGON": [{"lat": 0, "lon": 0}, {"lat": 1, "lon": 1}]}"#,
+    ] {
+        let result = deserialize_condition(cond_str);
+
+        assert!(result.is_err(), "Expected error for {}", cond_str);
+    }
+}
+
+const DOC_WITH_EMBEDDING_JSON: &str = r#"{
+    "fields":[
+       {
+          "Vector":[1.0, 1.0]
+       },
+       {
+          "OptionalVector":"0000803f00000000"
+       }
+    ]
+ }"#;
+
+#[test]
+fn match_doc_against_similar_conds() {
+    for (cond_str, expected) in [
+        (
+            r#"{"PROP": {"index": 0, "condition": {"SIMILAR": {"vector": [1, 0], "metric": "cosine", "threshold": 0.7}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"SIMILAR": {"vector": [1, 0], "metric": "cosine", "threshold": 0.75}}}}"#,
+            false,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"SIMILAR": {"vector": [2, 3], "metric": "dot", "threshold": 5}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 1, "condition": {"SIMILAR": {"vector": [1, 0], "metric": "l2", "threshold": 0}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 1, "condition": {"SIMILAR": {"vector": "0000803f0000803f", "metric": "l2", "threshold": 0.5}}}}"#,
+            false,
+        ),
+    ] {
+        let result = match_conditions_with(cond_str, DOC_WITH_EMBEDDING_JSON);
+
+        assert_eq!(
+            result.unwrap(),
+            expected,
+            "Unexpected result for {}",
+            cond_str
+        );
+    }
+}
+
+#[test]
+fn deserialize_invalid_similar_conds() {
+    for cond_str in [
+        r#"{"SIMILAR": {"vector": [1, 0], "metric": "manhattan", "threshold": 1}}"#,
+        r#"{"SIMILAR": {"vector": [1, 0], "metric": "l2"}}"#,
+        r#"{"SIMILAR": {"metric": "l2", "threshold": 1}}"#,
+        r#"{"SIMILAR": {"vector": ["a"], "metric": "l2", "threshold": 1}}"#,
+        r#"{"SIMILAR": {"vector": "0000803", "metric": "l2", "threshold": 1}}"#,
+    ] {
+        let result = deserialize_condition(cond_str);
+
+        assert!(result.is_err(), "Expected error for {}", cond_str);
+    }
+}
+
+const DOC_WITH_JSON_JSON: &str = r#"{
+    "fields":[
+       {
+          "Json":{"meta": {"tags": ["rust", "go"], "stars": 42, "owner": null}}
+       }
+    ]
+ }"#;
+
+#[test]
+fn match_doc_against_json_conds() {
+    for (cond_str, expected) in [
+        (
+            r#"{"PROP": {"index": 0, "condition": {"JSON_PATH": {"path": "meta.tags[1]", "condition": {"EQ": {"String": "go"}}}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"JSON_PATH": {"path": "meta.stars", "condition": {"GT": {"Int": 50}}}}}}"#,
+            false,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"JSON_PATH": {"path": "meta", "condition": {"EQ": {"Json": {"tags": ["rust", "go"], "stars": 42, "owner": null}}}}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"JSON_EXISTS": "meta.owner"}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"JSON_EXISTS": "meta.tags[2]"}}}"#,
+            false,
+        ),
+    ] {
+        let result = match_conditions_with(cond_str, DOC_WITH_JSON_JSON);
+
+        assert_eq!(
+            result.unwrap(),
+            expected,
+            "Unexpected result for {}",
+            cond_str
+        );
+    }
+}
+
+#[test]
+fn deserialize_invalid_json_conds() {
+    for cond_str in [
+        r#"{"JSON_PATH": {"path": "a..b", "condition": {"EQ": {"Int": 1}}}}"#,
+        r#"{"JSON_PATH": {"path": "a"}}"#,
+        r#"{"JSON_PATH": {"condition": {"EQ": {"Int": 1}}}}"#,
+        r#"{"JSON_EXISTS": 1}"#,
+    ] {
+        let result = deserialize_condition(cond_str);
+
+        assert!(result.is_err(), "Expected error for {}", cond_str);
+    }
+}
+
+const DOC_WITH_BLOBS_JSON: &str = r#"{
+    "fields":[
+       {
+          "Blob":{"hex":"deadbeef"}
+       },
+       {
+          "OptionalBlob":{"base64":"3q2+7w=="}
+       }
+    ]
+ }"#;
+
+#[test]
+fn match_doc_against_blob_conds() {
+    for (cond_str, expected) in [
+        (
+            r#"{"PROP": {"index": 0, "condition": {"EQ": {"Blob": {"base64": "3q2+7w=="}}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 1, "condition": {"EQ": {"Blob": {"hex": "DEADBEEF"}}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"IN": {"BlobArray": [{"hex": "00"}, {"hex": "deadbeef"}]}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"GT": {"Blob": {"hex": "deadbf"}}}}}"#,
+            false,
+        ),
+        (
+            r#"{"PROP": {"index": 1, "condition": {"PREFIX": {"Blob": {"hex": "dead"}}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 1, "condition": {"PREFIX": {"Blob": {"hex": "beef"}}}}}"#,
+            false,
+        ),
+    ] {
+        let result = match_conditions_with(cond_str, DOC_WITH_BLOBS_JSON);
+
+        assert_eq!(
+            result.unwrap(),
+            expected,
+            "Unexpected result for {}",
+            cond_str
+        );
+    }
+}
+
+const DOC_WITH_AMOUNTS_JSON: &str = r#"{
+    "fields":[
+       {
+          "Decimal":"0.30"
+       },
+       {
+          "BigInt":"340282366920938463463374607431768211456"
+       }
+    ]
+ }"#;
+
+#[test]
+fn match_doc_against_exact_number_conds() {
+    for (cond_str, expected) in [
+        (
+            r#"{"PROP": {"index": 0, "condition": {"EQ": {"Decimal": "0.3"}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"EQ": {"Float": 0.3}}}}"#,
+            false,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"GT": {"Float": 0.3}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 1, "condition": {"GT": {"Int": 9223372036854775807}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 1, "condition": {"IN": {"BigIntArray": ["1", "340282366920938463463374607431768211456"]}}}}"#,
+            true,
+        ),
+        (
+            r#"{"PROP": {"index": 0, "condition": {"BETWEEN": {"start": {"Decimal": "0.3"}, "end": {"Int": 1}, "startInclusive": false}}}}"#,
+            false,
+        ),
+    ] {
+        let result = match_conditions_with(cond_str, DOC_WITH_AMOUNTS_JSON);
+
+        assert_eq!(
+            result.unwrap(),
+            expected,
+            "Unexpected result for {}",
+            cond_str
+        );
+    }
+}
+
+#[test]
+fn filter_docs_through_abi() {
+    let docs = (0..20)
+        .map(|i| format!(r#"{{"fields":[{{"Int":{}}}]}}"#, i))
+        .collect::<Vec<_>>()
+        .join(",");
+    let docs_json = format!("[{}]", docs);
+    let cond_json = r#"{"PROP":{"index":0,"condition":{"GE":{"Int":15}}}}"#;
+
+    let result = filter_docs_with(cond_json, &docs_json, 4, 0);
+    assert_eq!(result.unwrap(), vec![15, 16, 17, 18, 19]);
+    let result = filter_docs_with(cond_json, &docs_json, 0, 2);
+    assert_eq!(result.unwrap(), vec![15, 16]);
+
+    let mut out = util::StringBuffer::new();
+    let mut indexes = [0u64; 3];
+    let mut count = 0;
+    let filter =
+        |docs: &str, indexes: &mut [u64], count: &mut u64, out: &mut util::StringBuffer| unsafe {
+            filter_docs(
+                cond_json.as_ptr().cast(),
+                cond_json.len(),
+                docs.as_ptr().cast(),
+                docs.len(),
+                2,
+                0,
+                indexes.as_mut_ptr(),
+                indexes.len(),
+                count,
+                out,
+            )
+        };
+    let code = filter(&docs_json, &mut indexes, &mut count, &mut out);
+    assert_no_error(out.data());
+    assert_eq!(code, util::ErrorCode::Ok);
+    assert_eq!(count, 5);
+    assert_eq!(indexes, [15, 16, 17]);
+
+    let code = filter(r#"{"fields":[]}"#, &mut indexes, &mut count, &mut out);
+    assert_eq!(code, util::ErrorCode::InvalidInput);
+    assert_eq!(count, 5);
+}
+
+// Matches semantic versions at least the target, counting its calls in the user data.
+extern "C" fn semver_ge(
+    target_json: *const c_char,
+    value_json: *const c_char,
+    user_data: *mut libc::c_void,
+) -> i32 {
+    let calls = unsafe { &*(user_data as *const std::sync::atomic::AtomicUsize) };
+    calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let version = |json: *const c_char| -> Option<Vec<u64>> {
+        let json = unsafe { std::ffi::CStr::from_ptr(json) }.to_str().ok()?;
+        let value: serde_json::Value = serde_json::from_str(json).ok()?;
+        value
+            .get("String")?
+            .as_str()?
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect()
+    };
+    match (version(target_json), version(value_json)) {
+        (Some(target), Some(value)) => (value >= target) as i32,
+        (None, _) => -1,
+        _ => 0,
+    }
+}
+
+#[test]
+fn match_doc_against_callback_operator() {
+    static CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let mut out = util::StringBuffer::new();
+    let name = "SEMVER_GE";
+    let user_data = &CALLS as *const _ as *mut libc::c_void;
+    let register = |out: &mut util::StringBuffer| unsafe {
+        register_operator(
+            name.as_ptr().cast(),
+            name.len(),
+            Some(semver_ge),
+            user_data,
+            out,
+        )
+    };
+
+    assert_eq!(register(&mut out), util::ErrorCode::Ok);
+    assert_no_error(out.data());
+    assert_eq!(register(&mut out), util::ErrorCode::InvalidInput);
+
+    let cond = r#"{"PROP":{"index":0,"condition":{"SEMVER_GE":{"String":"1.2.0"}}}}"#;
+    let doc = |version: &str| format!(r#"{{"fields":[{{"String":"{}"}}]}}"#, version);
+    assert_eq!(match_conditions_with(cond, &doc("1.10.0")), Ok(true));
+    assert_eq!(match_conditions_with(cond, &doc("1.1.9")), Ok(false));
+    assert_eq!(CALLS.load(std::sync::atomic::Ordering::Relaxed), 2);
+
+    let invalid = r#"{"SEMVER_GE":{"String":"x"}}"#;
+    let result = match_conditions_with(invalid, r#"{"String":"1.0.0"}"#);
+    assert_eq!(result.unwrap_err().code, util::ErrorCode::MatchFailed);
+
+    let unregister = || unsafe { unregister_operator(name.as_ptr().cast(), name.len()) };
+    assert_eq!(unregister(), util::ErrorCode::Ok);
+    assert_eq!(unregister(), util::ErrorCode::NotFound);
+    assert!(match_conditions_with(cond, &doc("1.10.0")).is_err());
+}
+
+#[test]
+fn register_operator_with_reserved_name() {
+    let always = |_: &core::doc::Field, _: &core::doc::Field| Ok(true);
+    let result = register_operator_with("EQ", std::sync::Arc::new(always));
+    assert!(result.is_err());
+}
+
+#[test]
+fn parse_every_builtin_key() {
+    // each reserved key reaches its own parser arm, whether or not it accepts the operand
+    for key in BUILTIN_KEYS {
+        if let Err(err) = deserialize_condition(&format!(r#"{{"{}":null}}"#, key)) {
+            assert!(!err.contains("Invalid key"), "{}: {}", key, err);
+            assert!(!err.contains("Unsupported key"), "{}: {}", key, err);
+        }
+    }
+}
+
+fn nested_props_json(depth: usize) -> String {
+    let mut cond = r#"{"EQ":{"Int":5}}"#.to_string();
+    for _ in 1..depth {
+        cond = format!(r#"{{"PROP":{{"index":0,"condition":{}}}}}"#, cond);
+    }
+    cond
+}
+
+#[test]
+fn reject_conditions_exceeding_limits() {
+    let result = deserialize_condition(&nested_props_json(40));
+    assert_eq!(
+        result.unwrap_err(),
+        "Condition depth exceeds the limit of 32"
+    );
+    // nesting beyond the recursion limit of the JSON parser fails before parsing the condition
+    assert!(deserialize_condition(&nested_props_json(10_000)).is_err());
+
+    let limits = limits::Limits::default()
+        .with_max_array_len(3)
+        .with_max_string_len(4);
+    let in_list = r#"{"PROP":{"index":0,"condition":{"IN":{"IntArray":[1,2,3,4]}}}}"#;
+    assert_eq!(
+        deserialize_condition_with_limits(in_list, &limits).unwrap_err(),
+        "Array length exceeds the limit of 3"
+    );
+    let like = r#"{"PROP":{"index":0,"condition":{"LIKE":{"String":"%%%%%"}}}}"#;
+    assert_eq!(
+        deserialize_condition_with_limits(like, &limits).unwrap_err(),
+        "String length exceeds the limit of 4"
+    );
+    assert!(deserialize_condition_with_limits(&nested_props_json(3), &limits).is_ok());
+}
+
+#[test]
+fn reject_docs_exceeding_limits() {
+    let cond = r#"{"PROP":{"index":0,"condition":{"EQ":{"Int":5}}}}"#;
+    let json = format!("{}null{}", "[".repeat(20), "]".repeat(20));
+    let deep_doc = format!(r#"{{"fields":[{{"Json":{}}}]}}"#, json);
+    assert_eq!(
+        match_conditions_with(cond, &deep_doc).unwrap_err(),
+        util::Error::new(
+            util::ErrorCode::InvalidInput,
+            "Document depth exceeds the limit of 16"
+        )
+    );
+    assert_eq!(
+        filter_docs_with(cond, &format!("[{}]", deep_doc), 1, 0)
+            .unwrap_err()
+            .to_string(),
+        "Document depth exceeds the limit of 16"
+    );
+
+    let limits = limits::Limits::default().with_max_string_len(8);
+    let long = r#"{"fields":[{"String":"aaaaaaaaa"}]}"#;
+    assert_eq!(
+        deserialize_doc_field_with_limits(long, &limits).unwrap_err(),
+        "String length exceeds the limit of 8"
+    );
+}
+
+const INVALID_UTF8: &[u8] = b"{\"EQ\":{\"String\":\"\xff\"}}";
+
+fn buffer_str(out: &util::StringBuffer) -> String {
+    unsafe { std::ffi::CStr::from_ptr(out.data()) }
+        .to_string_lossy()
+        .to_string()
+}
+
+#[test]
+fn match_conditions_rejects_invalid_pointers_and_utf8() {
+    let cond = SIMPLE_EQ_COND_JSON;
+    let doc = AUTHOR_DOC_WITH_PROPS_JSON;
+    let mut out = util::StringBuffer::new();
+    let mut result = true;
+    let null = std::ptr::null();
+    unsafe {
+        let code = match_conditions(
+            null,
+            4,
+            doc.as_ptr().cast(),
+            doc.len(),
+            &mut result,
+            &mut out,
+        );
+        assert_eq!(code, util::ErrorCode::NullPointer);
+        assert_eq!(buffer_str(&out), "condition is null");
+
+        let code = match_conditions(
+            cond.as_ptr().cast(),
+            cond.len(),
+            null,
+            4,
+            &mut result,
+            &mut out,
+        );
+        assert_eq!(code, util::ErrorCode::NullPointer);
+
+        let code = match_conditions(
+            cond.as_ptr().cast(),
+            cond.len(),
+            doc.as_ptr().cast(),
+            doc.len(),
+            std::ptr::null_mut(),
+            &mut out,
+        );
+        assert_eq!(code, util::ErrorCode::NullPointer);
+        assert_eq!(buffer_str(&out), "result is null");
+
+        let code = match_conditions(
+            INVALID_UTF8.as_ptr().cast(),
+            INVALID_UTF8.len(),
+            doc.as_ptr().cast(),
+            doc.len(),
+            &mut result,
+            &mut out,
+        );
+        assert_eq!(code, util::ErrorCode::InvalidUtf8);
+        assert!(buffer_str(&out).starts_with("condition is not valid UTF-8"));
+
+        // a null pointer with a length of 0 is an empty input, which isn't valid JSON
+        let code = match_conditions(
+            null,
+            0,
+            doc.as_ptr().cast(),
+            doc.len(),
+            &mut result,
+            &mut out,
+        );
+        assert_eq!(code, util::ErrorCode::InvalidInput);
+
+        // failures are reported without an error buffer too
+        let code = match_conditions(
+            cond.as_ptr().cast(),
+            cond.len(),
+            null,
+            4,
+            &mut result,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(code, util::ErrorCode::NullPointer);
+    }
+    assert!(result, "outputs are left unchanged on failure");
+}
+
+#[test]
+fn match_conditions_reads_length_delimited_inputs() {
+    // the inputs are slices of a larger buffer without null terminators
+    let buffer = format!("{}{}", SIMPLE_EQ_COND_JSON, r#"{"Int":10}"#);
+    let (cond, field) = buffer.split_at(SIMPLE_EQ_COND_JSON.len());
+    let mut out = util::StringBuffer::new();
+    let mut result = true;
+
+    let code = unsafe {
+        match_conditions(
+            cond.as_ptr().cast(),
+            cond.len(),
+            field.as_ptr().cast(),
+            field.len(),
+            &mut result,
+            &mut out,
+        )
+    };
+
+    assert_no_error(out.data());
+    assert_eq!(code, util::ErrorCode::Ok);
+    assert!(!result);
+}
+
+#[test]
+fn match_conditions_reports_match_failures() {
+    let cond = r#"{"PROP":{"index":9,"condition":{"EQ":{"Int":1}}}}"#;
+    let doc = r#"{"fields":[{"Int":1}]}"#;
+    let mut out = util::StringBuffer::new();
+    let mut result = false;
+
+    let code = unsafe {
+        match_conditions(
+            cond.as_ptr().cast(),
+            cond.len(),
+            doc.as_ptr().cast(),
+            doc.len(),
+            &mut result,
+            &mut out,
+        )
+    };
+
+    assert_eq!(code, util::ErrorCode::MatchFailed);
+    assert_eq!(buffer_str(&out), "Prop index is out of bound");
+}
+
+#[test]
+fn registered_conditions_reject_invalid_pointers_and_utf8() {
+    let mut out = util::StringBuffer::new();
+    let mut handle = 0;
+    unsafe {
+        let code = register_condition(std::ptr::null(), 1, &mut handle, &mut out);
+        assert_eq!(code, util::ErrorCode::NullPointer);
+        let code = register_condition(
+            INVALID_UTF8.as_ptr().cast(),
+            INVALID_UTF8.len(),
+            &mut handle,
+            &mut out,
+        );
+        assert_eq!(code, util::ErrorCode::InvalidUtf8);
+        let cond = SIMPLE_EQ_COND_JSON;
+        let code = register_condition(
+            cond.as_ptr().cast(),
+            cond.len(),
+            std::ptr::null_mut(),
+            &mut out,
+        );
+        assert_eq!(code, util::ErrorCode::NullPointer);
+        assert_eq!(handle, 0);
+
+        let code = register_condition(cond.as_ptr().cast(), cond.len(), &mut handle, &mut out);
+        assert_eq!(code, util::ErrorCode::Ok);
+
+        let mut result = false;
+        let code = match_registered_condition(handle, std::ptr::null(), 1, &mut result, &mut out);
+        assert_eq!(code, util::ErrorCode::NullPointer);
+        let code = match_registered_condition(
+            handle,
+            INVALID_UTF8.as_ptr().cast(),
+            INVALID_UTF8.len(),
+            &mut result,
+            &mut out,
+        );
+        assert_eq!(code, util::ErrorCode::InvalidUtf8);
+        let doc = AUTHOR_DOC_WITH_PROPS_JSON;
+        let code = match_registered_condition(
+            handle,
+            doc.as_ptr().cast(),
+            doc.len(),
+            std::ptr::null_mut(),
+            &mut out,
+        );
+        assert_eq!(code, util::ErrorCode::NullPointer);
+
+        assert_eq!(free_condition(handle), util::ErrorCode::Ok);
+        let code = match_registered_condition(
+            handle,
+            doc.as_ptr().cast(),
+            doc.len(),
+            &mut result,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(code, util::ErrorCode::NotFound);
+    }
+}
+
+fn filter_docs_raw(
+    cond: &[u8],
+    docs: &[u8],
+    indexes: *mut u64,
+    indexes_cap: usize,
+    count: *mut u64,
+    out: &mut util::StringBuffer,
+) -> util::ErrorCode {
+    unsafe {
+        filter_docs(
+            cond.as_ptr().cast(),
+            cond.len(),
+            docs.as_ptr().cast(),
+            docs.len(),
+            1,
+            0,
+            indexes,
+            indexes_cap,
+            count,
+            out,
+        )
+    }
+}
+
+#[test]
+fn filter_docs_rejects_invalid_pointers_and_utf8() {
+    let cond = r#"{"PROP":{"index":0,"condition":{"GE":{"Int":1}}}}"#;
+    let docs = r#"[{"fields":[{"Int":0}]},{"fields":[{"Int":1}]}]"#;
+    let mut out = util::StringBuffer::new();
+    let mut count = 7;
+    let mut indexes = [0u64; 2];
+    let mut filter = |cond: &[u8], docs: &[u8], indexes, cap, count| {
+        filter_docs_raw(cond, docs, indexes, cap, count, &mut out)
+    };
+
+    let code = filter(
+        cond.as_bytes(),
+        INVALID_UTF8,
+        indexes.as_mut_ptr(),
+        2,
+        &mut count,
+    );
+    assert_eq!(code, util::ErrorCode::InvalidUtf8);
+    let code = filter(
+        cond.as_bytes(),
+        docs.as_bytes(),
+        std::ptr::null_mut(),
+        2,
+        &mut count,
+    );
+    assert_eq!(code, util::ErrorCode::NullPointer);
+    let code = filter(
+        cond.as_bytes(),
+        docs.as_bytes(),
+        indexes.as_mut_ptr(),
+        2,
+        std::ptr::null_mut(),
+    );
+    assert_eq!(code, util::ErrorCode::NullPointer);
+    assert_eq!(count, 7);
+
+    // the indexes may be null if the caller only wants the count
+    let code = filter(
+        cond.as_bytes(),
+        docs.as_bytes(),
+        std::ptr::null_mut(),
+        0,
+        &mut count,
+    );
+    assert_eq!(code, util::ErrorCode::Ok);
+    assert_eq!(count, 1);
+
+    let code = unsafe {
+        filter_docs(
+            std::ptr::null(),
+            1,
+            docs.as_ptr().cast(),
+            docs.len(),
+            1,
+            0,
+            indexes.as_mut_ptr(),
+            2,
+            &mut count,
+            std::ptr::null_mut(),
+        )
+    };
+    assert_eq!(code, util::ErrorCode::NullPointer);
+}
+
+#[test]
+fn operators_reject_invalid_pointers_and_utf8() {
+    let mut out = util::StringBuffer::new();
+    let user_data = std::ptr::null_mut();
+    let name = "NULL_CALLBACK";
+    unsafe {
+        let code = register_operator(name.as_ptr().cast(), name.len(), None, user_data, &mut out);
+        assert_eq!(code, util::ErrorCode::NullPointer);
+        assert_eq!(buffer_str(&out), "callback is null");
+
+        let code = register_operator(std::ptr::null(), 3, Some(semver_ge), user_data, &mut out);
+        assert_eq!(code, util::ErrorCode::NullPointer);
+        let code = register_operator(
+            INVALID_UTF8.as_ptr().cast(),
+            INVALID_UTF8.len(),
+            Some(semver_ge),
+            user_data,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(code, util::ErrorCode::InvalidUtf8);
+        let code = register_operator(std::ptr::null(), 0, Some(semver_ge), user_data, &mut out);
+        assert_eq!(code, util::ErrorCode::InvalidInput);
+        let code = register_operator(
+            "OR".as_ptr().cast(),
+            2,
+            Some(semver_ge),
+            user_data,
+            &mut out,
+        );
+        assert_eq!(code, util::ErrorCode::InvalidInput);
+
+        assert_eq!(
+            unregister_operator(std::ptr::null(), 3),
+            util::ErrorCode::NullPointer
+        );
+        assert_eq!(
+            unregister_operator(INVALID_UTF8.as_ptr().cast(), INVALID_UTF8.len()),
+            util::ErrorCode::InvalidUtf8
+        );
+        assert_eq!(
+            unregister_operator(name.as_ptr().cast(), name.len()),
+            util::ErrorCode::NotFound
+        );
+    }
+}
+
+#[test]
+fn error_buffer_without_data_is_left_alone() {
+    let mut out = util::StringBuffer::new_with_cap(0);
+    let mut result = false;
+
+    let code = unsafe {
+        match_conditions(
+            std::ptr::null(),
+            1,
+            std::ptr::null(),
+            0,
+            &mut result,
+            &mut out,
+        )
+    };
+
+    assert_eq!(code, util::ErrorCode::NullPointer);
+    assert_eq!(out.cap(), 0);
+}
@@ -10,10 +10,14 @@
 
 extern crate libc;
 
+use core::json::Json;
 use serde_json;
+use serde_json::json;
 use serde_json::Value;
 use std::convert::TryFrom;
 
+use crate::util;
+
 #[derive(Debug)]
 pub struct FieldWrapper(pub core::doc::Field);
 
@@ -50,10 +54,74 @@ impl TryFrom<Value> for FieldWrapper {
                     }
                 } else if let Some(v) = map.remove("DateTime") {
                     if let Value::String(str_val) = v {
-                        let date_time = chrono::DateTime::parse_from_rfc3339(&str_val)
-                            .map_err(|e| e.to_string())?;
+                        let date_time = parse_date_time(&str_val)?;
                         return Ok(FieldWrapper(core::doc::Field::DateTime(date_time.into())));
                     }
+                } else if let Some(v) = map.remove("ZonedDateTime") {
+                    if let Value::String(str_val) = v {
+                        let date_time = parse_date_time(&str_val)?;
+                        return Ok(FieldWrapper(core::doc::Field::ZonedDateTime(date_time)));
+                    }
+                } else if let Some(v) = map.remove("Date") {
+                    if let Value::String(str_val) = v {
+                        let date = parse_date(&str_val)?;
+                        return Ok(FieldWrapper(core::doc::Field::Date(date)));
+                    }
+                } else if let Some(v) = map.remove("Time") {
+                    if let Value::String(str_val) = v {
+                        let time = parse_time(&str_val)?;
+                        return Ok(FieldWrapper(core::doc::Field::Time(time)));
+                    }
+                } else if let Some(v) = map.remove("GeoPoint") {
+                    let point = deserialize_geo_point(&v)?;
+                    return Ok(FieldWrapper(core::doc::Field::GeoPoint(point)));
+                } else if let Some(v) = map.remove("Vector") {
+                    let vector = deserialize_vector(&v)?;
+                    return Ok(FieldWrapper(core::doc::Field::Vector(vector)));
+                } else if let Some(v) = map.remove("Json") {
+                    return Ok(FieldWrapper(core::doc::Field::Json(json_from_value(&v))));
+                } else if let Some(v) = map.remove("Decimal") {
+                    return Ok(FieldWrapper(core::doc::Field::Decimal(deserialize_number(
+                        &v,
+                    )?)));
+                } else if let Some(v) = map.remove("BigInt") {
+                    return Ok(FieldWrapper(core::doc::Field::BigInt(deserialize_number(
+                        &v,
+                    )?)));
+                } else if let Some(v) = map.remove("DecimalArray") {
+                    if let Value::Array(arr) = v {
+                        let res_vec = arr
+                            .iter()
+                            .map(deserialize_number)
+                            .collect::<Result<Vec<_>, _>>()?;
+                        return Ok(FieldWrapper(core::doc::Field::DecimalArray(res_vec)));
+                    }
+                } else if let Some(v) = map.remove("BigIntArray") {
+                    if let Value::Array(arr) = v {
+                        let res_vec = arr
+                            .iter()
+                            .map(deserialize_number)
+                            .collect::<Result<Vec<_>, _>>()?;
+                        return Ok(FieldWrapper(core::doc::Field::BigIntArray(res_vec)));
+                    }
+                } else if let Some(v) = map.remove("OptionalDecimal") {
+                    if v.is_null() {
+                        return Ok(FieldWrapper(core::doc::Field::OptionalDecimal(None)));
+                    }
+                    let decimal = deserialize_number(&v)?;
+                    return Ok(FieldWrapper(core::doc::Field::OptionalDecimal(Some(
+                        decimal,
+                    ))));
+                } else if let Some(v) = map.remove("OptionalBigInt") {
+                    if v.is_null() {
+                        return Ok(FieldWrapper(core::doc::Field::OptionalBigInt(None)));
+                    }
+                    let big_int = deserialize_number(&v)?;
+                    return Ok(FieldWrapper(core::doc::Field::OptionalBigInt(Some(
+                        big_int,
+                    ))));
+                } else if let Some(v) = map.remove("Blob") {
+                    return Ok(FieldWrapper(core::doc::Field::Blob(deserialize_blob(&v)?)));
                 } else if let Some(doc_val) = map.remove("Doc") {
                     if let Value::Object(_) = doc_val {
                         let doc = DocWrapper::try_from(doc_val)?;
@@ -125,8 +193,7 @@ impl TryFrom<Value> for FieldWrapper {
                         res_vec.reserve(arr.len());
                         for str_val in arr {
                             if let Value::String(str) = str_val {
-                                let date_time = chrono::DateTime::parse_from_rfc3339(&str)
-                                    .map_err(|e| e.to_string())?;
+                                let date_time = parse_date_time(&str)?;
                                 res_vec.push(date_time.into());
                             } else {
                                 return Err("Expected a string in DateTimeArray".to_string());
@@ -134,6 +201,22 @@ impl TryFrom<Value> for FieldWrapper {
                         }
                         return Ok(FieldWrapper(core::doc::Field::DateTimeArray(res_vec)));
                     }
+                } else if let Some(v) = map.remove("BlobArray") {
+                    if let Value::Array(arr) = v {
+                        let res_vec = arr
+                            .iter()
+                            .map(deserialize_blob)
+                            .collect::<Result<Vec<_>, _>>()?;
+                        return Ok(FieldWrapper(core::doc::Field::BlobArray(res_vec)));
+                    }
+                } else if let Some(v) = map.remove("GeoPointArray") {
+                    if let Value::Array(arr) = v {
+                        let res_vec =
+                            arr.iter()
+                                .map(deserialize_geo_point)
+                                .collect::<Result<Vec<_>, String>>()?;
+                        return Ok(FieldWrapper(core::doc::Field::GeoPointArray(res_vec)));
+                    }
                 } else if let Some(v) = map.remove("DocArray") {
                     if let Value::Array(arr) = v {
                         let mut res_vec = Vec::new();
@@ -197,14 +280,64 @@ impl TryFrom<Value> for FieldWrapper {
                     if v.is_null() {
                         return Ok(FieldWrapper(core::doc::Field::OptionalDateTime(None)));
                     } else if let Value::String(str_val) = v {
-                        let date_time = chrono::DateTime::parse_from_rfc3339(&str_val)
-                            .map_err(|e| e.to_string())?;
+                        let date_time = parse_date_time(&str_val)?;
                         return Ok(FieldWrapper(core::doc::Field::OptionalDateTime(Some(
                             date_time.into(),
                         ))));
                     } else {
                         return Err("Expected a string or null in OptionalDateTime".to_string());
                     }
+                } else if let Some(v) = map.remove("OptionalZonedDateTime") {
+                    if v.is_null() {
+                        return Ok(FieldWrapper(core::doc::Field::OptionalZonedDateTime(None)));
+                    } else if let Value::String(str_val) = v {
+                        let date_time = parse_date_time(&str_val)?;
+                        return Ok(FieldWrapper(core::doc::Field::OptionalZonedDateTime(Some(
+                            date_time,
+                        ))));
+                    } else {
+                        return Err(
+                            "Expected a string or null in OptionalZonedDateTime".to_string()
+                        );
+                    }
+                } else if let Some(v) = map.remove("OptionalDate") {
+                    if v.is_null() {
+                        return Ok(FieldWrapper(core::doc::Field::OptionalDate(None)));
+                    } else if let Value::String(str_val) = v {
+                        let date = parse_date(&str_val)?;
+                        return Ok(FieldWrapper(core::doc::Field::OptionalDate(Some(date))));
+                    } else {
+                        return Err("Expected a string or null in OptionalDate".to_string());
+                    }
+                } else if let Some(v) = map.remove("OptionalTime") {
+                    if v.is_null() {
+                        return Ok(FieldWrapper(core::doc::Field::OptionalTime(None)));
+                    } else if let Value::String(str_val) = v {
+                        let time = parse_time(&str_val)?;
+                        return Ok(FieldWrapper(core::doc::Field::OptionalTime(Some(time))));
+                    } else {
+                        return Err("Expected a string or null in OptionalTime".to_string());
+                    }
+                } else if let Some(v) = map.remove("OptionalGeoPoint") {
+                    if v.is_null() {
+                        return Ok(FieldWrapper(core::doc::Field::OptionalGeoPoint(None)));
+                    }
+                    let point = deserialize_geo_point(&v)?;
+                    return Ok(FieldWrapper(core::doc::Field::OptionalGeoPoint(Some(
+                        point,
+                    ))));
+                } else if let Some(v) = map.remove("OptionalBlob") {
+                    if v.is_null() {
+                        return Ok(FieldWrapper(core::doc::Field::OptionalBlob(None)));
+                    }
+                    let blob = deserialize_blob(&v)?;
+                    return Ok(FieldWrapper(core::doc::Field::OptionalBlob(Some(blob))));
+                } else if let Some(v) = map.remove("OptionalVector") {
+                    if v.is_null() {
+                        return Ok(FieldWrapper(core::doc::Field::OptionalVector(None)));
+                    }
+                    let vector = deserialize_vector(&v)?;
+                    return Ok(FieldWrapper(core::doc::Field::OptionalVector(Some(vector))));
                 } else if let Some(v) = map.remove("OptionalBoolArray") {
                     if let Value::Array(arr) = v {
                         let mut res_vec = Vec::new();
@@ -299,8 +432,7 @@ impl TryFrom<Value> for FieldWrapper {
                             match el {
                                 Value::Null => res_vec.push(None),
                                 Value::String(str) => {
-                                    let date_time = chrono::DateTime::parse_from_rfc3339(&str)
-                                        .map_err(|e| e.to_string())?;
+                                    let date_time = parse_date_time(&str)?;
                                     res_vec.push(Some(date_time.into()));
                                 }
                                 _ => {
@@ -315,6 +447,19 @@ impl TryFrom<Value> for FieldWrapper {
                             res_vec,
                         )));
                     }
+                } else if let Some(v) = map.remove("OptionalGeoPointArray") {
+                    if let Value::Array(arr) = v {
+                        let res_vec = arr
+                            .iter()
+                            .map(|el| match el {
+                                Value::Null => Ok(None),
+                                _ => deserialize_geo_point(el).map(Some),
+                            })
+                            .collect::<Result<Vec<_>, String>>()?;
+                        return Ok(FieldWrapper(core::doc::Field::OptionalGeoPointArray(
+                            res_vec,
+                        )));
+                    }
                 }
                 Err("Invalid DocField JSON structure".to_string())
             }
@@ -324,6 +469,153 @@ impl TryFrom<Value> for FieldWrapper {
     }
 }
 
+// Parses an ISO-8601 datetime, keeping its offset. Accepted forms are RFC 3339, a space
+// instead of the `T` separator, offsets without a colon such as `+0200`, datetimes without
+// seconds or without an offset and dates alone. A missing offset is taken to be UTC and a
+// date alone is midnight of that day.
+pub fn parse_date_time(str_val: &str) -> Result<chrono::DateTime<chrono::FixedOffset>, String> {
+    let invalid = || format!("Invalid ISO-8601 datetime: {}", str_val);
+    let normalized = match str_val.as_bytes().get(10) {
+        Some(b' ' | b't') => format!("{}T{}", &str_val[..10], &str_val[11..]),
+        _ => str_val.to_string(),
+    };
+    if let Ok(date_time) = chrono::DateTime::parse_from_rfc3339(&normalized) {
+        return Ok(date_time);
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S%.f%z", "%Y-%m-%dT%H:%M%z"] {
+        if let Ok(date_time) = chrono::DateTime::parse_from_str(&normalized, format) {
+            return Ok(date_time);
+        }
+    }
+    let utc = chrono::FixedOffset::east_opt(0).ok_or_else(invalid)?;
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M"] {
+        if let Ok(date_time) = chrono::NaiveDateTime::parse_from_str(&normalized, format) {
+            return Ok(chrono::DateTime::from_naive_utc_and_offset(date_time, utc));
+        }
+    }
+    let date = parse_date(str_val).map_err(|_| invalid())?;
+    Ok(chrono::DateTime::from_naive_utc_and_offset(
+        date.and_time(chrono::NaiveTime::MIN),
+        utc,
+    ))
+}
+
+// Parses an ISO-8601 calendar date such as `2024-04-08`.
+pub fn parse_date(str_val: &str) -> Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(str_val, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid ISO-8601 date: {}", str_val))
+}
+
+// Parses an ISO-8601 time of day such as `13:45`, `13:45:30` or `13:45:30.250`.
+pub fn parse_time(str_val: &str) -> Result<chrono::NaiveTime, String> {
+    ["%H:%M:%S%.f", "%H:%M"]
+        .iter()
+        .find_map(|format| chrono::NaiveTime::parse_from_str(str_val, format).ok())
+        .ok_or_else(|| format!("Invalid ISO-8601 time: {}", str_val))
+}
+
+// Deserializes a point given as `{"lat": 52.52, "lon": 13.40}` in degrees.
+pub fn deserialize_geo_point(value: &Value) -> Result<core::doc::GeoPoint, String> {
+    let lat = value
+        .get("lat")
+        .and_then(Value::as_f64)
+        .ok_or("Latitude missing or not a number in GeoPoint")?;
+    let lon = value
+        .get("lon")
+        .and_then(Value::as_f64)
+        .ok_or("Longitude missing or not a number in GeoPoint")?;
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err("GeoPoint is out of range".to_string());
+    }
+    Ok(core::doc::GeoPoint { lat, lon })
+}
+
+// Deserializes a vector given either as an array of numbers or as a hex string of its binary
+// encoding, consecutive little-endian f32 values.
+pub fn deserialize_vector(value: &Value) -> Result<Vec<f32>, String> {
+    let vector = match value {
+        Value::Array(arr) => arr
+            .iter()
+            .map(|val| {
+                val.as_f64()
+                    .map(|val| val as f32)
+                    .ok_or("Expected a number in Vector".to_string())
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        Value::String(hex) => util::decode_hex(hex)
+            .and_then(|bytes| core::vector::decode(&bytes))
+            .ok_or("Invalid binary encoding of Vector")?,
+        _ => return Err("Expected an array or a hex string for Vector".to_string()),
+    };
+    if vector.iter().any(|val| !val.is_finite()) {
+        return Err("Vector values must be finite f32 numbers".to_string());
+    }
+    Ok(vector)
+}
+
+// Deserializes a Decimal or a BigInt given as a string, such as `"-12.50"`, so that no
+// precision is lost to JSON numbers.
+pub fn deserialize_number<T>(value: &Value) -> Result<T, String>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value
+        .as_str()
+        .ok_or("Expected a string for number")?
+        .parse()
+        .map_err(|e: T::Err| e.to_string())
+}
+
+// Deserializes bytes given as `{"hex": "00ff"}` or `{"base64": "AP8="}`.
+pub fn deserialize_blob(value: &Value) -> Result<Vec<u8>, String> {
+    if let Some(hex) = value.get("hex") {
+        let hex = hex.as_str().ok_or("Expected a string for hex in Blob")?;
+        return util::decode_hex(hex).ok_or_else(|| "Invalid hex in Blob".to_string());
+    }
+    if let Some(base64) = value.get("base64") {
+        let base64 = base64
+            .as_str()
+            .ok_or("Expected a string for base64 in Blob")?;
+        return util::decode_base64(base64).ok_or_else(|| "Invalid base64 in Blob".to_string());
+    }
+    Err("Expected a JSON object with hex or base64 for Blob".to_string())
+}
+
+fn json_from_value(value: &Value) -> Json {
+    match value {
+        Value::Null => Json::Null,
+        Value::Bool(val) => Json::Bool(*val),
+        Value::Number(num) => match num.as_i64() {
+            Some(val) => Json::Int(val),
+            None => Json::Float(num.as_f64().unwrap_or(f64::NAN)),
+        },
+        Value::String(val) => Json::String(val.clone()),
+        Value::Array(arr) => Json::Array(arr.iter().map(json_from_value).collect()),
+        Value::Object(map) => Json::Object(
+            map.iter()
+                .map(|(key, val)| (key.clone(), json_from_value(val)))
+                .collect(),
+        ),
+    }
+}
+
+fn json_to_value(json: &Json) -> Value {
+    match json {
+        Json::Null => Value::Null,
+        Json::Bool(val) => json!(val),
+        Json::Int(val) => json!(val),
+        Json::Float(val) => json!(val),
+        Json::String(val) => json!(val),
+        Json::Array(arr) => arr.iter().map(json_to_value).collect(),
+        Json::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, val)| (key.clone(), json_to_value(val)))
+                .collect(),
+        ),
+    }
+}
+
 impl TryFrom<Value> for DocWrapper {
     type Error = String;
 
@@ -345,6 +637,24 @@ impl TryFrom<Value> for DocWrapper {
                 } else {
                     return Err("'fields' key not found in Doc JSON".to_string());
                 }
+                if let Some(hidden_val) = map.get("hidden") {
+                    doc.hidden = hidden_val
+                        .as_bool()
+                        .ok_or("Expected a bool for hidden in Doc JSON")?;
+                }
+                if let Some(status_val) = map.get("status") {
+                    doc.status = match status_val.as_str() {
+                        Some("Active") => core::doc::Status::Active,
+                        Some("Deleted") => core::doc::Status::Deleted,
+                        _ => return Err("Expected Active or Deleted for status".to_string()),
+                    };
+                }
+                if let Some(version_val) = map.get("schema_version_id") {
+                    doc.schema_version_id = version_val
+                        .as_str()
+                        .ok_or("Expected a string for schema_version_id in Doc JSON")?
+                        .to_string();
+                }
                 Ok(DocWrapper(doc))
             }
             _ => Err("Expected a JSON object for Doc".to_string()),
@@ -358,8 +668,135 @@ pub fn deserialize_doc(json_str: &str) -> Result<core::doc::Doc, String> {
     Ok(doc.0)
 }
 
+// Deserializes a JSON array of documents.
+pub fn deserialize_docs(json_str: &str) -> Result<Vec<core::doc::Doc>, String> {
+    let v: Value = serde_json::from_str(json_str).map_err(|e| e.to_string())?;
+    let Value::Array(arr) = v else {
+        return Err("Expected a JSON array of documents".to_string());
+    };
+    arr.into_iter()
+        .map(|doc| DocWrapper::try_from(doc).map(|doc| doc.0))
+        .collect()
+}
+
 pub fn deserialize_field(json_str: &str) -> Result<core::doc::Field, String> {
     let v: Value = serde_json::from_str(json_str).map_err(|e| e.to_string())?;
     let field = FieldWrapper::try_from(v).map_err(|e| e.to_string())?;
     Ok(field.0)
 }
+
+pub fn serialize_doc(doc: &core::doc::Doc) -> String {
+    doc_to_value(doc).to_string()
+}
+
+pub fn serialize_field(field: &core::doc::Field) -> String {
+    field_to_value(field).to_string()
+}
+
+fn doc_to_value(doc: &core::doc::Doc) -> Value {
+    let status = match doc.status {
+        core::doc::Status::Active => "Active",
+        core::doc::Status::Deleted => "Deleted",
+    };
+    json!({
+        "fields": doc.fields.iter().map(field_to_value).collect::<Vec<_>>(),
+        "hidden": doc.hidden,
+        "status": status,
+        "schema_version_id": doc.schema_version_id,
+    })
+}
+
+fn field_to_value(field: &core::doc::Field) -> Value {
+    use core::doc::Field;
+
+    fn date_time(val: &chrono::DateTime<chrono::Utc>) -> Value {
+        Value::String(val.to_rfc3339())
+    }
+    fn zoned_date_time(val: &chrono::DateTime<chrono::FixedOffset>) -> Value {
+        Value::String(val.to_rfc3339())
+    }
+    fn date(val: &chrono::NaiveDate) -> Value {
+        Value::String(val.format("%Y-%m-%d").to_string())
+    }
+    fn time(val: &chrono::NaiveTime) -> Value {
+        Value::String(val.format("%H:%M:%S%.f").to_string())
+    }
+    fn number(val: &impl std::fmt::Display) -> Value {
+        Value::String(val.to_string())
+    }
+    fn blob(val: &[u8]) -> Value {
+        json!({ "base64": util::encode_base64(val) })
+    }
+    fn geo_point(val: &core::doc::GeoPoint) -> Value {
+        json!({ "lat": val.lat, "lon": val.lon })
+    }
+    let (key, value) = match field {
+        Field::Null => return Value::Null,
+        Field::Bool(val) => ("Bool", json!(val)),
+        Field::Int(val) => ("Int", json!(val)),
+        Field::Float(val) => ("Float", json!(val)),
+        Field::String(val) => ("String", json!(val)),
+        Field::DateTime(val) => ("DateTime", date_time(val)),
+        Field::ZonedDateTime(val) => ("ZonedDateTime", zoned_date_time(val)),
+        Field::Date(val) => ("Date", date(val)),
+        Field::Time(val) => ("Time", time(val)),
+        Field::GeoPoint(val) => ("GeoPoint", geo_point(val)),
+        Field::Vector(val) => ("Vector", json!(val)),
+        Field::Json(val) => ("Json", json_to_value(val)),
+        Field::Decimal(val) => ("Decimal", number(val)),
+        Field::BigInt(val) => ("BigInt", number(val)),
+        Field::Blob(val) => ("Blob", blob(val)),
+        Field::Doc(doc) => ("Doc", doc_to_value(doc)),
+        Field::BoolArray(arr) => ("BoolArray", json!(arr)),
+        Field::IntArray(arr) => ("IntArray", json!(arr)),
+        Field::FloatArray(arr) => ("FloatArray", json!(arr)),
+        Field::StringArray(arr) => ("StringArray", json!(arr)),
+        Field::DateTimeArray(arr) => ("DateTimeArray", arr.iter().map(date_time).collect()),
+        Field::GeoPointArray(arr) => ("GeoPointArray", arr.iter().map(geo_point).collect()),
+        Field::DecimalArray(arr) => ("DecimalArray", arr.iter().map(number).collect()),
+        Field::BigIntArray(arr) => ("BigIntArray", arr.iter().map(number).collect()),
+        Field::BlobArray(arr) => ("BlobArray", arr.iter().map(|val| blob(val)).collect()),
+        Field::DocArray(arr) => ("DocArray", arr.iter().map(doc_to_value).collect()),
+        Field::OptionalBool(val) => ("OptionalBool", json!(val)),
+        Field::OptionalInt(val) => ("OptionalInt", json!(val)),
+        Field::OptionalFloat(val) => ("OptionalFloat", json!(val)),
+        Field::OptionalString(val) => ("OptionalString", json!(val)),
+        Field::OptionalDateTime(val) => (
+            "OptionalDateTime",
+            val.as_ref().map_or(Value::Null, date_time),
+        ),
+        Field::OptionalZonedDateTime(val) => (
+            "OptionalZonedDateTime",
+            val.as_ref().map_or(Value::Null, zoned_date_time),
+        ),
+        Field::OptionalDate(val) => ("OptionalDate", val.as_ref().map_or(Value::Null, date)),
+        Field::OptionalTime(val) => ("OptionalTime", val.as_ref().map_or(Value::Null, time)),
+        Field::OptionalGeoPoint(val) => (
+            "OptionalGeoPoint",
+            val.as_ref().map_or(Value::Null, geo_point),
+        ),
+        Field::OptionalVector(val) => ("OptionalVector", json!(val)),
+        Field::OptionalDecimal(val) => {
+            ("OptionalDecimal", val.as_ref().map_or(Value::Null, number))
+        }
+        Field::OptionalBigInt(val) => ("OptionalBigInt", val.as_ref().map_or(Value::Null, number)),
+        Field::OptionalBlob(val) => ("OptionalBlob", val.as_deref().map_or(Value::Null, blob)),
+        Field::OptionalBoolArray(arr) => ("OptionalBoolArray", json!(arr)),
+        Field::OptionalIntArray(arr) => ("OptionalIntArray", json!(arr)),
+        Field::OptionalFloatArray(arr) => ("OptionalFloatArray", json!(arr)),
+        Field::OptionalStringArray(arr) => ("OptionalStringArray", json!(arr)),
+        Field::OptionalDateTimeArray(arr) => (
+            "OptionalDateTimeArray",
+            arr.iter()
+                .map(|val| val.as_ref().map_or(Value::Null, date_time))
+                .collect(),
+        ),
+        Field::OptionalGeoPointArray(arr) => (
+            "OptionalGeoPointArray",
+            arr.iter()
+                .map(|val| val.as_ref().map_or(Value::Null, geo_point))
+                .collect(),
+        ),
+    };
+    json!({ key: value })
+}
@@ -108,3 +108,267 @@ fn deserialize_doc_with_null_array_elements() {
         panic!("Expected Float variant");
     }
 }
+
+#[test]
+fn deserialize_doc_metadata() {
+    let json_str = r#"{"fields":[{"Int":1}],"hidden":true,"status":"Deleted","schema_version_id":"bafkreia2"}"#;
+    let doc = deserialize_doc(json_str).unwrap();
+
+    assert!(doc.hidden);
+    assert_eq!(doc.status, core::doc::Status::Deleted);
+    assert_eq!(doc.schema_version_id, "bafkreia2");
+
+    // metadata is optional
+    let doc = deserialize_doc(r#"{"fields":[]}"#).unwrap();
+    assert!(!doc.hidden);
+    assert_eq!(doc.status, core::doc::Status::Active);
+
+    for json_str in [
+        r#"{"fields":[],"hidden":"yes"}"#,
+        r#"{"fields":[],"status":"Purged"}"#,
+        r#"{"fields":[],"schema_version_id":1}"#,
+    ] {
+        assert!(
+            deserialize_doc(json_str).is_err(),
+            "Expected error for {}",
+            json_str
+        );
+    }
+}
+
+#[test]
+fn serialize_doc_round_trip() {
+    let json_str = r#"{"fields":[{"Int":42},null,{"DateTime":"2024-04-07T12:34:56+00:00"},{"Doc":{"fields":[{"OptionalIntArray":[1,null]}],"hidden":true,"schema_version_id":"v1","status":"Deleted"}},{"OptionalString":null},{"DocArray":[{"fields":[{"StringArray":["a"]}],"hidden":false,"schema_version_id":"","status":"Active"}]}],"hidden":false,"schema_version_id":"bafkreia2","status":"Deleted"}"#;
+    let doc = deserialize_doc(json_str).unwrap();
+
+    assert_eq!(serialize_doc(&doc), json_str);
+}
+
+#[test]
+fn serialize_field_round_trip() {
+    for json_str in [
+        r#"{"Float":1.5}"#,
+        r#"{"OptionalDateTimeArray":["2024-04-07T12:34:56+00:00",null]}"#,
+        r#"{"BoolArray":[true,false]}"#,
+        r#"null"#,
+    ] {
+        let field = deserialize_field(json_str).unwrap();
+
+        assert_eq!(serialize_field(&field), json_str);
+    }
+}
+
+#[test]
+fn geo_point_round_trip() {
+    for json_str in [
+        r#"{"GeoPoint":{"lat":52.52,"lon":13.405}}"#,
+        r#"{"GeoPointArray":[{"lat":0.0,"lon":-180.0}]}"#,
+        r#"{"OptionalGeoPoint":null}"#,
+        r#"{"OptionalGeoPointArray":[null,{"lat":-90.0,"lon":0.5}]}"#,
+    ] {
+        let field = deserialize_field(json_str).unwrap();
+
+        assert_eq!(serialize_field(&field), json_str);
+    }
+
+    for json_str in [
+        r#"{"GeoPoint":{"lat":95,"lon":0}}"#,
+        r#"{"GeoPoint":{"lat":0}}"#,
+        r#"{"GeoPointArray":[null]}"#,
+    ] {
+        assert!(
+            deserialize_field(json_str).is_err(),
+            "Expected error for {}",
+            json_str
+        );
+    }
+}
+
+#[test]
+fn vector_round_trip() {
+    for json_str in [
+        r#"{"Vector":[0.5,-2.0,0.0]}"#,
+        r#"{"OptionalVector":null}"#,
+        r#"{"OptionalVector":[1.0]}"#,
+    ] {
+        let field = deserialize_field(json_str).unwrap();
+
+        assert_eq!(serialize_field(&field), json_str);
+    }
+
+    // the binary encoding is accepted as hex and serialized as numbers
+    let field = deserialize_field(r#"{"Vector":"0000803f000000c0"}"#).unwrap();
+    assert_eq!(serialize_field(&field), r#"{"Vector":[1.0,-2.0]}"#);
+
+    for json_str in [
+        r#"{"Vector":[1, "2"]}"#,
+        r#"{"Vector":[1e39]}"#,
+        r#"{"Vector":"0000803"}"#,
+        r#"{"Vector":"0000803f00"}"#,
+        r#"{"Vector":"+000803f"}"#,
+        r#"{"Vector":{"values":[1]}}"#,
+    ] {
+        assert!(
+            deserialize_field(json_str).is_err(),
+            "Expected error for {}",
+            json_str
+        );
+    }
+}
+
+#[test]
+fn json_round_trip() {
+    for json_str in [
+        r#"{"Json":null}"#,
+        r#"{"Json":"text"}"#,
+        r#"{"Json":[1,2.5,true,null,{"a":[]}]}"#,
+        r#"{"Json":{"a":{"b":-3},"z":"last"}}"#,
+    ] {
+        let field = deserialize_field(json_str).unwrap();
+
+        assert_eq!(serialize_field(&field), json_str);
+    }
+}
+
+#[test]
+fn blob_round_trip() {
+    for json_str in [
+        r#"{"Blob":{"base64":""}}"#,
+        r#"{"Blob":{"base64":"AP8="}}"#,
+        r#"{"BlobArray":[{"base64":"AQID"},{"base64":"AQ=="}]}"#,
+        r#"{"OptionalBlob":null}"#,
+        r#"{"OptionalBlob":{"base64":"/+8A3w=="}}"#,
+    ] {
+        let field = deserialize_field(json_str).unwrap();
+
+        assert_eq!(serialize_field(&field), json_str);
+    }
+
+    // hex is accepted and serialized as base64
+    let field = deserialize_field(r#"{"Blob":{"hex":"00FF10"}}"#).unwrap();
+    assert_eq!(serialize_field(&field), r#"{"Blob":{"base64":"AP8Q"}}"#);
+
+    for json_str in [
+        r#"{"Blob":"00ff"}"#,
+        r#"{"Blob":{"hex":"0g"}}"#,
+        r#"{"Blob":{"hex":"123"}}"#,
+        r#"{"Blob":{"base64":"AP8"}}"#,
+        r#"{"Blob":{"base64":"A=P8"}}"#,
+        r#"{"Blob":{"base64":"AP9="}}"#,
+        r#"{"Blob":{"base64":"A==="}}"#,
+        r#"{"Blob":{"base64":"AP8=AP8="}}"#,
+        r#"{"Blob":{"base64":"AP-_"}}"#,
+        r#"{"BlobArray":[null]}"#,
+    ] {
+        assert!(
+            deserialize_field(json_str).is_err(),
+            "Expected error for {}",
+            json_str
+        );
+    }
+}
+
+#[test]
+fn exact_number_round_trip() {
+    for json_str in [
+        r#"{"Decimal":"-1234.5600"}"#,
+        r#"{"BigInt":"-340282366920938463463374607431768211456"}"#,
+        r#"{"DecimalArray":["0.1","2"]}"#,
+        r#"{"BigIntArray":[]}"#,
+        r#"{"OptionalDecimal":null}"#,
+        r#"{"OptionalBigInt":"7"}"#,
+    ] {
+        let field = deserialize_field(json_str).unwrap();
+
+        assert_eq!(serialize_field(&field), json_str);
+    }
+
+    for json_str in [
+        r#"{"Decimal":1.5}"#,
+        r#"{"Decimal":"1.5.0"}"#,
+        r#"{"BigInt":"1.5"}"#,
+        r#"{"BigIntArray":[1]}"#,
+        r#"{"OptionalDecimal":"x"}"#,
+    ] {
+        assert!(
+            deserialize_field(json_str).is_err(),
+            "Expected error for {}",
+            json_str
+        );
+    }
+}
+
+#[test]
+fn temporal_round_trip() {
+    for json_str in [
+        r#"{"DateTime":"2024-04-08T12:34:56.250+00:00"}"#,
+        r#"{"ZonedDateTime":"2024-04-08T14:34:56+02:00"}"#,
+        r#"{"ZonedDateTime":"2024-04-08T07:04:56.500-05:30"}"#,
+        r#"{"Date":"2024-04-08"}"#,
+        r#"{"Time":"12:34:56.250"}"#,
+        r#"{"OptionalZonedDateTime":null}"#,
+        r#"{"OptionalDate":"1999-12-31"}"#,
+        r#"{"OptionalTime":null}"#,
+    ] {
+        let field = deserialize_field(json_str).unwrap();
+
+        assert_eq!(serialize_field(&field), json_str);
+    }
+}
+
+#[test]
+fn parse_iso_8601_forms() {
+    let cases = [
+        ("2024-04-08T12:34:56Z", "2024-04-08T12:34:56+00:00"),
+        (
+            "2024-04-08T12:34:56.123456789+02:00",
+            "2024-04-08T12:34:56.123456789+02:00",
+        ),
+        ("2024-04-08 12:34:56+02:00", "2024-04-08T12:34:56+02:00"),
+        ("2024-04-08T12:34:56+0200", "2024-04-08T12:34:56+02:00"),
+        ("2024-04-08T12:34+02:00", "2024-04-08T12:34:00+02:00"),
+        ("2024-04-08T12:34:56.5", "2024-04-08T12:34:56.500+00:00"),
+        ("2024-04-08T12:34", "2024-04-08T12:34:00+00:00"),
+        ("2024-04-08", "2024-04-08T00:00:00+00:00"),
+    ];
+    for (input, expected) in cases {
+        let date_time = parse_date_time(input).unwrap();
+
+        assert_eq!(date_time.to_rfc3339(), expected, "parsing {}", input);
+    }
+
+    for input in [
+        "",
+        "2024-04-08T",
+        "2024-13-01",
+        "2024-04-08T25:00",
+        "12:34",
+        "08.04.2024",
+    ] {
+        assert!(
+            parse_date_time(input).is_err(),
+            "Expected error for {}",
+            input
+        );
+    }
+    assert!(parse_date("2024-02-30").is_err());
+    assert!(parse_time("12:34:56").is_ok());
+    assert!(parse_time("24:00").is_err());
+}
+
+#[test]
+fn date_time_converts_to_utc_and_zoned_keeps_offset() {
+    let json_str = r#"{"DateTime":"2024-04-08T14:34:56+02:00"}"#;
+    let field = deserialize_field(json_str).unwrap();
+    assert_eq!(
+        serialize_field(&field),
+        r#"{"DateTime":"2024-04-08T12:34:56+00:00"}"#
+    );
+
+    let json_str = r#"{"ZonedDateTime":"2024-04-08T12:34:56"}"#;
+    let field = deserialize_field(json_str).unwrap();
+    assert_eq!(
+        serialize_field(&field),
+        r#"{"ZonedDateTime":"2024-04-08T12:34:56+00:00"}"#
+    );
+}
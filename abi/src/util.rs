@@ -9,13 +9,170 @@
 // licenses/APL.txt.
 
 use libc::c_char;
-use std::ffi::CStr;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
 
-// const_char_to_str converts a const char pointer to a string slice.
-pub unsafe fn const_char_to_str<'a>(json_str: *const c_char) -> &'a str {
-    let c_str: &CStr = CStr::from_ptr(json_str);
-    let buf: &[u8] = c_str.to_bytes();
-    std::str::from_utf8_unchecked(buf)
+// ErrorCode is returned by every export to tell the caller whether the call succeeded and, if
+// not, why it failed.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ErrorCode {
+    Ok = 0,
+    // A required pointer was null.
+    NullPointer = 1,
+    // An input was not valid UTF-8.
+    InvalidUtf8 = 2,
+    // An input could not be parsed, exceeded the limits or named a reserved operator.
+    InvalidInput = 3,
+    // Matching failed, e.g. on a division by zero or a failing custom operator.
+    MatchFailed = 4,
+    // No condition or operator is registered under the handle or name.
+    NotFound = 5,
+    // The call panicked.
+    Panicked = 6,
+}
+
+// Error is the code and message of a failed call.
+#[derive(Debug, PartialEq)]
+pub struct Error {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new(code: ErrorCode, message: impl ToString) -> Self {
+        Error {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+// Parsers report their errors as strings, so they are invalid inputs.
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::new(ErrorCode::InvalidInput, message)
+    }
+}
+
+// Reads the `len` bytes at `ptr` as a UTF-8 string. The pointer may be null only if the length
+// is 0. The bytes need not be null-terminated.
+pub unsafe fn read_str<'a>(ptr: *const c_char, len: usize, name: &str) -> Result<&'a str, Error> {
+    if ptr.is_null() {
+        if len == 0 {
+            return Ok("");
+        }
+        return Err(Error::new(
+            ErrorCode::NullPointer,
+            format!("{} is null", name),
+        ));
+    }
+    if len > isize::MAX as usize {
+        return Err(Error::new(
+            ErrorCode::InvalidInput,
+            format!("{} is too long", name),
+        ));
+    }
+    let bytes = std::slice::from_raw_parts(ptr as *const u8, len);
+    std::str::from_utf8(bytes).map_err(|e| {
+        Error::new(
+            ErrorCode::InvalidUtf8,
+            format!("{} is not valid UTF-8: {}", name, e),
+        )
+    })
+}
+
+// Returns the value an output pointer points to, or an error if it is null.
+pub unsafe fn out_ref<'a, T>(ptr: *mut T, name: &str) -> Result<&'a mut T, Error> {
+    ptr.as_mut()
+        .ok_or_else(|| Error::new(ErrorCode::NullPointer, format!("{} is null", name)))
+}
+
+// Runs the body of an export. A failure, including a panic, is written to the error buffer
+// unless it is null, and its code is returned.
+pub unsafe fn run<F>(error: *mut StringBuffer, body: F) -> ErrorCode
+where
+    F: FnOnce() -> Result<(), Error>,
+{
+    let result = panic::catch_unwind(AssertUnwindSafe(body))
+        .unwrap_or_else(|_| Err(Error::new(ErrorCode::Panicked, "Call panicked")));
+    match result {
+        Ok(()) => ErrorCode::Ok,
+        Err(err) => {
+            if let Some(error) = error.as_mut() {
+                error.fill(&err.message);
+            }
+            err.code
+        }
+    }
+}
+
+// Decodes a string of hex digit pairs, or returns `None` if it is not valid hex.
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Encodes the bytes as padded standard base64.
+pub fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (i, b)| group | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (group >> (18 - 6 * i)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[index as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+// Decodes padded standard base64, or returns `None` if it is not valid base64.
+pub fn decode_base64(base64: &str) -> Option<Vec<u8>> {
+    let bytes = base64.as_bytes();
+    if !bytes.len().is_multiple_of(4) {
+        return None;
+    }
+    let mut decoded = Vec::with_capacity(bytes.len() / 4 * 3);
+    for (n, chunk) in bytes.chunks(4).enumerate() {
+        let is_last = n == bytes.len() / 4 - 1;
+        let padding = chunk.iter().rev().take_while(|b| **b == b'=').count();
+        if padding > 2 || (padding > 0 && !is_last) {
+            return None;
+        }
+        let mut group = 0u32;
+        for (i, b) in chunk[..4 - padding].iter().enumerate() {
+            let index = BASE64_ALPHABET.iter().position(|a| a == b)? as u32;
+            group |= index << (18 - 6 * i);
+        }
+        let len = 3 - padding;
+        // the bits left over by the padding must be zero
+        if group & ((1 << (8 * (3 - len))) - 1) != 0 {
+            return None;
+        }
+        decoded.extend((0..len).map(|i| (group >> (16 - 8 * i)) as u8));
+    }
+    Some(decoded)
 }
 
 // StringBuffer is a simple struct that stores a string buffer and its capacity.
@@ -30,7 +187,11 @@ impl StringBuffer {
 
     // Fills the buffer with the given input string.
     // If the input string is longer than the buffer, the buffer is truncated.
+    // A buffer without data is left as is.
     pub unsafe fn fill(&mut self, input: &str) {
+        if self.data.is_null() || self.cap == 0 {
+            return;
+        }
         let input_bytes = input.as_bytes();
         let length_to_copy = std::cmp::min(self.cap - 1, input_bytes.len());
         std::ptr::copy_nonoverlapping(input_bytes.as_ptr(), self.data as *mut u8, length_to_copy);
@@ -8,21 +8,60 @@
 // by the Apache License, Version 2.0, included in the file
 // licenses/APL.txt.
 
-use core::doc::Field;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use core::doc::{Doc, Field, Status};
 
 use crate::error;
+use crate::expr::{self, Expr};
+use crate::field_ord;
+use crate::fuzzy::Fuzzy;
+use crate::geo::{self, Shape};
+use crate::json::{self, JsonPath};
+use crate::time::{self, Clock, RelativeTime, SystemClock};
+use crate::vector::Threshold;
 
+use crate::op_array;
 use crate::op_eq;
 use crate::op_ge;
 use crate::op_gt;
 use crate::op_ilike;
 use crate::op_in;
 use crate::op_like;
+use crate::op_prefix;
+use crate::operator;
+use crate::text;
 
 #[derive(Clone, Debug)]
 pub enum Condition {
     Prop(usize, Box<Condition>),
+    // Applies the condition to a metadata value of the document.
+    Meta(Meta, Box<Condition>),
+    // Applies the condition to the value at the path of a JSON field.
+    JsonPath(JsonPath, Box<Condition>),
+    // Matches JSON fields that have a value at the path, which may be null.
+    JsonExists(JsonPath),
     Op(Op, Field),
+    FieldOp(Op, FieldRef),
+    // Compares against the value of a named param bound before matching.
+    ParamOp(Op, String),
+    // Compares the left expression against the right one.
+    ExprOp(Op, Expr, Expr),
+    // Matches values within the range.
+    Between(Range),
+    // Compares against a time relative to the clock the condition is matched with.
+    TimeOp(Op, RelativeTime),
+    // Matches arrays against the elements or the length given by the target.
+    ArrayOp(ArrayOp, Field),
+    // Matches strings similar to the given one.
+    Fuzzy(Fuzzy),
+    // Matches geo points within the shape.
+    Within(Shape),
+    // Matches vectors similar to the given one.
+    Similar(Threshold),
+    // Compares against the target with the operator registered under the name.
+    Custom(String, Field),
     CompoundOp(CompoundOp, Vec<Condition>),
 }
 
@@ -40,6 +79,60 @@ pub enum Op {
     NLIKE,
     ILIKE,
     NILIKE,
+    // Full-text search of String and StringArray fields, see `text::Query`.
+    MATCH,
+    // Matches strings and blobs starting with the given value.
+    PREFIX,
+}
+
+// Meta is a metadata value of a document that conditions can be applied to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Meta {
+    // The status as a string, either "Active" or "Deleted".
+    Status,
+    Hidden,
+    SchemaVersionId,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[allow(non_camel_case_types)]
+pub enum ArrayOp {
+    // The array holds every element of the target array.
+    CONTAINS,
+    // The array holds at least one element of the target array.
+    OVERLAPS,
+    // Every element of the array is held by the target array.
+    SUBSET_OF,
+    LEN_EQ,
+    LEN_NE,
+    LEN_GT,
+    LEN_GE,
+    LEN_LT,
+    LEN_LE,
+}
+
+// FieldRef points to another field of the document a condition is matched against.
+// `parent` is the number of levels to go up before following `path`, so that 0 refers to
+// a sibling field and 1 to a field of the parent document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldRef {
+    pub parent: usize,
+    pub path: Vec<usize>,
+}
+
+// Range bounds the numeric, temporal, String or Blob values matched by `Between`.
+#[derive(Clone, Debug)]
+pub struct Range {
+    pub start: Target,
+    pub end: Target,
+    pub start_inclusive: bool,
+    pub end_inclusive: bool,
+}
+
+#[derive(Clone, Debug)]
+pub enum Target {
+    Value(Field),
+    Time(RelativeTime),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -49,11 +142,58 @@ pub enum CompoundOp {
     OR,
 }
 
+// Matches the condition resolving its relative times against the system clock once, so they
+// all refer to the same instant.
 pub fn match_conditions(condition: &Condition, doc_field: &Field) -> error::Result<bool> {
+    match_conditions_at(condition, doc_field, &SystemClock)
+}
+
+// Matches a condition whose relative times have already been resolved with `time::resolve`.
+pub(crate) fn match_resolved(condition: &Condition, doc_field: &Field) -> error::Result<bool> {
+    match_in_scope(condition, doc_field, None, None)
+}
+
+// Matches the condition comparing each `ParamOp` against the value bound to its param, without
+// substituting the values into a copy of the condition.
+pub(crate) fn match_with_params(
+    condition: &Condition,
+    doc_field: &Field,
+    params: &HashMap<String, Field>,
+) -> error::Result<bool> {
+    if time::is_relative(condition) {
+        let condition = time::resolve(condition, &SystemClock)?;
+        match_in_scope(&condition, doc_field, None, Some(params))
+    } else {
+        match_in_scope(condition, doc_field, None, Some(params))
+    }
+}
+
+// Matches the condition resolving relative times against the given clock instead of the
+// system one.
+pub fn match_conditions_at(
+    condition: &Condition,
+    doc_field: &Field,
+    clock: &dyn Clock,
+) -> error::Result<bool> {
+    if time::is_relative(condition) {
+        match_in_scope(&time::resolve(condition, clock)?, doc_field, None, None)
+    } else {
+        match_in_scope(condition, doc_field, None, None)
+    }
+}
+
+// Matches the condition against the field. Relative times must have been resolved by the
+// caller, so that matching never reads the clock.
+fn match_in_scope(
+    condition: &Condition,
+    doc_field: &Field,
+    scope: Option<&Scope>,
+    params: Option<&HashMap<String, Field>>,
+) -> error::Result<bool> {
     match doc_field {
         Field::DocArray(arr) => {
             for doc in arr {
-                match match_conditions(condition, &Field::Doc(doc.clone())) {
+                match match_in_scope(condition, &Field::Doc(doc.clone()), scope, params) {
                     Ok(true) => return Result::Ok(true),
                     Ok(false) => continue,
                     Err(e) => return Result::Err(e),
@@ -63,31 +203,104 @@ pub fn match_conditions(condition: &Condition, doc_field: &Field) -> error::Resu
         }
         Field::OptionalInt(opt_int) => {
             if let Some(int_val) = opt_int {
-                return match_conditions(condition, &Field::Int(*int_val));
+                return match_in_scope(condition, &Field::Int(*int_val), scope, params);
             }
             return Result::Ok(false);
         }
         Field::OptionalFloat(opt_float) => {
             if let Some(float_val) = opt_float {
-                return match_conditions(condition, &Field::Float(*float_val));
+                return match_in_scope(condition, &Field::Float(*float_val), scope, params);
             }
             return Result::Ok(false);
         }
         Field::OptionalString(opt_str) => {
             if let Some(str_val) = opt_str {
-                return match_conditions(condition, &Field::String(str_val.clone()));
+                return match_in_scope(condition, &Field::String(str_val.clone()), scope, params);
             }
             return Result::Ok(false);
         }
         Field::OptionalBool(opt_bool) => {
             if let Some(bool_val) = opt_bool {
-                return match_conditions(condition, &Field::Bool(*bool_val));
+                return match_in_scope(condition, &Field::Bool(*bool_val), scope, params);
             }
             return Result::Ok(false);
         }
         Field::OptionalDateTime(opt_date_time) => {
             if let Some(date_time_val) = opt_date_time {
-                return match_conditions(condition, &Field::DateTime(date_time_val.clone()));
+                return match_in_scope(
+                    condition,
+                    &Field::DateTime(date_time_val.clone()),
+                    scope,
+                    params,
+                );
+            }
+            return Result::Ok(false);
+        }
+        Field::OptionalZonedDateTime(opt_date_time) => {
+            if let Some(date_time_val) = opt_date_time {
+                return match_in_scope(
+                    condition,
+                    &Field::ZonedDateTime(*date_time_val),
+                    scope,
+                    params,
+                );
+            }
+            return Result::Ok(false);
+        }
+        Field::OptionalDate(opt_date) => {
+            if let Some(date_val) = opt_date {
+                return match_in_scope(condition, &Field::Date(*date_val), scope, params);
+            }
+            return Result::Ok(false);
+        }
+        Field::OptionalTime(opt_time) => {
+            if let Some(time_val) = opt_time {
+                return match_in_scope(condition, &Field::Time(*time_val), scope, params);
+            }
+            return Result::Ok(false);
+        }
+        Field::OptionalGeoPoint(opt_point) => {
+            if let Some(point_val) = opt_point {
+                return match_in_scope(condition, &Field::GeoPoint(*point_val), scope, params);
+            }
+            return Result::Ok(false);
+        }
+        Field::OptionalDecimal(opt_decimal) => {
+            if let Some(decimal_val) = opt_decimal {
+                return match_in_scope(
+                    condition,
+                    &Field::Decimal(decimal_val.clone()),
+                    scope,
+                    params,
+                );
+            }
+            return Result::Ok(false);
+        }
+        Field::OptionalBigInt(opt_big_int) => {
+            if let Some(big_int_val) = opt_big_int {
+                return match_in_scope(
+                    condition,
+                    &Field::BigInt(big_int_val.clone()),
+                    scope,
+                    params,
+                );
+            }
+            return Result::Ok(false);
+        }
+        Field::OptionalBlob(opt_blob) => {
+            if let Some(blob_val) = opt_blob {
+                return match_in_scope(condition, &Field::Blob(blob_val.clone()), scope, params);
+            }
+            return Result::Ok(false);
+        }
+        Field::OptionalVector(opt_vector) => {
+            if let Some(vector_val) = opt_vector {
+                return match_in_scope(
+                    condition,
+                    &Field::Vector(vector_val.clone()),
+                    scope,
+                    params,
+                );
             }
             return Result::Ok(false);
         }
@@ -95,39 +308,61 @@ pub fn match_conditions(condition: &Condition, doc_field: &Field) -> error::Resu
     }
 
     match &condition {
-        &Condition::Op(ref op, ref target_doc_field) => {
-            match op {
-                Op::GT | Op::GE | Op::LT | Op::LE => {
-                    match doc_field {
-                        core::doc::Field::Null => return Result::Ok(false),
-                        _ => {}
-                    }
-                    match target_doc_field {
-                        core::doc::Field::Null => return Result::Ok(false),
-                        _ => {}
-                    }
-                }
-                _ => {}
-            }
-            match op {
-                Op::EQ => return Result::Ok(op_eq::handle(target_doc_field, doc_field)),
-                Op::NE => return Result::Ok(!op_eq::handle(target_doc_field, doc_field)),
-                Op::GT => return Result::Ok(op_gt::handle(target_doc_field, doc_field)),
-                Op::LT => return Result::Ok(!op_ge::handle(target_doc_field, doc_field)),
-                Op::GE => return Result::Ok(op_ge::handle(target_doc_field, doc_field)),
-                Op::LE => return Result::Ok(!op_gt::handle(target_doc_field, doc_field)),
-                Op::IN => return Result::Ok(op_in::handle(target_doc_field, doc_field)),
-                Op::NIN => return Result::Ok(!op_in::handle(target_doc_field, doc_field)),
-                Op::LIKE => return Result::Ok(op_like::handle(target_doc_field, doc_field)),
-                Op::NLIKE => return Result::Ok(!op_like::handle(target_doc_field, doc_field)),
-                Op::ILIKE => return Result::Ok(op_ilike::handle(target_doc_field, doc_field)),
-                Op::NILIKE => return Result::Ok(!op_ilike::handle(target_doc_field, doc_field)),
+        &Condition::Op(op, target_doc_field) => {
+            return Result::Ok(match_op(op, target_doc_field, doc_field));
+        }
+        &Condition::FieldOp(op, field_ref) => {
+            return match resolve_field_ref(field_ref, scope)? {
+                Some(ref_field) => Result::Ok(match_op(op, &unwrap_optional(ref_field), doc_field)),
+                None => Result::Ok(false),
             };
         }
+        &Condition::ParamOp(op, name) => {
+            return match params.and_then(|params| params.get(name)) {
+                Some(value) => Result::Ok(match_op(op, &unwrap_optional(value), doc_field)),
+                None => Result::Err(error::Error::UnboundParam(name.clone())),
+            };
+        }
+        &Condition::ExprOp(op, left, right) => {
+            return match_expr_op(op, left, right, doc_field, scope);
+        }
+        &Condition::Between(range) => {
+            return match_between(range, doc_field);
+        }
+        &Condition::Within(shape) => {
+            return Result::Ok(geo::handle(shape, doc_field));
+        }
+        &Condition::Fuzzy(fuzzy) => {
+            return Result::Ok(fuzzy.matches(doc_field));
+        }
+        &Condition::Similar(threshold) => {
+            return threshold.matches(doc_field);
+        }
+        &Condition::Custom(name, target) => {
+            return operator::match_custom(name, target, doc_field);
+        }
+        &Condition::ArrayOp(op, target) => {
+            return Result::Ok(op_array::handle(op, target, doc_field));
+        }
+        &Condition::TimeOp(_, _) => {
+            unreachable!("relative times are resolved before matching");
+        }
+        &Condition::Meta(meta, inner) => {
+            return match_meta(meta, inner, doc_field, scope, params);
+        }
+        &Condition::JsonPath(path, inner) => {
+            return match_json_path(path, inner, doc_field, scope, params);
+        }
+        &Condition::JsonExists(path) => {
+            return Result::Ok(json::value_at(path, doc_field).is_some());
+        }
         &Condition::Prop(index, op) => {
             if let Field::Doc(doc) = doc_field {
+                let scope = Scope { doc, parent: scope };
                 match doc.fields.get(*index) {
-                    Some(prop_data) => return match_conditions(op.as_ref(), prop_data),
+                    Some(prop_data) => {
+                        return match_in_scope(op.as_ref(), prop_data, Some(&scope), params)
+                    }
                     None => return Result::Err(error::Error::OutOfBoundPropIndex),
                 }
             }
@@ -136,7 +371,7 @@ pub fn match_conditions(condition: &Condition, doc_field: &Field) -> error::Resu
         &Condition::CompoundOp(op, conditions) => match op {
             CompoundOp::AND => {
                 for cond in conditions {
-                    match match_conditions(&cond, &doc_field) {
+                    match match_in_scope(&cond, &doc_field, scope, params) {
                         Ok(false) => return Result::Ok(false),
                         Ok(true) => continue,
                         Err(e) => return Result::Err(e),
@@ -146,14 +381,14 @@ pub fn match_conditions(condition: &Condition, doc_field: &Field) -> error::Resu
             }
             CompoundOp::OR => {
                 for cond in conditions {
-                    match match_conditions(&cond, &doc_field) {
+                    match match_in_scope(&cond, &doc_field, scope, params) {
                         Ok(true) => return Result::Ok(true),
                         Ok(false) => continue,
                         Err(e) => return Result::Err(e),
                     }
                 }
             }
-            CompoundOp::NOT => match match_conditions(&conditions[0], &doc_field) {
+            CompoundOp::NOT => match match_in_scope(&conditions[0], &doc_field, scope, params) {
                 Ok(res) => return Result::Ok(!res),
                 Err(e) => return Result::Err(e),
             },
@@ -161,3 +396,203 @@ pub fn match_conditions(condition: &Condition, doc_field: &Field) -> error::Resu
     }
     return Result::Ok(false);
 }
+
+pub(crate) fn match_op(op: &Op, target_doc_field: &Field, doc_field: &Field) -> bool {
+    // LT and LE negate GE and GT, so values that are never ordered must be ruled out first
+    let is_unordered = |field: &Field| matches!(field, Field::Null) || field_ord::is_nan(field);
+    let is_incomparable_temporal = field_ord::is_temporal(doc_field)
+        && field_ord::is_temporal(target_doc_field)
+        && field_ord::compare(doc_field, target_doc_field).is_none();
+    if matches!(op, Op::GT | Op::GE | Op::LT | Op::LE)
+        && (is_unordered(doc_field) || is_unordered(target_doc_field) || is_incomparable_temporal)
+    {
+        return false;
+    }
+    match op {
+        Op::EQ => op_eq::handle(target_doc_field, doc_field),
+        Op::NE => !op_eq::handle(target_doc_field, doc_field),
+        Op::GT => op_gt::handle(target_doc_field, doc_field),
+        Op::LT => !op_ge::handle(target_doc_field, doc_field),
+        Op::GE => op_ge::handle(target_doc_field, doc_field),
+        Op::LE => !op_gt::handle(target_doc_field, doc_field),
+        Op::IN => op_in::handle(target_doc_field, doc_field),
+        Op::NIN => !op_in::handle(target_doc_field, doc_field),
+        Op::LIKE => op_like::handle(target_doc_field, doc_field),
+        Op::NLIKE => !op_like::handle(target_doc_field, doc_field),
+        Op::ILIKE => op_ilike::handle(target_doc_field, doc_field),
+        Op::NILIKE => !op_ilike::handle(target_doc_field, doc_field),
+        Op::MATCH => text::handle(target_doc_field, doc_field),
+        Op::PREFIX => op_prefix::handle(target_doc_field, doc_field),
+    }
+}
+
+// Returns true if the field lies within the range. Values and bounds must be of comparable
+// types; nulls never match.
+pub(crate) fn match_between(range: &Range, doc_field: &Field) -> error::Result<bool> {
+    if !is_rangeable(doc_field) {
+        return Ok(false);
+    }
+    let start = range_target(&range.start);
+    let end = range_target(&range.end);
+    if !is_rangeable(&start) || !is_rangeable(&end) {
+        return Ok(false);
+    }
+    let after_start = match field_ord::compare(doc_field, &start) {
+        Some(Ordering::Greater) => true,
+        Some(Ordering::Equal) => range.start_inclusive,
+        _ => false,
+    };
+    let before_end = match field_ord::compare(doc_field, &end) {
+        Some(Ordering::Less) => true,
+        Some(Ordering::Equal) => range.end_inclusive,
+        _ => false,
+    };
+    Ok(after_start && before_end)
+}
+
+fn range_target(target: &Target) -> Field {
+    match target {
+        Target::Value(value) => unwrap_optional(value),
+        Target::Time(_) => unreachable!("relative times are resolved before matching"),
+    }
+}
+
+fn is_rangeable(field: &Field) -> bool {
+    matches!(
+        field,
+        Field::Int(_)
+            | Field::Float(_)
+            | Field::Decimal(_)
+            | Field::BigInt(_)
+            | Field::DateTime(_)
+            | Field::ZonedDateTime(_)
+            | Field::Date(_)
+            | Field::Time(_)
+            | Field::String(_)
+            | Field::Blob(_)
+    )
+}
+
+// Applies the condition to the metadata value of the document. Fields that are not
+// documents have no metadata and never match.
+pub(crate) fn match_meta(
+    meta: &Meta,
+    condition: &Condition,
+    doc_field: &Field,
+    scope: Option<&Scope>,
+    params: Option<&HashMap<String, Field>>,
+) -> error::Result<bool> {
+    match doc_field {
+        Field::Doc(doc) => {
+            let scope = Scope { doc, parent: scope };
+            match_in_scope(condition, &meta_value(meta, doc), Some(&scope), params)
+        }
+        _ => Ok(false),
+    }
+}
+
+// Matches the condition against the value at the path. Paths that don't exist never match.
+pub(crate) fn match_json_path(
+    path: &JsonPath,
+    condition: &Condition,
+    doc_field: &Field,
+    scope: Option<&Scope>,
+    params: Option<&HashMap<String, Field>>,
+) -> error::Result<bool> {
+    match json::value_at(path, doc_field) {
+        Some(value) => match_in_scope(condition, &value, scope, params),
+        None => Ok(false),
+    }
+}
+
+fn meta_value(meta: &Meta, doc: &Doc) -> Field {
+    match meta {
+        Meta::Status => Field::String(
+            match doc.status {
+                Status::Active => "Active",
+                Status::Deleted => "Deleted",
+            }
+            .to_string(),
+        ),
+        Meta::Hidden => Field::Bool(doc.hidden),
+        Meta::SchemaVersionId => Field::String(doc.schema_version_id.clone()),
+    }
+}
+
+// Scope is the chain of documents enclosing the field a condition is matched against.
+pub(crate) struct Scope<'a> {
+    pub doc: &'a Doc,
+    pub parent: Option<&'a Scope<'a>>,
+}
+
+// Returns the field the reference points to, or `None` if the path goes through a field
+// that is not a document.
+pub(crate) fn resolve_field_ref<'a>(
+    field_ref: &FieldRef,
+    scope: Option<&Scope<'a>>,
+) -> error::Result<Option<&'a Field>> {
+    let mut scope = scope;
+    for _ in 0..field_ref.parent {
+        scope = scope.and_then(|s| s.parent);
+    }
+    let mut doc = match scope {
+        Some(scope) => scope.doc,
+        None => return Err(error::Error::UnresolvedFieldRef),
+    };
+    let mut field: Option<&'a Field> = None;
+    for index in &field_ref.path {
+        if let Some(prev) = field {
+            match prev {
+                Field::Doc(nested) => doc = nested,
+                _ => return Ok(None),
+            }
+        }
+        match doc.fields.get(*index) {
+            Some(prop_data) => field = Some(prop_data),
+            None => return Err(error::Error::OutOfBoundPropIndex),
+        }
+    }
+    Ok(field)
+}
+
+// Evaluates both expressions and compares their values. Field references of the expressions
+// resolve against the matched field if it's a document, or the document holding it otherwise.
+pub(crate) fn match_expr_op(
+    op: &Op,
+    left: &Expr,
+    right: &Expr,
+    doc_field: &Field,
+    scope: Option<&Scope>,
+) -> error::Result<bool> {
+    let doc_scope;
+    let scope = match doc_field {
+        Field::Doc(doc) => {
+            doc_scope = Scope { doc, parent: scope };
+            Some(&doc_scope)
+        }
+        _ => scope,
+    };
+    let left = expr::eval(left, doc_field, scope)?;
+    let right = expr::eval(right, doc_field, scope)?;
+    Ok(match_op(op, &right, &left))
+}
+
+// Returns the value of an optional field, or null if it has none.
+pub(crate) fn unwrap_optional(field: &Field) -> Field {
+    match field {
+        Field::OptionalBool(val) => val.map_or(Field::Null, Field::Bool),
+        Field::OptionalInt(val) => val.map_or(Field::Null, Field::Int),
+        Field::OptionalFloat(val) => val.map_or(Field::Null, Field::Float),
+        Field::OptionalString(val) => val.clone().map_or(Field::Null, Field::String),
+        Field::OptionalDateTime(val) => val.map_or(Field::Null, Field::DateTime),
+        Field::OptionalZonedDateTime(val) => val.map_or(Field::Null, Field::ZonedDateTime),
+        Field::OptionalDate(val) => val.map_or(Field::Null, Field::Date),
+        Field::OptionalTime(val) => val.map_or(Field::Null, Field::Time),
+        Field::OptionalGeoPoint(val) => val.map_or(Field::Null, Field::GeoPoint),
+        Field::OptionalVector(val) => val.clone().map_or(Field::Null, Field::Vector),
+        Field::OptionalBlob(val) => val.clone().map_or(Field::Null, Field::Blob),
+        Field::OptionalDecimal(val) => val.clone().map_or(Field::Null, Field::Decimal),
+        Field::OptionalBigInt(val) => val.clone().map_or(Field::Null, Field::BigInt),
+        _ => field.clone(),
+    }
+}
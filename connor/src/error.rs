@@ -11,11 +11,29 @@
 use std::error;
 use std::fmt;
 
+use crate::limits::Limit;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug)]
 pub enum Error {
     OutOfBoundPropIndex,
+    UnresolvedFieldRef,
+    ArithmeticOverflow,
+    DivisionByZero,
+    InvalidExprOperand,
+    InvalidCondition(String),
+    UnboundParam(String),
+    UnknownParam(String),
+    ConflictingParamTypes(String),
+    ParamTypeMismatch(String),
+    InvalidRelativeTime(String),
+    DimensionMismatch(usize, usize),
+    InvalidJsonPath(String),
+    UnknownOperator(String),
+    InvalidOperator(String),
+    OperatorFailed(String),
+    LimitExceeded(Limit, usize),
 }
 
 impl error::Error for Error {}
@@ -24,6 +42,32 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Error::OutOfBoundPropIndex => write!(f, "Prop index is out of bound"),
+            Error::UnresolvedFieldRef => write!(f, "Field reference has no enclosing document"),
+            Error::ArithmeticOverflow => write!(f, "Arithmetic overflow in expression"),
+            Error::DivisionByZero => write!(f, "Division by zero in expression"),
+            Error::InvalidExprOperand => write!(f, "Invalid operand type in expression"),
+            Error::InvalidCondition(reason) => write!(f, "Invalid condition: {}", reason),
+            Error::UnboundParam(name) => write!(f, "Param {} is not bound", name),
+            Error::UnknownParam(name) => write!(f, "Param {} is not used by the condition", name),
+            Error::ConflictingParamTypes(name) => {
+                write!(f, "Param {} is used with incompatible operators", name)
+            }
+            Error::ParamTypeMismatch(name) => {
+                write!(f, "Value bound to param {} has the wrong type", name)
+            }
+            Error::InvalidRelativeTime(time) => write!(f, "Invalid relative time: {}", time),
+            Error::DimensionMismatch(expected, actual) => write!(
+                f,
+                "Vector has dimension {} but {} was expected",
+                actual, expected
+            ),
+            Error::InvalidJsonPath(path) => write!(f, "Invalid JSON path: {}", path),
+            Error::UnknownOperator(name) => write!(f, "Operator {} is not registered", name),
+            Error::InvalidOperator(reason) => write!(f, "Invalid operator: {}", reason),
+            Error::OperatorFailed(name) => write!(f, "Operator {} failed", name),
+            Error::LimitExceeded(limit, max) => {
+                write!(f, "{} exceeds the limit of {}", limit, max)
+            }
         }
     }
 }
@@ -0,0 +1,210 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use chrono::{Datelike, Timelike};
+use core::doc::Field;
+use core::json::Json;
+
+use crate::cond::{self, FieldRef, Scope};
+use crate::error;
+
+// Expr computes a value from the matched field, other fields of the document and literals.
+// A null operand makes the whole expression null.
+#[derive(Clone, Debug)]
+pub enum Expr {
+    Literal(Field),
+    // The field the condition is matched against.
+    Value,
+    Field(FieldRef),
+    Arith(ArithOp, Box<Expr>, Box<Expr>),
+    Call(Func, Box<Expr>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Func {
+    Abs,
+    Length,
+    Lower,
+    Upper,
+    Trim,
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl Expr {
+    // Returns every field reference used by the expression.
+    pub fn field_refs(&self) -> Vec<&FieldRef> {
+        let mut refs = Vec::new();
+        self.collect_field_refs(&mut refs);
+        refs
+    }
+
+    fn collect_field_refs<'a>(&'a self, refs: &mut Vec<&'a FieldRef>) {
+        match self {
+            Expr::Literal(_) | Expr::Value => {}
+            Expr::Field(field_ref) => refs.push(field_ref),
+            Expr::Arith(_, left, right) => {
+                left.collect_field_refs(refs);
+                right.collect_field_refs(refs);
+            }
+            Expr::Call(_, arg) => arg.collect_field_refs(refs),
+        }
+    }
+}
+
+pub(crate) fn eval(expr: &Expr, value: &Field, scope: Option<&Scope>) -> error::Result<Field> {
+    match expr {
+        Expr::Literal(literal) => Ok(cond::unwrap_optional(literal)),
+        Expr::Value => Ok(cond::unwrap_optional(value)),
+        Expr::Field(field_ref) => match cond::resolve_field_ref(field_ref, scope)? {
+            Some(field) => Ok(cond::unwrap_optional(field)),
+            None => Ok(Field::Null),
+        },
+        Expr::Arith(op, left, right) => {
+            let left = eval(left, value, scope)?;
+            let right = eval(right, value, scope)?;
+            arith(op, &left, &right)
+        }
+        Expr::Call(func, arg) => call(func, &eval(arg, value, scope)?),
+    }
+}
+
+fn arith(op: &ArithOp, left: &Field, right: &Field) -> error::Result<Field> {
+    match (left, right) {
+        (Field::Null, _) | (_, Field::Null) => Ok(Field::Null),
+        (Field::Int(left), Field::Int(right)) => {
+            let result = match op {
+                ArithOp::Add => left.checked_add(*right),
+                ArithOp::Sub => left.checked_sub(*right),
+                ArithOp::Mul => left.checked_mul(*right),
+                ArithOp::Div => {
+                    if *right == 0 {
+                        return Err(error::Error::DivisionByZero);
+                    }
+                    left.checked_div(*right)
+                }
+            };
+            result
+                .map(Field::Int)
+                .ok_or(error::Error::ArithmeticOverflow)
+        }
+        (Field::Int(_) | Field::Float(_), Field::Int(_) | Field::Float(_)) => {
+            let left = as_float(left);
+            let right = as_float(right);
+            let result = match op {
+                ArithOp::Add => left + right,
+                ArithOp::Sub => left - right,
+                ArithOp::Mul => left * right,
+                ArithOp::Div => {
+                    if right == 0.0 {
+                        return Err(error::Error::DivisionByZero);
+                    }
+                    left / right
+                }
+            };
+            if result.is_infinite() && left.is_finite() && right.is_finite() {
+                return Err(error::Error::ArithmeticOverflow);
+            }
+            Ok(Field::Float(result))
+        }
+        _ => Err(error::Error::InvalidExprOperand),
+    }
+}
+
+fn as_float(field: &Field) -> f64 {
+    match field {
+        Field::Int(int_val) => *int_val as f64,
+        Field::Float(float_val) => *float_val,
+        _ => f64::NAN,
+    }
+}
+
+fn call(func: &Func, arg: &Field) -> error::Result<Field> {
+    if let Field::Null = arg {
+        return Ok(Field::Null);
+    }
+    match (func, arg) {
+        (Func::Abs, Field::Int(int_val)) => int_val
+            .checked_abs()
+            .map(Field::Int)
+            .ok_or(error::Error::ArithmeticOverflow),
+        (Func::Abs, Field::Float(float_val)) => Ok(Field::Float(float_val.abs())),
+        (Func::Length, _) => match length(arg) {
+            Some(len) => Ok(Field::Int(len as i64)),
+            None => Err(error::Error::InvalidExprOperand),
+        },
+        (Func::Lower, Field::String(str_val)) => Ok(Field::String(str_val.to_lowercase())),
+        (Func::Upper, Field::String(str_val)) => Ok(Field::String(str_val.to_uppercase())),
+        (Func::Trim, Field::String(str_val)) => Ok(Field::String(str_val.trim().to_string())),
+        (Func::Year, Field::DateTime(date_time)) => Ok(Field::Int(date_time.year() as i64)),
+        (Func::Month, Field::DateTime(date_time)) => Ok(Field::Int(date_time.month() as i64)),
+        (Func::Day, Field::DateTime(date_time)) => Ok(Field::Int(date_time.day() as i64)),
+        (Func::Hour, Field::DateTime(date_time)) => Ok(Field::Int(date_time.hour() as i64)),
+        (Func::Minute, Field::DateTime(date_time)) => Ok(Field::Int(date_time.minute() as i64)),
+        (Func::Second, Field::DateTime(date_time)) => Ok(Field::Int(date_time.second() as i64)),
+        // zoned datetimes give their parts in their own offset
+        (Func::Year, Field::ZonedDateTime(date_time)) => Ok(Field::Int(date_time.year() as i64)),
+        (Func::Month, Field::ZonedDateTime(date_time)) => Ok(Field::Int(date_time.month() as i64)),
+        (Func::Day, Field::ZonedDateTime(date_time)) => Ok(Field::Int(date_time.day() as i64)),
+        (Func::Hour, Field::ZonedDateTime(date_time)) => Ok(Field::Int(date_time.hour() as i64)),
+        (Func::Minute, Field::ZonedDateTime(date_time)) => {
+            Ok(Field::Int(date_time.minute() as i64))
+        }
+        (Func::Second, Field::ZonedDateTime(date_time)) => {
+            Ok(Field::Int(date_time.second() as i64))
+        }
+        (Func::Year, Field::Date(date)) => Ok(Field::Int(date.year() as i64)),
+        (Func::Month, Field::Date(date)) => Ok(Field::Int(date.month() as i64)),
+        (Func::Day, Field::Date(date)) => Ok(Field::Int(date.day() as i64)),
+        (Func::Hour, Field::Time(time)) => Ok(Field::Int(time.hour() as i64)),
+        (Func::Minute, Field::Time(time)) => Ok(Field::Int(time.minute() as i64)),
+        (Func::Second, Field::Time(time)) => Ok(Field::Int(time.second() as i64)),
+        _ => Err(error::Error::InvalidExprOperand),
+    }
+}
+
+// Returns the number of characters of a string, the number of bytes of a blob or the number
+// of elements of an array.
+fn length(field: &Field) -> Option<usize> {
+    match field {
+        Field::String(str_val) => Some(str_val.chars().count()),
+        Field::Blob(blob_val) => Some(blob_val.len()),
+        Field::BoolArray(arr) => Some(arr.len()),
+        Field::IntArray(arr) => Some(arr.len()),
+        Field::FloatArray(arr) => Some(arr.len()),
+        Field::DecimalArray(arr) => Some(arr.len()),
+        Field::BigIntArray(arr) => Some(arr.len()),
+        Field::StringArray(arr) => Some(arr.len()),
+        Field::DateTimeArray(arr) => Some(arr.len()),
+        Field::GeoPointArray(arr) => Some(arr.len()),
+        Field::BlobArray(arr) => Some(arr.len()),
+        Field::DocArray(arr) => Some(arr.len()),
+        Field::OptionalBoolArray(arr) => Some(arr.len()),
+        Field::OptionalIntArray(arr) => Some(arr.len()),
+        Field::OptionalFloatArray(arr) => Some(arr.len()),
+        Field::OptionalStringArray(arr) => Some(arr.len()),
+        Field::OptionalDateTimeArray(arr) => Some(arr.len()),
+        Field::OptionalGeoPointArray(arr) => Some(arr.len()),
+        Field::Json(Json::Array(arr)) => Some(arr.len()),
+        _ => None,
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use std::cmp::Ordering;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use core::doc::Field;
+use core::number::Decimal;
+
+// Compares two scalar fields the same way the comparison operators do.
+// Null sorts before every other value. Returns `None` if the fields are not comparable.
+pub fn compare(a: &Field, b: &Field) -> Option<Ordering> {
+    match (a, b) {
+        (Field::Null, Field::Null) => Some(Ordering::Equal),
+        (Field::Null, _) if is_scalar(b) => Some(Ordering::Less),
+        (_, Field::Null) if is_scalar(a) => Some(Ordering::Greater),
+        (Field::Bool(a), Field::Bool(b)) => Some(a.cmp(b)),
+        (Field::Int(a), Field::Int(b)) => Some(a.cmp(b)),
+        (Field::Int(a), Field::Float(b)) => compare_int_float(*a, *b),
+        (Field::Float(a), Field::Int(b)) => compare_int_float(*b, *a).map(Ordering::reverse),
+        (Field::Float(a), Field::Float(b)) => a.partial_cmp(b),
+        (Field::String(a), Field::String(b)) => Some(a.cmp(b)),
+        (Field::DateTime(a), Field::DateTime(b)) => Some(a.cmp(b)),
+        (Field::Blob(a), Field::Blob(b)) => Some(a.cmp(b)),
+        _ if is_exact_number(a) || is_exact_number(b) => compare_exact(a, b),
+        _ if is_temporal(a) && is_temporal(b) => compare_temporal(a, b),
+        _ => None,
+    }
+}
+
+// Returns true if the field is a DateTime, ZonedDateTime, Date or Time.
+pub fn is_temporal(field: &Field) -> bool {
+    matches!(
+        field,
+        Field::DateTime(_) | Field::ZonedDateTime(_) | Field::Date(_) | Field::Time(_)
+    )
+}
+
+// Compares temporal values with these coercions:
+// - DateTime and ZonedDateTime compare as instants, so the offset does not matter.
+// - A Date compares with the calendar date of a datetime in the datetime's own offset, so every
+//   datetime on that day is equal to it.
+// - A Time compares with the time of day of a datetime in the datetime's own offset.
+// - A Date and a Time are not comparable.
+fn compare_temporal(a: &Field, b: &Field) -> Option<Ordering> {
+    match (a, b) {
+        (Field::Date(a), Field::Date(b)) => Some(a.cmp(b)),
+        (Field::Time(a), Field::Time(b)) => Some(a.cmp(b)),
+        (Field::Date(a), _) => Some(a.cmp(&local_date(b)?)),
+        (_, Field::Date(b)) => Some(local_date(a)?.cmp(b)),
+        (Field::Time(a), _) => Some(a.cmp(&local_time(b)?)),
+        (_, Field::Time(b)) => Some(local_time(a)?.cmp(b)),
+        _ => Some(instant(a)?.cmp(&instant(b)?)),
+    }
+}
+
+fn instant(field: &Field) -> Option<DateTime<Utc>> {
+    match field {
+        Field::DateTime(val) => Some(*val),
+        Field::ZonedDateTime(val) => Some(val.with_timezone(&Utc)),
+        _ => None,
+    }
+}
+
+fn local_date(field: &Field) -> Option<NaiveDate> {
+    match field {
+        Field::DateTime(val) => Some(val.date_naive()),
+        Field::ZonedDateTime(val) => Some(val.date_naive()),
+        _ => None,
+    }
+}
+
+fn local_time(field: &Field) -> Option<NaiveTime> {
+    match field {
+        Field::DateTime(val) => Some(val.time()),
+        Field::ZonedDateTime(val) => Some(val.time()),
+        _ => None,
+    }
+}
+
+// Compares an integer with a float exactly, without rounding the integer to the nearest float.
+// Returns `None` if the float is NaN.
+pub fn compare_int_float(int: i64, float: f64) -> Option<Ordering> {
+    // 2^63, the smallest float above every i64
+    const I64_END: f64 = 9_223_372_036_854_775_808.0;
+    if float.is_nan() {
+        return None;
+    }
+    if float >= I64_END {
+        return Some(Ordering::Less);
+    }
+    if float < -I64_END {
+        return Some(Ordering::Greater);
+    }
+    // the float is now within the range of i64, so its integer part converts exactly
+    let trunc = float.trunc();
+    let ord = int.cmp(&(trunc as i64));
+    if ord != Ordering::Equal {
+        return Some(ord);
+    }
+    0.0f64.partial_cmp(&(float - trunc))
+}
+
+// Returns true if the field is a NaN float. NaN is unordered: it is neither equal to, less
+// than nor greater than any value, NaN included, so no comparison operator matches it.
+pub fn is_nan(field: &Field) -> bool {
+    matches!(field, Field::Float(val) if val.is_nan())
+}
+
+// Returns true if the field is a Decimal or a BigInt, which compare exactly against every
+// other number.
+pub fn is_exact_number(field: &Field) -> bool {
+    matches!(field, Field::Decimal(_) | Field::BigInt(_))
+}
+
+// Compares numbers by their exact values. Infinite floats are beyond every other number and
+// NaN is not comparable.
+fn compare_exact(a: &Field, b: &Field) -> Option<Ordering> {
+    match (a, b) {
+        (Field::Float(a), _) if a.is_infinite() && is_number(b) => Some(a.total_cmp(&0.0)),
+        (_, Field::Float(b)) if b.is_infinite() && is_number(a) => Some(0.0f64.total_cmp(b)),
+        _ => Some(exact_value(a)?.cmp(&exact_value(b)?)),
+    }
+}
+
+fn is_number(field: &Field) -> bool {
+    matches!(field, Field::Int(_) | Field::Float(_)) || is_exact_number(field)
+}
+
+fn exact_value(field: &Field) -> Option<Decimal> {
+    match field {
+        Field::Int(val) => Some(Decimal::from(*val)),
+        Field::Float(val) => Decimal::from_f64(*val),
+        Field::Decimal(val) => Some(val.clone()),
+        Field::BigInt(val) => Some(val.as_decimal().clone()),
+        _ => None,
+    }
+}
+
+// Returns true if the field is a single orderable value.
+pub fn is_scalar(field: &Field) -> bool {
+    matches!(
+        field,
+        Field::Null
+            | Field::Bool(_)
+            | Field::Int(_)
+            | Field::Float(_)
+            | Field::Decimal(_)
+            | Field::BigInt(_)
+            | Field::String(_)
+            | Field::DateTime(_)
+            | Field::ZonedDateTime(_)
+            | Field::Blob(_)
+    )
+}
@@ -0,0 +1,132 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use std::collections::HashSet;
+
+use core::doc::Field;
+
+use crate::op_ilike;
+
+// Similarity is the measure used to decide whether two strings are close enough.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Similarity {
+    // Matches strings within the given number of single character edits.
+    Levenshtein(usize),
+    // Matches strings sharing at least the given ratio of trigrams, from 0 to 1.
+    Trigram(f64),
+}
+
+// Fuzzy matches strings similar to a value, tolerating misspellings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fuzzy {
+    pub value: String,
+    pub similarity: Similarity,
+    // Compares strings the way ILIKE does.
+    pub case_insensitive: bool,
+}
+
+impl Fuzzy {
+    // Returns true if the String, OptionalString or StringArray field holds a similar string.
+    pub fn matches(&self, field: &Field) -> bool {
+        strings(field).iter().any(|value| self.matches_str(value))
+    }
+
+    // Returns the similarity of the most similar string of the field, from 0 to 1, or `None`
+    // if the field holds no string.
+    pub fn score(&self, field: &Field) -> Option<f64> {
+        strings(field)
+            .iter()
+            .map(|value| self.score_str(value))
+            .reduce(f64::max)
+    }
+
+    fn matches_str(&self, value: &str) -> bool {
+        let (expected, value) = self.folded(value);
+        match self.similarity {
+            Similarity::Levenshtein(max_distance) => levenshtein(&expected, &value) <= max_distance,
+            Similarity::Trigram(threshold) => trigram_similarity(&expected, &value) >= threshold,
+        }
+    }
+
+    fn score_str(&self, value: &str) -> f64 {
+        let (expected, value) = self.folded(value);
+        match self.similarity {
+            Similarity::Levenshtein(_) => {
+                let len = expected.chars().count().max(value.chars().count());
+                if len == 0 {
+                    return 1.0;
+                }
+                1.0 - levenshtein(&expected, &value) as f64 / len as f64
+            }
+            Similarity::Trigram(_) => trigram_similarity(&expected, &value),
+        }
+    }
+
+    fn folded(&self, value: &str) -> (String, String) {
+        if self.case_insensitive {
+            (op_ilike::fold(&self.value), op_ilike::fold(value))
+        } else {
+            (self.value.clone(), value.to_string())
+        }
+    }
+}
+
+fn strings(field: &Field) -> Vec<&str> {
+    match field {
+        Field::String(val) | Field::OptionalString(Some(val)) => vec![val.as_str()],
+        Field::StringArray(arr) => arr.iter().map(String::as_str).collect(),
+        Field::OptionalStringArray(arr) => arr.iter().flatten().map(String::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+// Returns the number of single character insertions, deletions or substitutions needed to
+// turn one string into the other.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, a_char) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(a_char != b_char);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+// Returns the ratio of trigrams shared by both strings, from 0 to 1. Every word is padded
+// with two spaces in front and one behind, so that short words and word starts weigh more.
+pub fn trigram_similarity(a: &str, b: &str) -> f64 {
+    let a = trigrams(a);
+    let b = trigrams(b);
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let shared = a.intersection(&b).count();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+fn trigrams(value: &str) -> HashSet<[char; 3]> {
+    let mut result = HashSet::new();
+    for word in value.split(|c: char| !c.is_alphanumeric()) {
+        if word.is_empty() {
+            continue;
+        }
+        let padded: Vec<char> = "  ".chars().chain(word.chars()).chain([' ']).collect();
+        for window in padded.windows(3) {
+            result.insert([window[0], window[1], window[2]]);
+        }
+    }
+    result
+}
@@ -0,0 +1,87 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use core::doc::{Field, GeoPoint};
+
+// The mean radius of Earth in meters.
+pub const EARTH_RADIUS: f64 = 6_371_008.8;
+
+// Shape is an area of Earth that points can be tested against.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    // Points within the given distance in meters of the center.
+    Radius(GeoPoint, f64),
+    // Points between the south-west and the north-east corners. A box whose western
+    // longitude is greater than the eastern one crosses the antimeridian.
+    BBox(GeoPoint, GeoPoint),
+    // Points inside the polygon, whose edges are straight lines in latitude and longitude.
+    // Polygons with fewer than 3 vertices contain no points.
+    Polygon(Vec<GeoPoint>),
+}
+
+impl Shape {
+    pub fn contains(&self, point: &GeoPoint) -> bool {
+        match self {
+            Shape::Radius(center, meters) => distance(center, point) <= *meters,
+            Shape::BBox(south_west, north_east) => {
+                let within_lat = point.lat >= south_west.lat && point.lat <= north_east.lat;
+                let within_lon = if south_west.lon <= north_east.lon {
+                    point.lon >= south_west.lon && point.lon <= north_east.lon
+                } else {
+                    point.lon >= south_west.lon || point.lon <= north_east.lon
+                };
+                within_lat && within_lon
+            }
+            Shape::Polygon(vertices) => polygon_contains(vertices, point),
+        }
+    }
+}
+
+// Returns the great-circle distance between two points in meters using the haversine formula.
+pub fn distance(a: &GeoPoint, b: &GeoPoint) -> f64 {
+    let lat_a = a.lat.to_radians();
+    let lat_b = b.lat.to_radians();
+    let half_d_lat = (b.lat - a.lat).to_radians() / 2.0;
+    let half_d_lon = (b.lon - a.lon).to_radians() / 2.0;
+    let h = half_d_lat.sin().powi(2) + lat_a.cos() * lat_b.cos() * half_d_lon.sin().powi(2);
+    2.0 * EARTH_RADIUS * h.sqrt().min(1.0).asin()
+}
+
+// Counts how many edges a ray going east from the point crosses.
+fn polygon_contains(vertices: &[GeoPoint], point: &GeoPoint) -> bool {
+    if vertices.len() < 3 {
+        return false;
+    }
+    let mut inside = false;
+    let mut prev = &vertices[vertices.len() - 1];
+    for curr in vertices {
+        if (curr.lat > point.lat) != (prev.lat > point.lat) {
+            let crossing_lon =
+                curr.lon + (point.lat - curr.lat) / (prev.lat - curr.lat) * (prev.lon - curr.lon);
+            if point.lon < crossing_lon {
+                inside = !inside;
+            }
+        }
+        prev = curr;
+    }
+    inside
+}
+
+// Returns true if the point, or any point of an array, lies within the shape.
+pub(crate) fn handle(shape: &Shape, doc_field: &Field) -> bool {
+    match doc_field {
+        Field::GeoPoint(point) => shape.contains(point),
+        Field::GeoPointArray(arr) => arr.iter().any(|point| shape.contains(point)),
+        Field::OptionalGeoPointArray(arr) => {
+            arr.iter().flatten().any(|point| shape.contains(point))
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,263 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+
+use crate::error;
+use crate::vector::{Metric, Neighbor};
+
+const DEFAULT_MAX_NEIGHBORS: usize = 16;
+const DEFAULT_EF_CONSTRUCTION: usize = 100;
+
+// A node of the graph along with its distance to the vector being searched for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Candidate {
+    distance: f64,
+    id: usize,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.id.cmp(&other.id))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// HnswIndex is an in-memory hierarchical navigable small world graph for approximate
+// nearest-neighbour search over large collections of vectors. Every vector is linked to
+// its closest vectors on layer 0 and on a random number of sparser layers above it, and
+// searches descend from the top layer towards the query.
+#[derive(Clone, Debug)]
+pub struct HnswIndex {
+    metric: Metric,
+    max_neighbors: usize,
+    ef_construction: usize,
+    level_factor: f64,
+    vectors: Vec<Vec<f32>>,
+    // The neighbours of every node, for each layer the node is on.
+    links: Vec<Vec<Vec<usize>>>,
+    entry_point: Option<usize>,
+    rng_state: u64,
+}
+
+impl HnswIndex {
+    pub fn new(metric: Metric) -> Self {
+        Self::with_params(metric, DEFAULT_MAX_NEIGHBORS, DEFAULT_EF_CONSTRUCTION)
+    }
+
+    // Returns an index linking every node to at most `max_neighbors` nodes per layer, twice
+    // as many on layer 0, chosen among the `ef_construction` closest nodes found on insert.
+    pub fn with_params(metric: Metric, max_neighbors: usize, ef_construction: usize) -> Self {
+        let max_neighbors = max_neighbors.max(2);
+        Self {
+            metric,
+            max_neighbors,
+            ef_construction: ef_construction.max(1),
+            level_factor: 1.0 / (max_neighbors as f64).ln(),
+            vectors: Vec::new(),
+            links: Vec::new(),
+            entry_point: None,
+            rng_state: 0x2545_F491_4F6C_DD1D,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vectors.is_empty()
+    }
+
+    // Returns the dimension of the indexed vectors, set by the first one inserted.
+    pub fn dimension(&self) -> Option<usize> {
+        self.vectors.first().map(Vec::len)
+    }
+
+    // Adds the vector to the index and returns its id, the number of vectors inserted before.
+    pub fn insert(&mut self, vector: Vec<f32>) -> error::Result<usize> {
+        self.check_dimension(&vector)?;
+        let id = self.vectors.len();
+        let level = self.random_level();
+        self.vectors.push(vector);
+        self.links.push(vec![Vec::new(); level + 1]);
+
+        let entry = match self.entry_point {
+            Some(entry) => entry,
+            None => {
+                self.entry_point = Some(id);
+                return Ok(id);
+            }
+        };
+        let top = self.links[entry].len() - 1;
+        let query = self.vectors[id].clone();
+        let mut entry_points = vec![entry];
+        for layer in (level + 1..=top).rev() {
+            entry_points = self.closest(&query, &entry_points, layer);
+        }
+        for layer in (0..=level.min(top)).rev() {
+            let found = self.search_layer(&query, &entry_points, self.ef_construction, layer);
+            let max_links = self.max_links(layer);
+            let neighbors: Vec<usize> = found.iter().take(max_links).map(|c| c.id).collect();
+            for &neighbor in &neighbors {
+                self.links[neighbor][layer].push(id);
+                if self.links[neighbor][layer].len() > max_links {
+                    self.prune(neighbor, layer, max_links);
+                }
+            }
+            self.links[id][layer] = neighbors;
+            entry_points = found.iter().map(|c| c.id).collect();
+        }
+        if level > top {
+            self.entry_point = Some(id);
+        }
+        Ok(id)
+    }
+
+    // Returns approximately the k vectors closest to the query, closest first, with their ids
+    // as indexes. Considering more than k candidates with a larger `ef` improves the recall.
+    pub fn search(&self, query: &[f32], k: usize, ef: usize) -> error::Result<Vec<Neighbor>> {
+        self.check_dimension(query)?;
+        let entry = match self.entry_point {
+            Some(entry) => entry,
+            None => return Ok(Vec::new()),
+        };
+        let mut entry_points = vec![entry];
+        for layer in (1..self.links[entry].len()).rev() {
+            entry_points = self.closest(query, &entry_points, layer);
+        }
+        let found = self.search_layer(query, &entry_points, ef.max(k), 0);
+        Ok(found
+            .into_iter()
+            .take(k)
+            .filter_map(|c| {
+                let measure = self.metric.measure(query, &self.vectors[c.id])?;
+                Some(Neighbor {
+                    index: c.id,
+                    measure,
+                })
+            })
+            .collect())
+    }
+
+    fn check_dimension(&self, vector: &[f32]) -> error::Result<()> {
+        match self.dimension() {
+            Some(dimension) if dimension != vector.len() => {
+                Err(error::Error::DimensionMismatch(dimension, vector.len()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn max_links(&self, layer: usize) -> usize {
+        if layer == 0 {
+            2 * self.max_neighbors
+        } else {
+            self.max_neighbors
+        }
+    }
+
+    fn distance(&self, query: &[f32], id: usize) -> f64 {
+        // vectors the metric is undefined for are never close
+        self.metric
+            .distance(query, &self.vectors[id])
+            .unwrap_or(f64::INFINITY)
+    }
+
+    // Keeps only the closest links of the node on the layer.
+    fn prune(&mut self, node: usize, layer: usize, max_links: usize) {
+        let vector = &self.vectors[node];
+        let mut links: Vec<Candidate> = self.links[node][layer]
+            .iter()
+            .map(|&id| Candidate {
+                distance: self.distance(vector, id),
+                id,
+            })
+            .collect();
+        links.sort();
+        self.links[node][layer] = links.into_iter().take(max_links).map(|c| c.id).collect();
+    }
+
+    fn closest(&self, query: &[f32], entry_points: &[usize], layer: usize) -> Vec<usize> {
+        let found = self.search_layer(query, entry_points, 1, layer);
+        found.into_iter().map(|c| c.id).collect()
+    }
+
+    // Returns up to `ef` nodes of the layer closest to the query, closest first, found by
+    // following links from the entry points.
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entry_points: &[usize],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let mut visited: HashSet<usize> = entry_points.iter().copied().collect();
+        let mut candidates = BinaryHeap::new();
+        let mut found = BinaryHeap::new();
+        for &id in entry_points {
+            let candidate = Candidate {
+                distance: self.distance(query, id),
+                id,
+            };
+            candidates.push(Reverse(candidate));
+            found.push(candidate);
+        }
+        while found.len() > ef {
+            found.pop();
+        }
+        while let Some(Reverse(closest)) = candidates.pop() {
+            if let Some(furthest) = found.peek() {
+                if found.len() >= ef && closest > *furthest {
+                    break;
+                }
+            }
+            for &id in &self.links[closest.id][layer] {
+                if !visited.insert(id) {
+                    continue;
+                }
+                let candidate = Candidate {
+                    distance: self.distance(query, id),
+                    id,
+                };
+                let is_closer = found.peek().is_none_or(|furthest| candidate < *furthest);
+                if found.len() < ef || is_closer {
+                    candidates.push(Reverse(candidate));
+                    found.push(candidate);
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+        found.into_sorted_vec()
+    }
+
+    // Draws the top layer of a new node from an exponentially decaying distribution, using a
+    // xorshift generator so that indexes built from the same vectors are identical.
+    fn random_level(&mut self) -> usize {
+        self.rng_state ^= self.rng_state >> 12;
+        self.rng_state ^= self.rng_state << 25;
+        self.rng_state ^= self.rng_state >> 27;
+        let bits = self.rng_state.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11;
+        let uniform = (bits as f64 + 1.0) / (1u64 << 53) as f64;
+        (-uniform.ln() * self.level_factor) as usize
+    }
+}
@@ -0,0 +1,420 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+// Static reasoning about conditions.
+//
+// Every prop is assumed to hold values of a single type (as enforced by the schema) and
+// top-level props are assumed to be independent of each other. Conditions are rewritten
+// into disjunctive normal form and each conjunction is checked for satisfiability by
+// intersecting the sets of values each prop may take. Anything that can't be represented
+// as a set of values (like patterns other than prefix LIKE) makes the answer `Unknown`
+// unless another part of the condition already decides it.
+
+use std::collections::HashMap;
+
+use chrono::Duration;
+use core::doc::Field;
+
+use crate::cond::{CompoundOp, Condition, Op};
+use crate::span::{self, Bound, Span};
+
+// Upper bound on the number of conjunctions a condition may expand to before giving up.
+const MAX_CONJUNCTIONS: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Answer {
+    Yes,
+    No,
+    Unknown,
+}
+
+// Returns whether every document matching `a` also matches `b`.
+pub fn implies(a: &Condition, b: &Condition) -> Answer {
+    is_unsatisfiable(&Condition::CompoundOp(
+        CompoundOp::AND,
+        vec![
+            a.clone(),
+            Condition::CompoundOp(CompoundOp::NOT, vec![b.clone()]),
+        ],
+    ))
+}
+
+// Returns whether no document can match both `a` and `b`.
+pub fn is_disjoint(a: &Condition, b: &Condition) -> Answer {
+    is_unsatisfiable(&Condition::CompoundOp(
+        CompoundOp::AND,
+        vec![a.clone(), b.clone()],
+    ))
+}
+
+// Returns whether no document can match the condition.
+pub fn is_unsatisfiable(condition: &Condition) -> Answer {
+    match satisfiable(condition) {
+        Answer::Yes => Answer::No,
+        Answer::No => Answer::Yes,
+        Answer::Unknown => Answer::Unknown,
+    }
+}
+
+fn satisfiable(condition: &Condition) -> Answer {
+    let dnf = match to_dnf(condition, false) {
+        Some(dnf) => dnf,
+        None => return Answer::Unknown,
+    };
+    let mut answer = Answer::No;
+    for conjunction in dnf {
+        match conjunction_satisfiable(conjunction) {
+            Answer::Yes => return Answer::Yes,
+            Answer::Unknown => answer = Answer::Unknown,
+            Answer::No => {}
+        }
+    }
+    answer
+}
+
+// ValueSet is the set of values a prop may take for a condition to match.
+// `absent` stands for an optional field without a value, which never matches an operator.
+#[derive(Clone, Debug)]
+struct ValueSet {
+    spans: Vec<Span>,
+    absent: bool,
+}
+
+impl ValueSet {
+    fn full() -> Self {
+        Self {
+            spans: vec![Span::full()],
+            absent: true,
+        }
+    }
+
+    fn empty() -> Self {
+        Self {
+            spans: Vec::new(),
+            absent: false,
+        }
+    }
+
+    fn complement(&self) -> Self {
+        Self {
+            spans: span::complement(&self.spans),
+            absent: !self.absent,
+        }
+    }
+
+    fn intersect(&self, other: &Self) -> Option<Self> {
+        Some(Self {
+            spans: span::intersect(&self.spans, &other.spans)?,
+            absent: self.absent && other.absent,
+        })
+    }
+
+    fn union(&self, other: &Self) -> Option<Self> {
+        let mut spans = self.spans.clone();
+        spans.extend(other.spans.iter().cloned());
+        Some(Self {
+            spans: span::normalize(spans)?,
+            absent: self.absent || other.absent,
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.absent && self.spans.is_empty()
+    }
+
+    // Returns true if the set is known to contain a value, whatever the type of the prop is.
+    fn has_witness(&self) -> bool {
+        self.absent || self.spans.iter().any(has_witness)
+    }
+}
+
+#[derive(Clone)]
+enum Literal {
+    // The value of the prop at the given index, or of the matched field itself.
+    Value(Option<usize>, ValueSet),
+    // A condition on a nested document (or any document of a nested array).
+    Nested(usize, Condition, bool),
+    Opaque,
+}
+
+type Conjunction = Vec<Literal>;
+
+fn to_dnf(condition: &Condition, negated: bool) -> Option<Vec<Conjunction>> {
+    match condition {
+        Condition::Op(_, _) => Some(vec![vec![value_literal(None, condition, negated)]]),
+        Condition::Between(_) => Some(vec![vec![value_literal(None, condition, negated)]]),
+        Condition::FieldOp(_, _)
+        | Condition::ParamOp(_, _)
+        | Condition::ExprOp(_, _, _)
+        | Condition::TimeOp(_, _)
+        | Condition::ArrayOp(_, _)
+        | Condition::Meta(_, _)
+        | Condition::JsonPath(_, _)
+        | Condition::JsonExists(_)
+        | Condition::Fuzzy(_)
+        | Condition::Within(_)
+        | Condition::Similar(_)
+        | Condition::Custom(_, _) => Some(vec![vec![Literal::Opaque]]),
+        Condition::Prop(index, inner) => {
+            if has_prop(inner) {
+                Some(vec![vec![Literal::Nested(
+                    *index,
+                    inner.as_ref().clone(),
+                    negated,
+                )]])
+            } else {
+                Some(vec![vec![value_literal(Some(*index), inner, negated)]])
+            }
+        }
+        Condition::CompoundOp(CompoundOp::NOT, conditions) => match conditions.first() {
+            Some(cond) => to_dnf(cond, !negated),
+            None => Some(vec![vec![Literal::Opaque]]),
+        },
+        Condition::CompoundOp(op, conditions) => {
+            let is_conjunction = (*op == CompoundOp::AND) != negated;
+            if is_conjunction {
+                let mut result: Vec<Conjunction> = vec![Vec::new()];
+                for cond in conditions {
+                    let dnf = to_dnf(cond, negated)?;
+                    if result.len() * dnf.len() > MAX_CONJUNCTIONS {
+                        return None;
+                    }
+                    let mut product = Vec::with_capacity(result.len() * dnf.len());
+                    for left in &result {
+                        for right in &dnf {
+                            let mut conjunction = Vec::with_capacity(left.len() + right.len());
+                            conjunction.extend(left.iter().cloned());
+                            conjunction.extend(right.iter().cloned());
+                            product.push(conjunction);
+                        }
+                    }
+                    result = product;
+                }
+                Some(result)
+            } else {
+                let mut result = Vec::new();
+                for cond in conditions {
+                    result.extend(to_dnf(cond, negated)?);
+                    if result.len() > MAX_CONJUNCTIONS {
+                        return None;
+                    }
+                }
+                Some(result)
+            }
+        }
+    }
+}
+
+fn has_prop(condition: &Condition) -> bool {
+    match condition {
+        Condition::Op(_, _)
+        | Condition::FieldOp(_, _)
+        | Condition::ParamOp(_, _)
+        | Condition::ExprOp(_, _, _)
+        | Condition::Between(_)
+        | Condition::TimeOp(_, _)
+        | Condition::ArrayOp(_, _)
+        | Condition::Meta(_, _)
+        | Condition::JsonPath(_, _)
+        | Condition::JsonExists(_)
+        | Condition::Fuzzy(_)
+        | Condition::Within(_)
+        | Condition::Similar(_)
+        | Condition::Custom(_, _) => false,
+        Condition::Prop(_, _) => true,
+        Condition::CompoundOp(_, conditions) => conditions.iter().any(has_prop),
+    }
+}
+
+fn value_literal(index: Option<usize>, condition: &Condition, negated: bool) -> Literal {
+    match value_set(condition) {
+        Some(values) if negated => Literal::Value(index, values.complement()),
+        Some(values) => Literal::Value(index, values),
+        None => Literal::Opaque,
+    }
+}
+
+// Returns the set of values matching a condition without props.
+fn value_set(condition: &Condition) -> Option<ValueSet> {
+    match condition {
+        Condition::Op(op, target) => op_value_set(op, target),
+        Condition::Between(range) => Some(ValueSet {
+            spans: span::range_spans(range)?,
+            absent: false,
+        }),
+        Condition::Prop(_, _)
+        | Condition::FieldOp(_, _)
+        | Condition::ParamOp(_, _)
+        | Condition::ExprOp(_, _, _)
+        | Condition::TimeOp(_, _)
+        | Condition::ArrayOp(_, _)
+        | Condition::Meta(_, _)
+        | Condition::JsonPath(_, _)
+        | Condition::JsonExists(_)
+        | Condition::Fuzzy(_)
+        | Condition::Within(_)
+        | Condition::Similar(_)
+        | Condition::Custom(_, _) => None,
+        Condition::CompoundOp(CompoundOp::AND, conditions) => {
+            let mut values = ValueSet::full();
+            for cond in conditions {
+                values = values.intersect(&value_set(cond)?)?;
+            }
+            Some(values)
+        }
+        Condition::CompoundOp(CompoundOp::OR, conditions) => {
+            let mut values = ValueSet::empty();
+            for cond in conditions {
+                values = values.union(&value_set(cond)?)?;
+            }
+            Some(values)
+        }
+        Condition::CompoundOp(CompoundOp::NOT, conditions) => {
+            Some(value_set(conditions.first()?)?.complement())
+        }
+    }
+}
+
+fn op_value_set(op: &Op, target: &Field) -> Option<ValueSet> {
+    let negated_op = match op {
+        Op::NE => Op::EQ,
+        Op::NIN => Op::IN,
+        Op::NLIKE => Op::LIKE,
+        _ => {
+            return Some(ValueSet {
+                spans: span::op_spans(op, target)?,
+                absent: false,
+            })
+        }
+    };
+    let spans = span::op_spans(&negated_op, target)?;
+    Some(ValueSet {
+        spans: span::complement(&spans),
+        absent: false,
+    })
+}
+
+fn conjunction_satisfiable(conjunction: Conjunction) -> Answer {
+    let mut values: HashMap<Option<usize>, ValueSet> = HashMap::new();
+    let mut nested: HashMap<usize, (Vec<Condition>, Vec<Condition>)> = HashMap::new();
+    let mut has_opaque = false;
+
+    for literal in conjunction {
+        match literal {
+            Literal::Value(index, set) => {
+                let set = match values.get(&index) {
+                    Some(current) => match current.intersect(&set) {
+                        Some(set) => set,
+                        None => {
+                            has_opaque = true;
+                            continue;
+                        }
+                    },
+                    None => set,
+                };
+                if set.is_empty() {
+                    return Answer::No;
+                }
+                values.insert(index, set);
+            }
+            Literal::Nested(index, cond, negated) => {
+                let entry = nested.entry(index).or_default();
+                if negated {
+                    entry.1.push(cond);
+                } else {
+                    entry.0.push(cond);
+                }
+            }
+            Literal::Opaque => has_opaque = true,
+        }
+    }
+
+    let mut answer = if has_opaque || !values.values().all(ValueSet::has_witness) {
+        Answer::Unknown
+    } else {
+        Answer::Yes
+    };
+
+    for (positives, negatives) in nested.values() {
+        for positive in positives {
+            if satisfiable(positive) == Answer::No {
+                return Answer::No;
+            }
+            // a nested document matching `positive` would also match a negated condition
+            if negatives
+                .iter()
+                .any(|negative| implies(positive, negative) == Answer::Yes)
+            {
+                return Answer::No;
+            }
+        }
+        if answer == Answer::Yes {
+            // a single nested document matching everything is a valid witness for both
+            // nested documents and nested arrays
+            let mut conditions = positives.clone();
+            conditions.extend(
+                negatives
+                    .iter()
+                    .map(|cond| Condition::CompoundOp(CompoundOp::NOT, vec![cond.clone()])),
+            );
+            if satisfiable(&Condition::CompoundOp(CompoundOp::AND, conditions)) != Answer::Yes {
+                answer = Answer::Unknown;
+            }
+        }
+    }
+    answer
+}
+
+// Returns true if the span is known to contain a value, whatever the type of the prop is.
+fn has_witness(span: &Span) -> bool {
+    let (start, end) = match (&span.start, &span.end) {
+        (Bound::Included(_), _) | (_, Bound::Included(_)) => return true,
+        (Bound::Unbounded, Bound::Unbounded) => return true,
+        (start, end) => (bound_value(start), bound_value(end)),
+    };
+    match (start, end) {
+        (_, Some(Field::Null)) => false,
+        (Some(Field::Null), None) => true,
+        (Some(Field::Int(start)), Some(Field::Int(end))) => *end as i128 - *start as i128 > 1,
+        (start, Some(Field::Int(_) | Field::Float(_)))
+        | (start @ Some(Field::Int(_) | Field::Float(_)), None) => {
+            let low = match start {
+                Some(Field::Int(v)) => *v as f64,
+                Some(Field::Float(v)) => *v,
+                _ => f64::NEG_INFINITY,
+            };
+            let high = match end {
+                Some(Field::Int(v)) => *v as f64,
+                Some(Field::Float(v)) => *v,
+                _ => f64::INFINITY,
+            };
+            // look for an integer strictly within the bounds, as the prop could be an Int
+            let candidate = (low.floor() + 1.0).max(i64::MIN as f64);
+            candidate < high && candidate <= i64::MAX as f64
+        }
+        (Some(Field::DateTime(start)), Some(Field::DateTime(end))) => {
+            *end - *start > Duration::nanoseconds(1)
+        }
+        (_, Some(Field::DateTime(_))) | (Some(Field::DateTime(_)), None) => true,
+        (Some(Field::String(start)), Some(Field::String(end))) => format!("{}\u{0}", start) < *end,
+        (_, Some(Field::String(end))) => !end.is_empty(),
+        (Some(Field::String(_)), None) => true,
+        (Some(Field::Null), Some(Field::Bool(end))) => *end,
+        (Some(Field::Bool(start)), None) => !*start,
+        _ => false,
+    }
+}
+
+fn bound_value(bound: &Bound) -> Option<&Field> {
+    match bound {
+        Bound::Unbounded => None,
+        Bound::Included(value) | Bound::Excluded(value) => Some(value),
+    }
+}
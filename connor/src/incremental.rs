@@ -0,0 +1,306 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::mem;
+
+use core::doc::{Doc, Field};
+
+use crate::cond::{self, CompoundOp, Condition, Target};
+use crate::error;
+use crate::subscription::SubscriptionId;
+
+// Transition is a change in whether a subscription matches the document.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transition {
+    Started(SubscriptionId),
+    Stopped(SubscriptionId),
+}
+
+// DocMatches caches the results of the sub-conditions of every subscription for one document,
+// so that updating some fields re-evaluates only the `Prop` branches reading them.
+//
+// The branches of AND, OR and NOT are cached separately. A branch depends on the single prop
+// it applies to, unless it references other fields or relative times, in which case it is
+// re-evaluated on every update.
+#[derive(Debug)]
+pub struct DocMatches {
+    doc: Doc,
+    subscriptions: BTreeMap<SubscriptionId, Subscription>,
+}
+
+#[derive(Debug)]
+struct Subscription {
+    condition: Condition,
+    node: Node,
+}
+
+// Node is a sub-condition along with its cached outcome, which is `None` if evaluating it
+// failed. Errors are kept as outcomes, since they only surface if no earlier branch of an AND
+// or OR decides the result first.
+#[derive(Clone, Debug)]
+enum Node {
+    Branch {
+        condition: Condition,
+        deps: Deps,
+        outcome: Option<bool>,
+    },
+    Compound {
+        op: CompoundOp,
+        children: Vec<Node>,
+        deps: Deps,
+        outcome: Option<bool>,
+    },
+}
+
+// Deps are the document props a node reads. `None` means the node may read any of them.
+type Deps = Option<BTreeSet<usize>>;
+
+impl DocMatches {
+    pub fn new(doc: Doc) -> Self {
+        Self {
+            doc,
+            subscriptions: BTreeMap::new(),
+        }
+    }
+
+    pub fn doc(&self) -> &Doc {
+        &self.doc
+    }
+
+    // Evaluates the condition of the subscription and caches its results, replacing any
+    // subscription with the same ID. Returns whether the document matches.
+    pub fn add(&mut self, id: SubscriptionId, condition: Condition) -> error::Result<bool> {
+        let doc_field = Field::Doc(self.doc.clone());
+        let node = Node::build(&condition, &doc_field);
+        let Some(is_match) = node.outcome() else {
+            return Err(evaluation_error(&condition, &doc_field));
+        };
+        self.subscriptions
+            .insert(id, Subscription { condition, node });
+        Ok(is_match)
+    }
+
+    // Drops the cached results of the subscription. Returns false if there is no subscription
+    // with the ID.
+    pub fn remove(&mut self, id: SubscriptionId) -> bool {
+        self.subscriptions.remove(&id).is_some()
+    }
+
+    // Returns whether the document matches the subscription, or `None` if there is no
+    // subscription with the ID.
+    pub fn is_match(&self, id: SubscriptionId) -> Option<bool> {
+        self.subscriptions
+            .get(&id)
+            .map(|subscription| subscription.node.outcome() == Some(true))
+    }
+
+    // Returns the IDs of the subscriptions matching the document, in ascending order.
+    pub fn matching(&self) -> Vec<SubscriptionId> {
+        self.subscriptions
+            .iter()
+            .filter(|(_, subscription)| subscription.node.outcome() == Some(true))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    // Sets the fields at the given prop indexes to their new values and re-evaluates the
+    // branches reading them. Returns the subscriptions that started or stopped matching, in
+    // ascending order of ID. If any subscription fails to evaluate, the document and the
+    // cached results are left unchanged.
+    pub fn update(&mut self, changes: Vec<(usize, Field)>) -> error::Result<Vec<Transition>> {
+        if changes
+            .iter()
+            .any(|(index, _)| *index >= self.doc.fields.len())
+        {
+            return Err(error::Error::OutOfBoundPropIndex);
+        }
+        let changed = changes
+            .iter()
+            .map(|(index, _)| *index)
+            .collect::<BTreeSet<_>>();
+        let old_values = changes
+            .into_iter()
+            .map(|(index, value)| (index, mem::replace(&mut self.doc.fields[index], value)))
+            .collect::<Vec<_>>();
+
+        let doc_field = Field::Doc(self.doc.clone());
+        let mut updated = Vec::new();
+        for (id, subscription) in &self.subscriptions {
+            if !subscription.node.reads(&changed) {
+                continue;
+            }
+            let mut node = subscription.node.clone();
+            node.refresh(&changed, &doc_field);
+            if node.outcome().is_none() {
+                let err = evaluation_error(&subscription.condition, &doc_field);
+                for (index, value) in old_values.into_iter().rev() {
+                    self.doc.fields[index] = value;
+                }
+                return Err(err);
+            }
+            updated.push((*id, node));
+        }
+
+        let mut transitions = Vec::new();
+        for (id, node) in updated {
+            let subscription = self.subscriptions.get_mut(&id).unwrap();
+            let was_match = subscription.node.outcome() == Some(true);
+            let is_match = node.outcome() == Some(true);
+            subscription.node = node;
+            match (was_match, is_match) {
+                (false, true) => transitions.push(Transition::Started(id)),
+                (true, false) => transitions.push(Transition::Stopped(id)),
+                _ => {}
+            }
+        }
+        Ok(transitions)
+    }
+}
+
+impl Node {
+    fn build(condition: &Condition, doc_field: &Field) -> Node {
+        match condition {
+            Condition::CompoundOp(op, conditions) => {
+                let children = conditions
+                    .iter()
+                    .map(|cond| Node::build(cond, doc_field))
+                    .collect::<Vec<_>>();
+                let deps = children
+                    .iter()
+                    .try_fold(BTreeSet::new(), |mut deps, child| {
+                        deps.extend(child.deps().as_ref()?);
+                        Some(deps)
+                    });
+                Node::Compound {
+                    outcome: combine(op, &children),
+                    op: op.clone(),
+                    children,
+                    deps,
+                }
+            }
+            _ => Node::Branch {
+                condition: condition.clone(),
+                deps: deps(condition),
+                outcome: cond::match_conditions(condition, doc_field).ok(),
+            },
+        }
+    }
+
+    fn outcome(&self) -> Option<bool> {
+        match self {
+            Node::Branch { outcome, .. } | Node::Compound { outcome, .. } => *outcome,
+        }
+    }
+
+    fn deps(&self) -> &Deps {
+        match self {
+            Node::Branch { deps, .. } | Node::Compound { deps, .. } => deps,
+        }
+    }
+
+    // Returns true if the node reads any of the changed props.
+    fn reads(&self, changed: &BTreeSet<usize>) -> bool {
+        match self.deps() {
+            Some(deps) => !deps.is_disjoint(changed),
+            None => true,
+        }
+    }
+
+    // Re-evaluates the branches reading any of the changed props.
+    fn refresh(&mut self, changed: &BTreeSet<usize>, doc_field: &Field) {
+        if !self.reads(changed) {
+            return;
+        }
+        match self {
+            Node::Branch {
+                condition, outcome, ..
+            } => *outcome = cond::match_conditions(condition, doc_field).ok(),
+            Node::Compound {
+                op,
+                children,
+                outcome,
+                ..
+            } => {
+                for child in children.iter_mut() {
+                    child.refresh(changed, doc_field);
+                }
+                *outcome = combine(op, children);
+            }
+        }
+    }
+}
+
+// Combines the outcomes of the children the way `match_conditions` evaluates them: in order,
+// stopping at the first child that decides the result or fails.
+fn combine(op: &CompoundOp, children: &[Node]) -> Option<bool> {
+    match op {
+        CompoundOp::AND => {
+            for child in children {
+                if !child.outcome()? {
+                    return Some(false);
+                }
+            }
+            Some(true)
+        }
+        CompoundOp::OR => {
+            for child in children {
+                if child.outcome()? {
+                    return Some(true);
+                }
+            }
+            Some(false)
+        }
+        CompoundOp::NOT => children.first()?.outcome().map(|outcome| !outcome),
+    }
+}
+
+// Returns the props read by a branch, or `None` if it may read any of them.
+fn deps(condition: &Condition) -> Deps {
+    match condition {
+        Condition::Prop(index, inner) if is_self_contained(inner) => Some(BTreeSet::from([*index])),
+        // updates change fields only, never the metadata of the document
+        Condition::Meta(_, inner) if is_self_contained(inner) => Some(BTreeSet::new()),
+        _ => None,
+    }
+}
+
+// Returns true if the result of the condition depends only on the value it is matched against,
+// so not on other fields of the document or on the current time.
+fn is_self_contained(condition: &Condition) -> bool {
+    match condition {
+        Condition::Prop(_, inner) | Condition::Meta(_, inner) | Condition::JsonPath(_, inner) => {
+            is_self_contained(inner)
+        }
+        Condition::CompoundOp(_, conditions) => conditions.iter().all(is_self_contained),
+        Condition::ExprOp(_, left, right) => {
+            left.field_refs().is_empty() && right.field_refs().is_empty()
+        }
+        Condition::Between(range) => {
+            matches!(range.start, Target::Value(_)) && matches!(range.end, Target::Value(_))
+        }
+        Condition::FieldOp(_, _) | Condition::ParamOp(_, _) | Condition::TimeOp(_, _) => false,
+        Condition::JsonExists(_)
+        | Condition::Op(_, _)
+        | Condition::ArrayOp(_, _)
+        | Condition::Fuzzy(_)
+        | Condition::Within(_)
+        | Condition::Similar(_)
+        | Condition::Custom(_, _) => true,
+    }
+}
+
+// Returns the error raised by matching the condition, which is known to fail.
+fn evaluation_error(condition: &Condition, doc_field: &Field) -> error::Error {
+    match cond::match_conditions(condition, doc_field) {
+        Err(err) => err,
+        Ok(_) => error::Error::InvalidCondition("inconsistent evaluation".to_string()),
+    }
+}
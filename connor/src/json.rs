@@ -0,0 +1,124 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use std::fmt;
+use std::str::FromStr;
+
+use core::doc::Field;
+use core::json::Json;
+
+use crate::error;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathSegment {
+    // A key of an object.
+    Key(String),
+    // An index of an array.
+    Index(usize),
+}
+
+// JsonPath addresses a value nested in a JSON value, such as `meta.tags[0]`. Keys are separated
+// by dots, array indexes are given in brackets, and keys that aren't plain words can be quoted
+// in brackets, such as `meta["first name"]`. The empty path addresses the whole value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonPath {
+    pub segments: Vec<PathSegment>,
+}
+
+impl JsonPath {
+    // Returns the value at the path, or `None` if the path doesn't exist.
+    pub fn get<'a>(&self, json: &'a Json) -> Option<&'a Json> {
+        self.segments
+            .iter()
+            .try_fold(json, |json, segment| match (segment, json) {
+                (PathSegment::Key(key), Json::Object(map)) => map.get(key),
+                (PathSegment::Index(index), Json::Array(arr)) => arr.get(*index),
+                _ => None,
+            })
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = error::Error;
+
+    fn from_str(path: &str) -> error::Result<Self> {
+        let invalid = || error::Error::InvalidJsonPath(path.to_string());
+        let mut segments = Vec::new();
+        let mut rest = path;
+        while !rest.is_empty() {
+            if let Some(quoted) = rest.strip_prefix("[\"") {
+                let end = quoted.find("\"]").ok_or_else(invalid)?;
+                segments.push(PathSegment::Key(quoted[..end].to_string()));
+                rest = &quoted[end + 2..];
+            } else if let Some(bracketed) = rest.strip_prefix('[') {
+                let end = bracketed.find(']').ok_or_else(invalid)?;
+                let index = bracketed[..end].parse().map_err(|_| invalid())?;
+                segments.push(PathSegment::Index(index));
+                rest = &bracketed[end + 1..];
+            } else {
+                // keys after the first one are separated by dots
+                if !segments.is_empty() {
+                    rest = rest.strip_prefix('.').ok_or_else(invalid)?;
+                }
+                let end = rest.find(|c| !is_word_char(c)).unwrap_or(rest.len());
+                if end == 0 {
+                    return Err(invalid());
+                }
+                segments.push(PathSegment::Key(rest[..end].to_string()));
+                rest = &rest[end..];
+            }
+        }
+        Ok(Self { segments })
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) if !key.is_empty() && key.chars().all(is_word_char) => {
+                    if i > 0 {
+                        write!(f, ".")?;
+                    }
+                    write!(f, "{}", key)?;
+                }
+                PathSegment::Key(key) => write!(f, "[\"{}\"]", key)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+// Returns the value at the path of a Json field, or `None` if the field is not JSON or the
+// path doesn't exist.
+pub(crate) fn value_at(path: &JsonPath, doc_field: &Field) -> Option<Field> {
+    match doc_field {
+        Field::Json(json) => path.get(json).map(to_field),
+        _ => None,
+    }
+}
+
+// Converts scalar JSON values to the fields operators compare them as. Arrays and objects stay
+// JSON.
+pub(crate) fn to_field(json: &Json) -> Field {
+    match json {
+        Json::Null => Field::Null,
+        Json::Bool(val) => Field::Bool(*val),
+        Json::Int(val) => Field::Int(*val),
+        Json::Float(val) => Field::Float(*val),
+        Json::String(val) => Field::String(val.clone()),
+        Json::Array(_) | Json::Object(_) => Field::Json(json.clone()),
+    }
+}
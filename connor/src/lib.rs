@@ -10,10 +10,30 @@
 
 pub mod cond;
 pub mod error;
+pub mod expr;
+pub mod fuzzy;
+pub mod geo;
+pub mod hnsw;
+pub mod implication;
+pub mod incremental;
+pub mod json;
+pub mod limits;
+pub mod operator;
+pub mod parallel;
+pub mod partial;
+pub mod prepared;
+pub mod span;
+pub mod subscription;
+pub mod text;
+pub mod time;
+pub mod vector;
 
+mod field_ord;
+mod op_array;
 mod op_eq;
 mod op_ge;
 mod op_gt;
 mod op_ilike;
 mod op_in;
 mod op_like;
+mod op_prefix;
@@ -0,0 +1,295 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use std::fmt;
+
+use core::doc::{Doc, Field};
+use core::json::Json;
+
+use crate::cond::{Condition, Target};
+use crate::error;
+use crate::expr::Expr;
+use crate::geo::Shape;
+
+const DEFAULT_MAX_CONDITION_DEPTH: usize = 32;
+const DEFAULT_MAX_CONDITION_NODES: usize = 10_000;
+const DEFAULT_MAX_ARRAY_LEN: usize = 100_000;
+const DEFAULT_MAX_DOC_DEPTH: usize = 16;
+const DEFAULT_MAX_STRING_LEN: usize = 1 << 20;
+
+// Limit is one of the bounds checked by `Limits`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Limit {
+    // The nesting of conditions and expressions.
+    ConditionDepth,
+    // The number of conditions and expressions.
+    ConditionNodes,
+    // The length of arrays, vectors, polygons, IN lists and the fields of a document.
+    ArrayLength,
+    // The nesting of documents and JSON values.
+    DocDepth,
+    // The length in bytes of strings and blobs.
+    StringLength,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Limit::ConditionDepth => write!(f, "Condition depth"),
+            Limit::ConditionNodes => write!(f, "Condition node count"),
+            Limit::ArrayLength => write!(f, "Array length"),
+            Limit::DocDepth => write!(f, "Document depth"),
+            Limit::StringLength => write!(f, "String length"),
+        }
+    }
+}
+
+// Limits bound the size of conditions and documents received from untrusted clients.
+//
+// Matching recurses once per nested condition, document and JSON value, so checking the
+// inputs before matching them keeps a crafted input from exhausting the stack. The checks
+// themselves stop descending as soon as a limit is exceeded.
+#[derive(Clone, Debug)]
+pub struct Limits {
+    max_condition_depth: usize,
+    max_condition_nodes: usize,
+    max_array_len: usize,
+    max_doc_depth: usize,
+    max_string_len: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_condition_depth: DEFAULT_MAX_CONDITION_DEPTH,
+            max_condition_nodes: DEFAULT_MAX_CONDITION_NODES,
+            max_array_len: DEFAULT_MAX_ARRAY_LEN,
+            max_doc_depth: DEFAULT_MAX_DOC_DEPTH,
+            max_string_len: DEFAULT_MAX_STRING_LEN,
+        }
+    }
+}
+
+impl Limits {
+    pub fn with_max_condition_depth(mut self, max: usize) -> Self {
+        self.max_condition_depth = max;
+        self
+    }
+
+    pub fn with_max_condition_nodes(mut self, max: usize) -> Self {
+        self.max_condition_nodes = max;
+        self
+    }
+
+    pub fn with_max_array_len(mut self, max: usize) -> Self {
+        self.max_array_len = max;
+        self
+    }
+
+    pub fn with_max_doc_depth(mut self, max: usize) -> Self {
+        self.max_doc_depth = max;
+        self
+    }
+
+    pub fn with_max_string_len(mut self, max: usize) -> Self {
+        self.max_string_len = max;
+        self
+    }
+
+    // Checks the condition, including the values it compares against. The top-level
+    // condition has a depth of 1.
+    pub fn check_condition(&self, condition: &Condition) -> error::Result<()> {
+        let mut nodes = 0;
+        self.visit_condition(condition, 1, &mut nodes)
+    }
+
+    // Checks the document. The top-level document has a depth of 1.
+    pub fn check_doc(&self, doc: &Doc) -> error::Result<()> {
+        self.visit_doc(doc, 1)
+    }
+
+    // Checks the field as a value outside of any document.
+    pub fn check_field(&self, field: &Field) -> error::Result<()> {
+        self.visit_field(field, 0)
+    }
+
+    fn ensure(&self, limit: Limit, value: usize) -> error::Result<()> {
+        let max = match limit {
+            Limit::ConditionDepth => self.max_condition_depth,
+            Limit::ConditionNodes => self.max_condition_nodes,
+            Limit::ArrayLength => self.max_array_len,
+            Limit::DocDepth => self.max_doc_depth,
+            Limit::StringLength => self.max_string_len,
+        };
+        if value > max {
+            return Err(error::Error::LimitExceeded(limit, max));
+        }
+        Ok(())
+    }
+
+    fn visit_condition(
+        &self,
+        condition: &Condition,
+        depth: usize,
+        nodes: &mut usize,
+    ) -> error::Result<()> {
+        self.ensure(Limit::ConditionDepth, depth)?;
+        *nodes += 1;
+        self.ensure(Limit::ConditionNodes, *nodes)?;
+        match condition {
+            Condition::Prop(_, inner) | Condition::Meta(_, inner) => {
+                self.visit_condition(inner, depth + 1, nodes)
+            }
+            Condition::JsonPath(path, inner) => {
+                self.ensure(Limit::ArrayLength, path.segments.len())?;
+                self.visit_condition(inner, depth + 1, nodes)
+            }
+            Condition::JsonExists(path) => self.ensure(Limit::ArrayLength, path.segments.len()),
+            Condition::Op(_, target) | Condition::ArrayOp(_, target) => self.visit_field(target, 0),
+            Condition::FieldOp(_, field_ref) => {
+                self.ensure(Limit::ArrayLength, field_ref.path.len())
+            }
+            Condition::ParamOp(_, name) => self.ensure(Limit::StringLength, name.len()),
+            Condition::ExprOp(_, left, right) => {
+                self.visit_expr(left, depth + 1, nodes)?;
+                self.visit_expr(right, depth + 1, nodes)
+            }
+            Condition::Between(range) => {
+                for target in [&range.start, &range.end] {
+                    match target {
+                        Target::Value(value) => self.visit_field(value, 0)?,
+                        Target::Time(time) => {
+                            self.ensure(Limit::ArrayLength, time.offsets.len())?
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Condition::TimeOp(_, time) => self.ensure(Limit::ArrayLength, time.offsets.len()),
+            Condition::Fuzzy(fuzzy) => self.ensure(Limit::StringLength, fuzzy.value.len()),
+            Condition::Within(Shape::Polygon(vertices)) => {
+                self.ensure(Limit::ArrayLength, vertices.len())
+            }
+            Condition::Within(_) => Ok(()),
+            Condition::Similar(threshold) => {
+                self.ensure(Limit::ArrayLength, threshold.vector.len())
+            }
+            Condition::Custom(name, target) => {
+                self.ensure(Limit::StringLength, name.len())?;
+                self.visit_field(target, 0)
+            }
+            Condition::CompoundOp(_, conditions) => {
+                self.ensure(Limit::ArrayLength, conditions.len())?;
+                for cond in conditions {
+                    self.visit_condition(cond, depth + 1, nodes)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn visit_expr(&self, expr: &Expr, depth: usize, nodes: &mut usize) -> error::Result<()> {
+        self.ensure(Limit::ConditionDepth, depth)?;
+        *nodes += 1;
+        self.ensure(Limit::ConditionNodes, *nodes)?;
+        match expr {
+            Expr::Literal(value) => self.visit_field(value, 0),
+            Expr::Value => Ok(()),
+            Expr::Field(field_ref) => self.ensure(Limit::ArrayLength, field_ref.path.len()),
+            Expr::Arith(_, left, right) => {
+                self.visit_expr(left, depth + 1, nodes)?;
+                self.visit_expr(right, depth + 1, nodes)
+            }
+            Expr::Call(_, arg) => self.visit_expr(arg, depth + 1, nodes),
+        }
+    }
+
+    fn visit_doc(&self, doc: &Doc, depth: usize) -> error::Result<()> {
+        self.ensure(Limit::DocDepth, depth)?;
+        self.ensure(Limit::ArrayLength, doc.fields.len())?;
+        self.ensure(Limit::StringLength, doc.schema_version_id.len())?;
+        for field in &doc.fields {
+            self.visit_field(field, depth)?;
+        }
+        Ok(())
+    }
+
+    // Checks a field of a document at the given depth, which is 0 outside of any document.
+    fn visit_field(&self, field: &Field, depth: usize) -> error::Result<()> {
+        match field {
+            Field::String(val) | Field::OptionalString(Some(val)) => {
+                self.ensure(Limit::StringLength, val.len())
+            }
+            Field::Blob(val) | Field::OptionalBlob(Some(val)) => {
+                self.ensure(Limit::StringLength, val.len())
+            }
+            Field::Vector(val) | Field::OptionalVector(Some(val)) => {
+                self.ensure(Limit::ArrayLength, val.len())
+            }
+            Field::Json(json) => self.visit_json(json, depth + 1),
+            Field::Doc(doc) => self.visit_doc(doc, depth + 1),
+            Field::StringArray(arr) => {
+                self.ensure(Limit::ArrayLength, arr.len())?;
+                arr.iter()
+                    .try_for_each(|val| self.ensure(Limit::StringLength, val.len()))
+            }
+            Field::OptionalStringArray(arr) => {
+                self.ensure(Limit::ArrayLength, arr.len())?;
+                arr.iter()
+                    .flatten()
+                    .try_for_each(|val| self.ensure(Limit::StringLength, val.len()))
+            }
+            Field::BlobArray(arr) => {
+                self.ensure(Limit::ArrayLength, arr.len())?;
+                arr.iter()
+                    .try_for_each(|val| self.ensure(Limit::StringLength, val.len()))
+            }
+            Field::DocArray(docs) => {
+                self.ensure(Limit::ArrayLength, docs.len())?;
+                docs.iter()
+                    .try_for_each(|doc| self.visit_doc(doc, depth + 1))
+            }
+            Field::BoolArray(arr) => self.ensure(Limit::ArrayLength, arr.len()),
+            Field::IntArray(arr) => self.ensure(Limit::ArrayLength, arr.len()),
+            Field::FloatArray(arr) => self.ensure(Limit::ArrayLength, arr.len()),
+            Field::DecimalArray(arr) => self.ensure(Limit::ArrayLength, arr.len()),
+            Field::BigIntArray(arr) => self.ensure(Limit::ArrayLength, arr.len()),
+            Field::DateTimeArray(arr) => self.ensure(Limit::ArrayLength, arr.len()),
+            Field::GeoPointArray(arr) => self.ensure(Limit::ArrayLength, arr.len()),
+            Field::OptionalBoolArray(arr) => self.ensure(Limit::ArrayLength, arr.len()),
+            Field::OptionalIntArray(arr) => self.ensure(Limit::ArrayLength, arr.len()),
+            Field::OptionalFloatArray(arr) => self.ensure(Limit::ArrayLength, arr.len()),
+            Field::OptionalDateTimeArray(arr) => self.ensure(Limit::ArrayLength, arr.len()),
+            Field::OptionalGeoPointArray(arr) => self.ensure(Limit::ArrayLength, arr.len()),
+            _ => Ok(()),
+        }
+    }
+
+    fn visit_json(&self, json: &Json, depth: usize) -> error::Result<()> {
+        self.ensure(Limit::DocDepth, depth)?;
+        match json {
+            Json::String(val) => self.ensure(Limit::StringLength, val.len()),
+            Json::Array(arr) => {
+                self.ensure(Limit::ArrayLength, arr.len())?;
+                arr.iter()
+                    .try_for_each(|val| self.visit_json(val, depth + 1))
+            }
+            Json::Object(map) => {
+                self.ensure(Limit::ArrayLength, map.len())?;
+                for (key, val) in map {
+                    self.ensure(Limit::StringLength, key.len())?;
+                    self.visit_json(val, depth + 1)?;
+                }
+                Ok(())
+            }
+            Json::Null | Json::Bool(_) | Json::Int(_) | Json::Float(_) => Ok(()),
+        }
+    }
+}
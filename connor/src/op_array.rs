@@ -0,0 +1,101 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use std::cmp::Ordering;
+
+use core::doc::Field;
+use core::json::Json;
+
+use crate::cond::ArrayOp;
+use crate::field_ord;
+use crate::json;
+
+// Null elements of optional arrays only equal other nulls, so `CONTAINS [null]` matches
+// arrays holding a null element. Fields that are not arrays never match.
+pub fn handle(op: &ArrayOp, target: &Field, doc_field: &Field) -> bool {
+    let values = match elements(doc_field) {
+        Some(values) => values,
+        None => return false,
+    };
+    if let Some(len) = len_op(op) {
+        return match target {
+            Field::Int(target_len) => len(values.len() as i64, *target_len),
+            _ => false,
+        };
+    }
+    let targets = match elements(target) {
+        Some(targets) => targets,
+        None => return false,
+    };
+    match op {
+        ArrayOp::CONTAINS => targets.iter().all(|target| has_element(&values, target)),
+        ArrayOp::OVERLAPS => targets.iter().any(|target| has_element(&values, target)),
+        ArrayOp::SUBSET_OF => values.iter().all(|value| has_element(&targets, value)),
+        _ => false,
+    }
+}
+
+fn len_op(op: &ArrayOp) -> Option<fn(i64, i64) -> bool> {
+    match op {
+        ArrayOp::LEN_EQ => Some(|len, target| len == target),
+        ArrayOp::LEN_NE => Some(|len, target| len != target),
+        ArrayOp::LEN_GT => Some(|len, target| len > target),
+        ArrayOp::LEN_GE => Some(|len, target| len >= target),
+        ArrayOp::LEN_LT => Some(|len, target| len < target),
+        ArrayOp::LEN_LE => Some(|len, target| len <= target),
+        ArrayOp::CONTAINS | ArrayOp::OVERLAPS | ArrayOp::SUBSET_OF => None,
+    }
+}
+
+fn has_element(values: &[Field], element: &Field) -> bool {
+    values.iter().any(|value| match (value, element) {
+        // geo points are not ordered, but they are equal if their coordinates are
+        (Field::GeoPoint(a), Field::GeoPoint(b)) => a == b,
+        _ => field_ord::compare(value, element) == Some(Ordering::Equal),
+    })
+}
+
+// Returns the elements of a scalar array, with null for the missing ones of optional arrays.
+pub(crate) fn elements(field: &Field) -> Option<Vec<Field>> {
+    fn opt<T>(val: &Option<T>, f: impl Fn(&T) -> Field) -> Field {
+        val.as_ref().map_or(Field::Null, f)
+    }
+    let values = match field {
+        Field::BoolArray(arr) => arr.iter().map(|v| Field::Bool(*v)).collect(),
+        Field::IntArray(arr) => arr.iter().map(|v| Field::Int(*v)).collect(),
+        Field::FloatArray(arr) => arr.iter().map(|v| Field::Float(*v)).collect(),
+        Field::DecimalArray(arr) => arr.iter().map(|v| Field::Decimal(v.clone())).collect(),
+        Field::BigIntArray(arr) => arr.iter().map(|v| Field::BigInt(v.clone())).collect(),
+        Field::StringArray(arr) => arr.iter().map(|v| Field::String(v.clone())).collect(),
+        Field::DateTimeArray(arr) => arr.iter().map(|v| Field::DateTime(*v)).collect(),
+        Field::BlobArray(arr) => arr.iter().map(|v| Field::Blob(v.clone())).collect(),
+        Field::GeoPointArray(arr) => arr.iter().map(|v| Field::GeoPoint(*v)).collect(),
+        Field::OptionalBoolArray(arr) => arr.iter().map(|v| opt(v, |v| Field::Bool(*v))).collect(),
+        Field::OptionalIntArray(arr) => arr.iter().map(|v| opt(v, |v| Field::Int(*v))).collect(),
+        Field::OptionalFloatArray(arr) => {
+            arr.iter().map(|v| opt(v, |v| Field::Float(*v))).collect()
+        }
+        Field::OptionalStringArray(arr) => arr
+            .iter()
+            .map(|v| opt(v, |v| Field::String(v.clone())))
+            .collect(),
+        Field::OptionalDateTimeArray(arr) => arr
+            .iter()
+            .map(|v| opt(v, |v| Field::DateTime(*v)))
+            .collect(),
+        Field::OptionalGeoPointArray(arr) => arr
+            .iter()
+            .map(|v| opt(v, |v| Field::GeoPoint(*v)))
+            .collect(),
+        Field::Json(Json::Array(arr)) => arr.iter().map(json::to_field).collect(),
+        _ => return None,
+    };
+    Some(values)
+}
@@ -8,7 +8,17 @@
 // by the Apache License, Version 2.0, included in the file
 // licenses/APL.txt.
 
+use std::cmp::Ordering;
+
+use crate::field_ord;
+
 pub fn handle(target_doc_field: &core::doc::Field, doc_field: &core::doc::Field) -> bool {
+    if field_ord::is_exact_number(target_doc_field) || field_ord::is_exact_number(doc_field) {
+        return field_ord::compare(target_doc_field, doc_field) == Some(Ordering::Equal);
+    }
+    if field_ord::is_temporal(target_doc_field) && field_ord::is_temporal(doc_field) {
+        return field_ord::compare(target_doc_field, doc_field) == Some(Ordering::Equal);
+    }
     match target_doc_field {
         core::doc::Field::Null => matches!(doc_field, core::doc::Field::Null),
         core::doc::Field::String(str_cond) => {
@@ -22,7 +32,8 @@ pub fn handle(target_doc_field: &core::doc::Field, doc_field: &core::doc::Field)
                 return int_val == int_cond;
             }
             if let core::doc::Field::Float(float_val) = doc_field {
-                return *float_val == *int_cond as f64;
+                return field_ord::compare_int_float(*int_cond, *float_val)
+                    == Some(Ordering::Equal);
             }
             false
         }
@@ -31,7 +42,8 @@ pub fn handle(target_doc_field: &core::doc::Field, doc_field: &core::doc::Field)
                 return float_val == float_cond;
             }
             if let core::doc::Field::Int(int_val) = doc_field {
-                return *int_val as f64 == *float_cond;
+                return field_ord::compare_int_float(*int_val, *float_cond)
+                    == Some(Ordering::Equal);
             }
             false
         }
@@ -41,9 +53,57 @@ pub fn handle(target_doc_field: &core::doc::Field, doc_field: &core::doc::Field)
             }
             false
         }
-        core::doc::Field::DateTime(date_time_cond) => {
-            if let core::doc::Field::DateTime(date_time_val) = doc_field {
-                return date_time_val == date_time_cond;
+        core::doc::Field::GeoPoint(point_cond) => {
+            if let core::doc::Field::GeoPoint(point_val) = doc_field {
+                return point_val == point_cond;
+            }
+            false
+        }
+        core::doc::Field::Vector(vector_cond) => {
+            if let core::doc::Field::Vector(vector_val) = doc_field {
+                return vector_val == vector_cond;
+            }
+            false
+        }
+        core::doc::Field::Json(json_cond) => {
+            if let core::doc::Field::Json(json_val) = doc_field {
+                return json_val == json_cond;
+            }
+            false
+        }
+        core::doc::Field::DecimalArray(arr_cond) => {
+            if let core::doc::Field::DecimalArray(arr_val) = doc_field {
+                return arr_val == arr_cond;
+            }
+            false
+        }
+        core::doc::Field::BigIntArray(arr_cond) => {
+            if let core::doc::Field::BigIntArray(arr_val) = doc_field {
+                return arr_val == arr_cond;
+            }
+            false
+        }
+        core::doc::Field::Blob(blob_cond) => {
+            if let core::doc::Field::Blob(blob_val) = doc_field {
+                return blob_val == blob_cond;
+            }
+            false
+        }
+        core::doc::Field::BlobArray(arr_cond) => {
+            if let core::doc::Field::BlobArray(arr_val) = doc_field {
+                return arr_val == arr_cond;
+            }
+            false
+        }
+        core::doc::Field::GeoPointArray(arr_cond) => {
+            if let core::doc::Field::GeoPointArray(arr_val) = doc_field {
+                return arr_val == arr_cond;
+            }
+            false
+        }
+        core::doc::Field::OptionalGeoPointArray(arr_cond) => {
+            if let core::doc::Field::OptionalGeoPointArray(arr_val) = doc_field {
+                return arr_val == arr_cond;
             }
             false
         }
@@ -8,7 +8,17 @@
 // by the Apache License, Version 2.0, included in the file
 // licenses/APL.txt.
 
+use std::cmp::Ordering;
+
+use crate::field_ord;
+
 pub fn handle(condition: &core::doc::Field, doc_field: &core::doc::Field) -> bool {
+    if field_ord::is_exact_number(condition) || field_ord::is_exact_number(doc_field) {
+        return field_ord::compare(doc_field, condition) == Some(Ordering::Greater);
+    }
+    if field_ord::is_temporal(condition) && field_ord::is_temporal(doc_field) {
+        return field_ord::compare(doc_field, condition) == Some(Ordering::Greater);
+    }
     match condition {
         core::doc::Field::Null => false,
         core::doc::Field::Int(int_cond) => {
@@ -16,7 +26,7 @@ pub fn handle(condition: &core::doc::Field, doc_field: &core::doc::Field) -> boo
                 return int_val > int_cond;
             }
             if let core::doc::Field::Float(float_val) = doc_field {
-                return *float_val > *int_cond as f64;
+                return field_ord::compare_int_float(*int_cond, *float_val) == Some(Ordering::Less);
             }
             false
         }
@@ -25,13 +35,14 @@ pub fn handle(condition: &core::doc::Field, doc_field: &core::doc::Field) -> boo
                 return float_val > float_cond;
             }
             if let core::doc::Field::Int(int_val) = doc_field {
-                return *int_val as f64 > *float_cond;
+                return field_ord::compare_int_float(*int_val, *float_cond)
+                    == Some(Ordering::Greater);
             }
             false
         }
-        core::doc::Field::DateTime(date_time_cond) => {
-            if let core::doc::Field::DateTime(date_time_val) = doc_field {
-                return date_time_val > date_time_cond;
+        core::doc::Field::Blob(blob_cond) => {
+            if let core::doc::Field::Blob(blob_val) = doc_field {
+                return blob_val > blob_cond;
             }
             false
         }
@@ -8,10 +8,15 @@
 // by the Apache License, Version 2.0, included in the file
 // licenses/APL.txt.
 
+// Folds the case of a string so that case-insensitive comparisons can compare it as is.
+pub(crate) fn fold(value: &str) -> String {
+    value.to_lowercase()
+}
+
 fn ilike(condition: &str, data: &str) -> bool {
     let mut has_prefix = false;
     let mut has_suffix = false;
-    let mut cn = condition.to_lowercase();
+    let mut cn = fold(condition);
 
     if cn.starts_with('%') {
         has_prefix = true;
@@ -22,7 +27,7 @@ fn ilike(condition: &str, data: &str) -> bool {
         cn.pop();
     }
 
-    let data = data.to_lowercase();
+    let data = fold(data);
 
     match (has_prefix, has_suffix) {
         (true, true) => data.contains(&cn),
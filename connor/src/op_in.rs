@@ -8,7 +8,28 @@
 // by the Apache License, Version 2.0, included in the file
 // licenses/APL.txt.
 
+use std::cmp::Ordering;
+
+use crate::field_ord;
+use crate::op_array;
+
 pub fn handle(condition: &core::doc::Field, doc_field: &core::doc::Field) -> bool {
+    let is_exact_array = matches!(
+        condition,
+        core::doc::Field::DecimalArray(_) | core::doc::Field::BigIntArray(_)
+    );
+    // zoned datetimes, dates and times may equal values of other temporal types
+    let is_coerced_temporal = matches!(
+        doc_field,
+        core::doc::Field::ZonedDateTime(_) | core::doc::Field::Date(_) | core::doc::Field::Time(_)
+    );
+    if is_exact_array || field_ord::is_exact_number(doc_field) || is_coerced_temporal {
+        return op_array::elements(condition).is_some_and(|values| {
+            values
+                .iter()
+                .any(|value| field_ord::compare(value, doc_field) == Some(Ordering::Equal))
+        });
+    }
     match condition {
         core::doc::Field::BoolArray(arr_cond) => {
             if let core::doc::Field::Bool(val) = doc_field {
@@ -40,6 +61,12 @@ pub fn handle(condition: &core::doc::Field, doc_field: &core::doc::Field) -> boo
             }
             false
         }
+        core::doc::Field::BlobArray(arr_cond) => {
+            if let core::doc::Field::Blob(val) = doc_field {
+                return arr_cond.contains(val);
+            }
+            false
+        }
         core::doc::Field::OptionalBoolArray(arr_cond) => match doc_field {
             core::doc::Field::OptionalBool(val) => arr_cond.contains(&val),
             core::doc::Field::Bool(val) => arr_cond.contains(&Some(*val)),
@@ -0,0 +1,21 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+pub fn handle(condition: &core::doc::Field, doc_field: &core::doc::Field) -> bool {
+    match (condition, doc_field) {
+        (core::doc::Field::String(str_cond), core::doc::Field::String(str_val)) => {
+            str_val.starts_with(str_cond.as_str())
+        }
+        (core::doc::Field::Blob(blob_cond), core::doc::Field::Blob(blob_val)) => {
+            blob_val.starts_with(blob_cond)
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use core::doc::Field;
+
+use crate::cond::{self, Op};
+use crate::error;
+
+// Operator is a predicate comparing the value of a document against a target.
+// Operators are shared between threads, so they must be safe to call concurrently.
+pub trait Operator: Send + Sync {
+    // Returns true if the value matches the target. Optional values are unwrapped before they
+    // reach the operator, and optionals without a value never match.
+    fn matches(&self, target: &Field, value: &Field) -> error::Result<bool>;
+}
+
+impl Operator for Op {
+    fn matches(&self, target: &Field, value: &Field) -> error::Result<bool> {
+        Ok(cond::match_op(self, target, value))
+    }
+}
+
+impl<F> Operator for F
+where
+    F: Fn(&Field, &Field) -> error::Result<bool> + Send + Sync,
+{
+    fn matches(&self, target: &Field, value: &Field) -> error::Result<bool> {
+        self(target, value)
+    }
+}
+
+// Registered operators are looked up by name each time a `Custom` condition is matched, so
+// unregistering an operator makes conditions using it fail with `UnknownOperator`.
+fn registry() -> &'static RwLock<HashMap<String, Arc<dyn Operator>>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, Arc<dyn Operator>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+// Registers the operator under the name. Names must be non-empty and unique.
+pub fn register(name: &str, operator: Arc<dyn Operator>) -> error::Result<()> {
+    if name.is_empty() {
+        return Err(error::Error::InvalidOperator(
+            "operator name must not be empty".to_string(),
+        ));
+    }
+    let mut operators = registry().write().unwrap_or_else(|e| e.into_inner());
+    if operators.contains_key(name) {
+        return Err(error::Error::InvalidOperator(format!(
+            "operator {} is already registered",
+            name
+        )));
+    }
+    operators.insert(name.to_string(), operator);
+    Ok(())
+}
+
+// Removes the operator. Returns false if no operator has the name.
+pub fn unregister(name: &str) -> bool {
+    registry()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(name)
+        .is_some()
+}
+
+// Returns the operator registered under the name, if any.
+pub fn get(name: &str) -> Option<Arc<dyn Operator>> {
+    registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(name)
+        .cloned()
+}
+
+// Applies the named operator to the value.
+pub(crate) fn match_custom(name: &str, target: &Field, value: &Field) -> error::Result<bool> {
+    match get(name) {
+        Some(operator) => operator.matches(target, value),
+        None => Err(error::Error::UnknownOperator(name.to_string())),
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use core::doc::Field;
+
+use crate::cond::{self, Condition};
+use crate::error;
+use crate::time::{self, SystemClock};
+
+const DEFAULT_CHUNK_SIZE: usize = 64;
+
+// Conditions and documents are shared by the worker threads.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Condition>();
+    assert_send_sync::<Field>();
+};
+
+// ParallelFilter matches a condition against many documents on several threads.
+//
+// Documents are given as fields, usually `Field::Doc`, the way `match_conditions` takes them,
+// so matching them doesn't copy them.
+//
+// Documents are split into chunks that the threads take in order. Results are returned in
+// document order and are the same as matching the documents one by one: an error is
+// returned only if matching stops at the failing document.
+#[derive(Clone, Debug)]
+pub struct ParallelFilter {
+    threads: usize,
+    chunk_size: usize,
+}
+
+// The outcome of matching one chunk: the indexes of the matching documents, up to the first
+// error if any.
+struct ChunkResult {
+    matches: Vec<usize>,
+    error: Option<error::Error>,
+}
+
+// The chunks matched so far. Chunks before `next` have all been matched and hold
+// `prefix_matches` matches in total.
+struct Progress {
+    chunks: BTreeMap<usize, ChunkResult>,
+    next: usize,
+    prefix_matches: usize,
+}
+
+impl Default for ParallelFilter {
+    // Uses as many threads as the system can run in parallel.
+    fn default() -> Self {
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        Self::new(threads)
+    }
+}
+
+impl ParallelFilter {
+    // Returns a filter using the given number of threads, at least one.
+    pub fn new(threads: usize) -> Self {
+        Self {
+            threads: threads.max(1),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    // Sets the number of documents a thread matches at a time, at least one.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    // Returns the indexes of the documents matching the condition, in ascending order.
+    pub fn filter(&self, condition: &Condition, docs: &[Field]) -> error::Result<Vec<usize>> {
+        self.first_n(condition, docs, usize::MAX)
+    }
+
+    // Returns the indexes of the first `n` documents matching the condition, in ascending
+    // order. Threads stop taking chunks once the chunks before them hold `n` matches.
+    pub fn first_n(
+        &self,
+        condition: &Condition,
+        docs: &[Field],
+        n: usize,
+    ) -> error::Result<Vec<usize>> {
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+        // every document is compared against the same instant
+        let resolved;
+        let condition = if time::is_relative(condition) {
+            resolved = time::resolve(condition, &SystemClock)?;
+            &resolved
+        } else {
+            condition
+        };
+        let chunk_count = docs.len().div_ceil(self.chunk_size);
+        let next_chunk = AtomicUsize::new(0);
+        let done = AtomicBool::new(false);
+        let progress = Mutex::new(Progress {
+            chunks: BTreeMap::new(),
+            next: 0,
+            prefix_matches: 0,
+        });
+
+        let work = || {
+            while !done.load(Ordering::Acquire) {
+                let chunk = next_chunk.fetch_add(1, Ordering::AcqRel);
+                if chunk >= chunk_count {
+                    break;
+                }
+                let result = self.match_chunk(condition, docs, chunk, n);
+                let mut progress = progress.lock().unwrap_or_else(|e| e.into_inner());
+                progress.chunks.insert(chunk, result);
+                if progress.advance(n) {
+                    done.store(true, Ordering::Release);
+                }
+            }
+        };
+        let threads = self.threads.min(chunk_count);
+        if threads <= 1 {
+            work();
+        } else {
+            thread::scope(|scope| {
+                for _ in 0..threads {
+                    scope.spawn(work);
+                }
+            });
+        }
+
+        let progress = progress.into_inner().unwrap_or_else(|e| e.into_inner());
+        let mut matches = Vec::new();
+        for (_, chunk) in progress.chunks.into_iter().take(progress.next) {
+            for index in chunk.matches {
+                if matches.len() == n {
+                    return Ok(matches);
+                }
+                matches.push(index);
+            }
+            if let Some(err) = chunk.error {
+                if matches.len() < n {
+                    return Err(err);
+                }
+            }
+        }
+        Ok(matches)
+    }
+
+    fn match_chunk(
+        &self,
+        condition: &Condition,
+        docs: &[Field],
+        chunk: usize,
+        n: usize,
+    ) -> ChunkResult {
+        let start = chunk * self.chunk_size;
+        let end = (start + self.chunk_size).min(docs.len());
+        let mut result = ChunkResult {
+            matches: Vec::new(),
+            error: None,
+        };
+        for (index, doc) in docs.iter().enumerate().take(end).skip(start) {
+            match cond::match_resolved(condition, doc) {
+                Ok(true) => {
+                    result.matches.push(index);
+                    if result.matches.len() == n {
+                        break;
+                    }
+                }
+                Ok(false) => {}
+                Err(err) => {
+                    result.error = Some(err);
+                    break;
+                }
+            }
+        }
+        result
+    }
+}
+
+impl Progress {
+    // Moves `next` past the chunks matched in order. Returns true if no further chunk can
+    // change the result, either because there are `n` matches or because matching failed.
+    fn advance(&mut self, n: usize) -> bool {
+        while let Some(chunk) = self.chunks.get(&self.next) {
+            self.next += 1;
+            self.prefix_matches += chunk.matches.len();
+            if self.prefix_matches >= n || chunk.error.is_some() {
+                return true;
+            }
+        }
+        false
+    }
+}
@@ -0,0 +1,260 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use core::doc::Field;
+
+use crate::cond::{self, match_conditions, CompoundOp, Condition, Scope};
+use crate::error;
+use crate::time::{self, SystemClock};
+
+// Truth is the result of matching a condition against a document with unknown fields.
+// Compound operators follow Kleene's three-valued logic.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Truth {
+    True,
+    False,
+    Unknown,
+}
+
+impl Truth {
+    pub fn and(self, other: Truth) -> Truth {
+        match (self, other) {
+            (Truth::False, _) | (_, Truth::False) => Truth::False,
+            (Truth::True, Truth::True) => Truth::True,
+            _ => Truth::Unknown,
+        }
+    }
+
+    pub fn or(self, other: Truth) -> Truth {
+        match (self, other) {
+            (Truth::True, _) | (_, Truth::True) => Truth::True,
+            (Truth::False, Truth::False) => Truth::False,
+            _ => Truth::Unknown,
+        }
+    }
+
+    // Returns the decided value, or `None` if the result is unknown.
+    pub fn decided(self) -> Option<bool> {
+        match self {
+            Truth::True => Some(true),
+            Truth::False => Some(false),
+            Truth::Unknown => None,
+        }
+    }
+}
+
+impl std::ops::Not for Truth {
+    type Output = Truth;
+
+    fn not(self) -> Truth {
+        match self {
+            Truth::True => Truth::False,
+            Truth::False => Truth::True,
+            Truth::Unknown => Truth::Unknown,
+        }
+    }
+}
+
+impl From<bool> for Truth {
+    fn from(value: bool) -> Self {
+        if value {
+            Truth::True
+        } else {
+            Truth::False
+        }
+    }
+}
+
+// UnknownFields marks the prop paths of a document whose values are not available.
+// Marking a path also marks every path nested under it.
+#[derive(Clone, Debug, Default)]
+pub struct UnknownFields {
+    paths: Vec<Vec<usize>>,
+}
+
+impl UnknownFields {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Returns a set marking every top-level field unknown except the given ones.
+    pub fn all_except(known: &[usize], field_count: usize) -> Self {
+        let mut unknown = Self::new();
+        for index in (0..field_count).filter(|index| !known.contains(index)) {
+            unknown.add(vec![index]);
+        }
+        unknown
+    }
+
+    // Marks the field at the given prop path unknown.
+    pub fn add(&mut self, path: Vec<usize>) {
+        self.paths.push(path);
+    }
+
+    // Returns true if the field at the given prop path is unknown.
+    pub fn contains(&self, path: &[usize]) -> bool {
+        self.paths.iter().any(|unknown| path.starts_with(unknown))
+    }
+}
+
+// Matches the condition against a document where some fields are unknown.
+// Returns `Truth::Unknown` only if the result depends on the value of an unknown field.
+pub fn match_conditions_partial(
+    condition: &Condition,
+    doc_field: &Field,
+    unknown: &UnknownFields,
+) -> error::Result<Truth> {
+    let mut path = Vec::new();
+    if time::is_relative(condition) {
+        let condition = time::resolve(condition, &SystemClock)?;
+        match_partial(&condition, doc_field, unknown, &mut path, None)
+    } else {
+        match_partial(condition, doc_field, unknown, &mut path, None)
+    }
+}
+
+fn match_partial(
+    condition: &Condition,
+    doc_field: &Field,
+    unknown: &UnknownFields,
+    path: &mut Vec<usize>,
+    scope: Option<&Scope>,
+) -> error::Result<Truth> {
+    match doc_field {
+        Field::DocArray(arr) => {
+            let mut result = Truth::False;
+            for doc in arr {
+                let doc_field = Field::Doc(doc.clone());
+                result = result.or(match_partial(condition, &doc_field, unknown, path, scope)?);
+                if result == Truth::True {
+                    break;
+                }
+            }
+            return Ok(result);
+        }
+        Field::OptionalInt(None)
+        | Field::OptionalFloat(None)
+        | Field::OptionalString(None)
+        | Field::OptionalBool(None)
+        | Field::OptionalDateTime(None)
+        | Field::OptionalZonedDateTime(None)
+        | Field::OptionalDate(None)
+        | Field::OptionalTime(None)
+        | Field::OptionalGeoPoint(None)
+        | Field::OptionalVector(None)
+        | Field::OptionalBlob(None)
+        | Field::OptionalDecimal(None)
+        | Field::OptionalBigInt(None) => return Ok(Truth::False),
+        _ => {}
+    }
+
+    match condition {
+        Condition::Op(_, _)
+        | Condition::ParamOp(_, _)
+        | Condition::Between(_)
+        | Condition::TimeOp(_, _)
+        | Condition::ArrayOp(_, _)
+        | Condition::Fuzzy(_)
+        | Condition::Within(_)
+        | Condition::Similar(_)
+        | Condition::Custom(_, _)
+        | Condition::JsonExists(_) => Ok(Truth::from(match_conditions(condition, doc_field)?)),
+        // metadata is always known
+        Condition::Meta(meta, inner) => Ok(Truth::from(cond::match_meta(
+            meta, inner, doc_field, scope, None,
+        )?)),
+        // the JSON value is known if the field holding it is
+        Condition::JsonPath(path, inner) => Ok(Truth::from(cond::match_json_path(
+            path, inner, doc_field, scope, None,
+        )?)),
+        Condition::FieldOp(op, field_ref) => {
+            // the path of the document the condition is applied to, then the referenced one
+            let base_len = path.len().checked_sub(field_ref.parent + 1);
+            if let Some(base_len) = base_len {
+                let mut ref_path = path[..base_len].to_vec();
+                ref_path.extend(&field_ref.path);
+                if unknown.contains(&ref_path) {
+                    return Ok(Truth::Unknown);
+                }
+            }
+            match cond::resolve_field_ref(field_ref, scope)? {
+                Some(ref_field) => Ok(Truth::from(cond::match_op(
+                    op,
+                    &cond::unwrap_optional(ref_field),
+                    doc_field,
+                ))),
+                None => Ok(Truth::False),
+            }
+        }
+        Condition::ExprOp(op, left, right) => {
+            // expressions resolve their references against the matched document, if any
+            let base_len = match doc_field {
+                Field::Doc(_) => Some(path.len()),
+                _ => path.len().checked_sub(1),
+            };
+            let field_refs = left.field_refs().into_iter().chain(right.field_refs());
+            for field_ref in field_refs {
+                if let Some(base_len) = base_len.and_then(|len| len.checked_sub(field_ref.parent)) {
+                    let mut ref_path = path[..base_len].to_vec();
+                    ref_path.extend(&field_ref.path);
+                    if unknown.contains(&ref_path) {
+                        return Ok(Truth::Unknown);
+                    }
+                }
+            }
+            Ok(Truth::from(cond::match_expr_op(
+                op, left, right, doc_field, scope,
+            )?))
+        }
+        Condition::Prop(index, inner) => {
+            path.push(*index);
+            let result = if unknown.contains(path) {
+                Ok(Truth::Unknown)
+            } else if let Field::Doc(doc) = doc_field {
+                let scope = Scope { doc, parent: scope };
+                match doc.fields.get(*index) {
+                    Some(prop_data) => match_partial(inner, prop_data, unknown, path, Some(&scope)),
+                    None => Err(error::Error::OutOfBoundPropIndex),
+                }
+            } else {
+                Ok(Truth::False)
+            };
+            path.pop();
+            result
+        }
+        Condition::CompoundOp(CompoundOp::AND, conditions) => {
+            let mut result = Truth::True;
+            for cond in conditions {
+                result = result.and(match_partial(cond, doc_field, unknown, path, scope)?);
+                if result == Truth::False {
+                    break;
+                }
+            }
+            Ok(result)
+        }
+        Condition::CompoundOp(CompoundOp::OR, conditions) => {
+            let mut result = Truth::False;
+            for cond in conditions {
+                result = result.or(match_partial(cond, doc_field, unknown, path, scope)?);
+                if result == Truth::True {
+                    break;
+                }
+            }
+            Ok(result)
+        }
+        Condition::CompoundOp(CompoundOp::NOT, conditions) => Ok(!match_partial(
+            &conditions[0],
+            doc_field,
+            unknown,
+            path,
+            scope,
+        )?),
+    }
+}
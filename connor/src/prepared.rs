@@ -0,0 +1,213 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use std::collections::{BTreeMap, HashMap};
+
+use core::doc::Field;
+
+use crate::cond::{self, CompoundOp, Condition, Op};
+use crate::error;
+
+// ParamType is the kind of value a param accepts, derived from the operators it's used with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParamType {
+    // Any value, as accepted by EQ and NE.
+    Any,
+    // A number, temporal value or blob, as accepted by the comparison operators.
+    Ordered,
+    // A string pattern or query, as accepted by the LIKE and MATCH operators.
+    String,
+    // An array of values, as accepted by IN and NIN.
+    Array,
+}
+
+impl ParamType {
+    fn for_op(op: &Op) -> ParamType {
+        match op {
+            // prefixes may be strings or blobs
+            Op::EQ | Op::NE | Op::PREFIX => ParamType::Any,
+            Op::GT | Op::GE | Op::LT | Op::LE => ParamType::Ordered,
+            Op::IN | Op::NIN => ParamType::Array,
+            Op::LIKE | Op::NLIKE | Op::ILIKE | Op::NILIKE | Op::MATCH => ParamType::String,
+        }
+    }
+
+    // Returns the type accepted by both types, if any.
+    fn unify(self, other: ParamType) -> Option<ParamType> {
+        match (self, other) {
+            (ParamType::Any, other) | (other, ParamType::Any) => Some(other),
+            (a, b) if a == b => Some(a),
+            _ => None,
+        }
+    }
+
+    // Returns true if the value can be bound to a param of this type.
+    pub fn accepts(&self, value: &Field) -> bool {
+        match self {
+            ParamType::Any => !matches!(value, Field::Doc(_) | Field::DocArray(_)),
+            ParamType::Ordered => {
+                matches!(
+                    value,
+                    Field::Int(_)
+                        | Field::Float(_)
+                        | Field::Decimal(_)
+                        | Field::BigInt(_)
+                        | Field::DateTime(_)
+                        | Field::ZonedDateTime(_)
+                        | Field::Date(_)
+                        | Field::Time(_)
+                        | Field::Blob(_)
+                )
+            }
+            ParamType::String => matches!(value, Field::String(_)),
+            ParamType::Array => matches!(
+                value,
+                Field::BoolArray(_)
+                    | Field::IntArray(_)
+                    | Field::FloatArray(_)
+                    | Field::DecimalArray(_)
+                    | Field::BigIntArray(_)
+                    | Field::StringArray(_)
+                    | Field::DateTimeArray(_)
+                    | Field::BlobArray(_)
+                    | Field::OptionalBoolArray(_)
+                    | Field::OptionalIntArray(_)
+                    | Field::OptionalFloatArray(_)
+                    | Field::OptionalStringArray(_)
+                    | Field::OptionalDateTimeArray(_)
+            ),
+        }
+    }
+}
+
+// PreparedCondition is a validated condition with named params that can be matched
+// repeatedly with different param values.
+#[derive(Clone, Debug)]
+pub struct PreparedCondition {
+    condition: Condition,
+    params: BTreeMap<String, ParamType>,
+}
+
+impl PreparedCondition {
+    // Validates the condition and collects the types of its params.
+    pub fn prepare(condition: Condition) -> error::Result<Self> {
+        let mut params = BTreeMap::new();
+        collect_params(&condition, &mut params)?;
+        Ok(Self { condition, params })
+    }
+
+    // Returns the params of the condition along with the type of value each accepts.
+    pub fn params(&self) -> &BTreeMap<String, ParamType> {
+        &self.params
+    }
+
+    // Returns the condition with every param replaced by its value.
+    // All params must be bound and every value must match the type of its param.
+    // This copies the whole condition, which `execute` avoids.
+    pub fn bind(&self, values: &HashMap<String, Field>) -> error::Result<Condition> {
+        self.check_values(values)?;
+        Ok(bind_params(&self.condition, values))
+    }
+
+    // Matches the condition against the document field with the given param values, which
+    // are checked like `bind` does. The values are looked up as the params are reached, so
+    // the condition isn't copied.
+    pub fn execute(
+        &self,
+        values: &HashMap<String, Field>,
+        doc_field: &Field,
+    ) -> error::Result<bool> {
+        self.check_values(values)?;
+        cond::match_with_params(&self.condition, doc_field, values)
+    }
+
+    fn check_values(&self, values: &HashMap<String, Field>) -> error::Result<()> {
+        if let Some(name) = values.keys().find(|name| !self.params.contains_key(*name)) {
+            return Err(error::Error::UnknownParam(name.clone()));
+        }
+        for (name, param_type) in &self.params {
+            match values.get(name) {
+                Some(value) if param_type.accepts(&cond::unwrap_optional(value)) => {}
+                Some(_) => return Err(error::Error::ParamTypeMismatch(name.clone())),
+                None => return Err(error::Error::UnboundParam(name.clone())),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn collect_params(
+    condition: &Condition,
+    params: &mut BTreeMap<String, ParamType>,
+) -> error::Result<()> {
+    match condition {
+        Condition::ParamOp(op, name) => {
+            let param_type = ParamType::for_op(op);
+            let param_type = match params.get(name) {
+                Some(current) => current
+                    .unify(param_type)
+                    .ok_or_else(|| error::Error::ConflictingParamTypes(name.clone()))?,
+                None => param_type,
+            };
+            params.insert(name.clone(), param_type);
+        }
+        Condition::Prop(_, inner) | Condition::Meta(_, inner) | Condition::JsonPath(_, inner) => {
+            collect_params(inner, params)?
+        }
+        Condition::CompoundOp(op, conditions) => {
+            if *op == CompoundOp::NOT && conditions.len() != 1 {
+                return Err(error::Error::InvalidCondition(
+                    "NOT expects exactly one condition".to_string(),
+                ));
+            }
+            for cond in conditions {
+                collect_params(cond, params)?;
+            }
+        }
+        Condition::Op(_, _)
+        | Condition::FieldOp(_, _)
+        | Condition::ExprOp(_, _, _)
+        | Condition::Between(_)
+        | Condition::TimeOp(_, _)
+        | Condition::ArrayOp(_, _)
+        | Condition::Fuzzy(_)
+        | Condition::Within(_)
+        | Condition::Similar(_)
+        | Condition::Custom(_, _)
+        | Condition::JsonExists(_) => {}
+    }
+    Ok(())
+}
+
+fn bind_params(condition: &Condition, values: &HashMap<String, Field>) -> Condition {
+    match condition {
+        Condition::ParamOp(op, name) => match values.get(name) {
+            Some(value) => Condition::Op(op.clone(), cond::unwrap_optional(value)),
+            None => condition.clone(),
+        },
+        Condition::Prop(index, inner) => {
+            Condition::Prop(*index, Box::new(bind_params(inner, values)))
+        }
+        Condition::Meta(meta, inner) => {
+            Condition::Meta(meta.clone(), Box::new(bind_params(inner, values)))
+        }
+        Condition::JsonPath(path, inner) => {
+            Condition::JsonPath(path.clone(), Box::new(bind_params(inner, values)))
+        }
+        Condition::CompoundOp(op, conditions) => Condition::CompoundOp(
+            op.clone(),
+            conditions
+                .iter()
+                .map(|cond| bind_params(cond, values))
+                .collect(),
+        ),
+        _ => condition.clone(),
+    }
+}
@@ -0,0 +1,531 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use std::cmp::Ordering;
+
+use core::doc::Field;
+
+use crate::cond::{self, CompoundOp, Condition, Op, Range, Target};
+use crate::field_ord;
+use crate::op_array;
+
+#[derive(Clone, Debug)]
+pub enum Bound {
+    Unbounded,
+    Included(Field),
+    Excluded(Field),
+}
+
+// Span is a contiguous range of index values.
+#[derive(Clone, Debug)]
+pub struct Span {
+    pub start: Bound,
+    pub end: Bound,
+}
+
+// IndexScan is the result of analysing a condition against an indexed prop path.
+// Documents matching the condition are guaranteed to have their indexed value within one
+// of the spans. Only documents within the spans that also match the residual condition
+// (if any) match the original condition.
+#[derive(Clone, Debug)]
+pub struct IndexScan {
+    pub spans: Vec<Span>,
+    pub residual: Option<Condition>,
+}
+
+impl Span {
+    // Returns a span covering every value.
+    pub fn full() -> Self {
+        Self {
+            start: Bound::Unbounded,
+            end: Bound::Unbounded,
+        }
+    }
+
+    // Returns a span containing only the given value.
+    pub fn point(value: Field) -> Self {
+        Self {
+            start: Bound::Included(value.clone()),
+            end: Bound::Included(value),
+        }
+    }
+
+    // Returns true if the span contains exactly one value.
+    pub fn is_point(&self) -> bool {
+        match (&self.start, &self.end) {
+            (Bound::Included(start), Bound::Included(end)) => {
+                field_ord::compare(start, end) == Some(Ordering::Equal)
+            }
+            _ => false,
+        }
+    }
+
+    // Returns true if the given value lies within the span.
+    pub fn contains(&self, value: &Field) -> bool {
+        let after_start = match &self.start {
+            Bound::Unbounded => true,
+            Bound::Included(start) => matches!(
+                field_ord::compare(value, start),
+                Some(Ordering::Greater | Ordering::Equal)
+            ),
+            Bound::Excluded(start) => field_ord::compare(value, start) == Some(Ordering::Greater),
+        };
+        let before_end = match &self.end {
+            Bound::Unbounded => true,
+            Bound::Included(end) => matches!(
+                field_ord::compare(value, end),
+                Some(Ordering::Less | Ordering::Equal)
+            ),
+            Bound::Excluded(end) => field_ord::compare(value, end) == Some(Ordering::Less),
+        };
+        after_start && before_end
+    }
+}
+
+// Derives the index spans for the value found at the given prop path of a document.
+// Sub-conditions that can't be answered by the index scan alone are returned as residual.
+pub fn extract_spans(condition: &Condition, path: &[usize]) -> IndexScan {
+    let analysis = analyze(condition, path);
+    IndexScan {
+        spans: analysis.spans.unwrap_or_else(|| vec![Span::full()]),
+        residual: analysis.residual,
+    }
+}
+
+struct Analysis {
+    // `None` means the condition doesn't constrain the indexed value.
+    spans: Option<Vec<Span>>,
+    residual: Option<Condition>,
+}
+
+impl Analysis {
+    fn unconstrained(condition: &Condition) -> Self {
+        Self {
+            spans: None,
+            residual: Some(condition.clone()),
+        }
+    }
+}
+
+fn analyze(condition: &Condition, path: &[usize]) -> Analysis {
+    match condition {
+        Condition::Op(op, target) => {
+            if !path.is_empty() {
+                return Analysis::unconstrained(condition);
+            }
+            match op_spans(op, target) {
+                Some(spans) => Analysis {
+                    spans: Some(spans),
+                    residual: None,
+                },
+                None => Analysis::unconstrained(condition),
+            }
+        }
+        Condition::Between(range) => {
+            if !path.is_empty() {
+                return Analysis::unconstrained(condition);
+            }
+            match range_spans(range) {
+                Some(spans) => Analysis {
+                    spans: Some(spans),
+                    residual: None,
+                },
+                None => Analysis::unconstrained(condition),
+            }
+        }
+        Condition::Prop(index, inner) => match path.split_first() {
+            Some((head, tail)) if head == index => {
+                let analysis = analyze(inner, tail);
+                Analysis {
+                    spans: analysis.spans,
+                    residual: analysis
+                        .residual
+                        .map(|residual| Condition::Prop(*index, Box::new(residual))),
+                }
+            }
+            _ => Analysis::unconstrained(condition),
+        },
+        Condition::CompoundOp(CompoundOp::AND, conditions) => {
+            let mut spans: Option<Vec<Span>> = None;
+            let mut residuals = Vec::new();
+            for cond in conditions {
+                let analysis = analyze(cond, path);
+                match (spans.as_ref(), analysis.spans) {
+                    (_, None) => {}
+                    (None, Some(cond_spans)) => spans = Some(cond_spans),
+                    (Some(current), Some(cond_spans)) => match intersect(current, &cond_spans) {
+                        Some(intersection) => spans = Some(intersection),
+                        None => {
+                            // values are not comparable, so let the residual filter handle it
+                            residuals.push(cond.clone());
+                            continue;
+                        }
+                    },
+                }
+                if let Some(residual) = analysis.residual {
+                    residuals.push(residual);
+                }
+            }
+            Analysis {
+                spans,
+                residual: conjunction(residuals),
+            }
+        }
+        Condition::CompoundOp(CompoundOp::OR, conditions) => {
+            let mut spans = Vec::new();
+            let mut is_exact = true;
+            for cond in conditions {
+                let analysis = analyze(cond, path);
+                match analysis.spans {
+                    Some(cond_spans) => spans.extend(cond_spans),
+                    None => return Analysis::unconstrained(condition),
+                }
+                is_exact = is_exact && analysis.residual.is_none();
+            }
+            match normalize(spans) {
+                Some(spans) => Analysis {
+                    spans: Some(spans),
+                    residual: if is_exact {
+                        None
+                    } else {
+                        Some(condition.clone())
+                    },
+                },
+                None => Analysis::unconstrained(condition),
+            }
+        }
+        Condition::FieldOp(_, _)
+        | Condition::ParamOp(_, _)
+        | Condition::ExprOp(_, _, _)
+        | Condition::TimeOp(_, _)
+        | Condition::ArrayOp(_, _)
+        | Condition::Meta(_, _)
+        | Condition::JsonPath(_, _)
+        | Condition::JsonExists(_)
+        | Condition::Fuzzy(_)
+        | Condition::Within(_)
+        | Condition::Similar(_)
+        | Condition::Custom(_, _)
+        | Condition::CompoundOp(CompoundOp::NOT, _) => Analysis::unconstrained(condition),
+    }
+}
+
+fn conjunction(mut conditions: Vec<Condition>) -> Option<Condition> {
+    match conditions.len() {
+        0 => None,
+        1 => conditions.pop(),
+        _ => Some(Condition::CompoundOp(CompoundOp::AND, conditions)),
+    }
+}
+
+// Returns the spans matched exactly by the given operator, or `None` if the operator
+// can't be expressed as spans.
+pub(crate) fn op_spans(op: &Op, target: &Field) -> Option<Vec<Span>> {
+    match op {
+        // NaN is unordered and never matches a comparison
+        Op::EQ | Op::GT | Op::GE | Op::LT | Op::LE if field_ord::is_nan(target) => Some(Vec::new()),
+        Op::EQ if field_ord::is_scalar(target) => Some(vec![Span::point(target.clone())]),
+        Op::IN => op_array::elements(target).and_then(|values| {
+            let values = values.into_iter().filter(|value| !field_ord::is_nan(value));
+            normalize(values.map(Span::point).collect())
+        }),
+        Op::GT | Op::GE | Op::LT | Op::LE => match target {
+            Field::Int(_)
+            | Field::Float(_)
+            | Field::Decimal(_)
+            | Field::BigInt(_)
+            | Field::DateTime(_)
+            | Field::ZonedDateTime(_)
+            | Field::Blob(_) => {
+                let value = target.clone();
+                Some(vec![match op {
+                    Op::GT => Span {
+                        start: Bound::Excluded(value),
+                        end: Bound::Unbounded,
+                    },
+                    Op::GE => Span {
+                        start: Bound::Included(value),
+                        end: Bound::Unbounded,
+                    },
+                    // null sorts first and never satisfies a comparison
+                    Op::LT => Span {
+                        start: Bound::Excluded(Field::Null),
+                        end: Bound::Excluded(value),
+                    },
+                    _ => Span {
+                        start: Bound::Excluded(Field::Null),
+                        end: Bound::Included(value),
+                    },
+                }])
+            }
+            Field::Null => Some(Vec::new()),
+            _ => None,
+        },
+        Op::LIKE => match target {
+            Field::String(pattern) if !pattern.starts_with('%') && pattern.ends_with('%') => {
+                let prefix = &pattern[..pattern.len() - 1];
+                Some(vec![prefix_span(
+                    Field::String(prefix.to_string()),
+                    prefix_successor(prefix).map(Field::String),
+                )])
+            }
+            _ => None,
+        },
+        Op::PREFIX => match target {
+            Field::String(prefix) => Some(vec![prefix_span(
+                target.clone(),
+                prefix_successor(prefix).map(Field::String),
+            )]),
+            Field::Blob(prefix) => Some(vec![prefix_span(
+                target.clone(),
+                blob_prefix_successor(prefix).map(Field::Blob),
+            )]),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Returns the span matched by a range with fixed bounds, or `None` if the bounds are relative
+// or not comparable.
+pub(crate) fn range_spans(range: &Range) -> Option<Vec<Span>> {
+    let (start, end) = match (&range.start, &range.end) {
+        (Target::Value(start), Target::Value(end)) => {
+            (cond::unwrap_optional(start), cond::unwrap_optional(end))
+        }
+        _ => return None,
+    };
+    let is_unordered = |field: &Field| matches!(field, Field::Null) || field_ord::is_nan(field);
+    if is_unordered(&start) || is_unordered(&end) {
+        return Some(Vec::new());
+    }
+    let is_rangeable = |field: &Field| {
+        matches!(
+            field,
+            Field::Int(_)
+                | Field::Float(_)
+                | Field::Decimal(_)
+                | Field::BigInt(_)
+                | Field::DateTime(_)
+                | Field::ZonedDateTime(_)
+                | Field::String(_)
+                | Field::Blob(_)
+        )
+    };
+    if !is_rangeable(&start) || !is_rangeable(&end) {
+        return None;
+    }
+    let span = Span {
+        start: if range.start_inclusive {
+            Bound::Included(start)
+        } else {
+            Bound::Excluded(start)
+        },
+        end: if range.end_inclusive {
+            Bound::Included(end)
+        } else {
+            Bound::Excluded(end)
+        },
+    };
+    Some(if is_empty(&span)? {
+        Vec::new()
+    } else {
+        vec![span]
+    })
+}
+
+// Returns the span of values starting with the prefix, given the successor of the prefix.
+fn prefix_span(prefix: Field, successor: Option<Field>) -> Span {
+    Span {
+        start: Bound::Included(prefix),
+        end: successor.map_or(Bound::Unbounded, Bound::Excluded),
+    }
+}
+
+// Returns the smallest blob greater than every blob starting with the given prefix.
+fn blob_prefix_successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut bytes = prefix.to_vec();
+    while let Some(last) = bytes.pop() {
+        if last < u8::MAX {
+            bytes.push(last + 1);
+            return Some(bytes);
+        }
+    }
+    None
+}
+
+// Returns the smallest string greater than every string starting with the given prefix.
+fn prefix_successor(prefix: &str) -> Option<String> {
+    let mut chars: Vec<char> = prefix.chars().collect();
+    while let Some(last) = chars.pop() {
+        let mut next = last as u32 + 1;
+        // skip the surrogate range which has no valid chars
+        if (0xD800..=0xDFFF).contains(&next) {
+            next = 0xE000;
+        }
+        if let Some(next) = char::from_u32(next) {
+            chars.push(next);
+            return Some(chars.into_iter().collect());
+        }
+    }
+    None
+}
+
+fn compare_start(a: &Bound, b: &Bound) -> Option<Ordering> {
+    match (a, b) {
+        (Bound::Unbounded, Bound::Unbounded) => Some(Ordering::Equal),
+        (Bound::Unbounded, _) => Some(Ordering::Less),
+        (_, Bound::Unbounded) => Some(Ordering::Greater),
+        (Bound::Included(a), Bound::Included(b)) | (Bound::Excluded(a), Bound::Excluded(b)) => {
+            field_ord::compare(a, b)
+        }
+        (Bound::Included(a), Bound::Excluded(b)) => {
+            field_ord::compare(a, b).map(|ord| ord.then(Ordering::Less))
+        }
+        (Bound::Excluded(a), Bound::Included(b)) => {
+            field_ord::compare(a, b).map(|ord| ord.then(Ordering::Greater))
+        }
+    }
+}
+
+fn compare_end(a: &Bound, b: &Bound) -> Option<Ordering> {
+    match (a, b) {
+        (Bound::Unbounded, Bound::Unbounded) => Some(Ordering::Equal),
+        (Bound::Unbounded, _) => Some(Ordering::Greater),
+        (_, Bound::Unbounded) => Some(Ordering::Less),
+        (Bound::Included(a), Bound::Included(b)) | (Bound::Excluded(a), Bound::Excluded(b)) => {
+            field_ord::compare(a, b)
+        }
+        (Bound::Included(a), Bound::Excluded(b)) => {
+            field_ord::compare(a, b).map(|ord| ord.then(Ordering::Greater))
+        }
+        (Bound::Excluded(a), Bound::Included(b)) => {
+            field_ord::compare(a, b).map(|ord| ord.then(Ordering::Less))
+        }
+    }
+}
+
+// Returns true if a span ending at `end` overlaps or touches a span beginning at `start`.
+fn connects(end: &Bound, start: &Bound) -> Option<bool> {
+    match (end, start) {
+        (Bound::Unbounded, _) | (_, Bound::Unbounded) => Some(true),
+        (Bound::Excluded(end), Bound::Excluded(start)) => {
+            field_ord::compare(start, end).map(|ord| ord == Ordering::Less)
+        }
+        (
+            Bound::Included(end) | Bound::Excluded(end),
+            Bound::Included(start) | Bound::Excluded(start),
+        ) => field_ord::compare(start, end).map(|ord| ord != Ordering::Greater),
+    }
+}
+
+pub(crate) fn is_empty(span: &Span) -> Option<bool> {
+    match (&span.start, &span.end) {
+        (Bound::Unbounded, _) | (_, Bound::Unbounded) => Some(false),
+        (Bound::Included(start), Bound::Included(end)) => {
+            field_ord::compare(start, end).map(|ord| ord == Ordering::Greater)
+        }
+        (
+            Bound::Included(start) | Bound::Excluded(start),
+            Bound::Included(end) | Bound::Excluded(end),
+        ) => field_ord::compare(start, end).map(|ord| ord != Ordering::Less),
+    }
+}
+
+// Sorts the spans and merges the overlapping ones.
+// Returns `None` if the spans hold values that are not comparable with each other.
+pub(crate) fn normalize(spans: Vec<Span>) -> Option<Vec<Span>> {
+    let mut spans = spans
+        .into_iter()
+        .filter_map(|span| match is_empty(&span) {
+            Some(true) => None,
+            Some(false) => Some(Some(span)),
+            None => Some(None),
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let mut comparable = true;
+    spans.sort_by(|a, b| {
+        compare_start(&a.start, &b.start).unwrap_or_else(|| {
+            comparable = false;
+            Ordering::Equal
+        })
+    });
+    if !comparable {
+        return None;
+    }
+
+    let mut merged: Vec<Span> = Vec::with_capacity(spans.len());
+    for span in spans {
+        if let Some(last) = merged.last_mut() {
+            if connects(&last.end, &span.start)? {
+                if compare_end(&span.end, &last.end)? == Ordering::Greater {
+                    last.end = span.end;
+                }
+                continue;
+            }
+        }
+        merged.push(span);
+    }
+    Some(merged)
+}
+
+// Returns the spans covered by both span sets.
+pub(crate) fn intersect(a: &[Span], b: &[Span]) -> Option<Vec<Span>> {
+    let mut result = Vec::new();
+    for span_a in a {
+        for span_b in b {
+            let start = match compare_start(&span_a.start, &span_b.start)? {
+                Ordering::Less => span_b.start.clone(),
+                _ => span_a.start.clone(),
+            };
+            let end = match compare_end(&span_a.end, &span_b.end)? {
+                Ordering::Greater => span_b.end.clone(),
+                _ => span_a.end.clone(),
+            };
+            result.push(Span { start, end });
+        }
+    }
+    normalize(result)
+}
+
+// Returns the spans covering every value not covered by the given normalized spans.
+pub(crate) fn complement(spans: &[Span]) -> Vec<Span> {
+    fn flip(bound: &Bound) -> Bound {
+        match bound {
+            Bound::Unbounded => Bound::Unbounded,
+            Bound::Included(value) => Bound::Excluded(value.clone()),
+            Bound::Excluded(value) => Bound::Included(value.clone()),
+        }
+    }
+
+    let mut result = Vec::new();
+    let mut cursor = Bound::Unbounded;
+    for span in spans {
+        if !matches!(span.start, Bound::Unbounded) {
+            let gap = Span {
+                start: cursor,
+                end: flip(&span.start),
+            };
+            if is_empty(&gap) == Some(false) {
+                result.push(gap);
+            }
+        }
+        if matches!(span.end, Bound::Unbounded) {
+            return result;
+        }
+        cursor = flip(&span.end);
+    }
+    result.push(Span {
+        start: cursor,
+        end: Bound::Unbounded,
+    });
+    result
+}
@@ -0,0 +1,319 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::{DateTime, Utc};
+use core::doc::{Doc, Field};
+
+use crate::cond::{self, CompoundOp, Condition, Op};
+use crate::error;
+use crate::field_ord;
+use crate::span::{self, Bound, Span};
+
+pub type SubscriptionId = u64;
+
+type PredicateId = usize;
+
+// SubscriptionMatcher finds the subscriptions whose filters match a document without
+// evaluating every filter.
+//
+// Each filter is split into the conjuncts of its top-level AND. A conjunct on a single prop
+// whose EQ, IN, GT or GE operators can be expressed as spans becomes an indexed predicate.
+// LT, LE and negated conditions also match values of other types, which no span holds, so
+// they are never indexed.
+// Given a document, the matcher looks up the predicates satisfied by each prop value and
+// counts them per subscription. Only subscriptions whose predicates are all satisfied are
+// candidates, and only candidates with conjuncts other than EQ, GT and GE are evaluated in
+// full. Filters without any indexed predicate are always evaluated.
+//
+// Documents that lack a prop, or hold an optional without a value in it, satisfy no
+// predicate on that prop.
+#[derive(Debug, Default)]
+pub struct SubscriptionMatcher {
+    next_id: SubscriptionId,
+    next_predicate: PredicateId,
+    subscriptions: BTreeMap<SubscriptionId, Subscription>,
+    // The subscriptions without any indexed predicate.
+    unindexed: BTreeSet<SubscriptionId>,
+    owners: HashMap<PredicateId, SubscriptionId>,
+    props: HashMap<usize, PropIndex>,
+}
+
+#[derive(Debug)]
+struct Subscription {
+    condition: Condition,
+    // The prop and ID of every indexed predicate.
+    predicates: Vec<(usize, PredicateId)>,
+    // True if the predicates are the whole filter, so candidates need no evaluation.
+    is_exact: bool,
+}
+
+// PropIndex holds the predicates on one prop. Points with a hashable value are found by
+// lookup, every other span is checked in turn.
+#[derive(Debug, Default)]
+struct PropIndex {
+    points: HashMap<PointKey, Vec<(PredicateId, Field)>>,
+    spans: Vec<(PredicateId, Span)>,
+}
+
+// PointKey is a hashable form of a scalar value. Values that compare equal have the same key:
+// integral floats use the key of the integer and datetimes the key of their instant.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum PointKey {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(u64),
+    String(String),
+    DateTime(DateTime<Utc>),
+    Blob(Vec<u8>),
+}
+
+impl SubscriptionMatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscriptions.is_empty()
+    }
+
+    // Registers the filter and returns the ID of its subscription.
+    pub fn add(&mut self, condition: Condition) -> SubscriptionId {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let mut conjuncts = Vec::new();
+        flatten_and(&condition, &mut conjuncts);
+        let mut predicates = Vec::new();
+        let mut is_exact = true;
+        for conjunct in conjuncts {
+            let Condition::Prop(prop, _) = conjunct else {
+                is_exact = false;
+                continue;
+            };
+            // LT, LE and the negations also match values outside of their spans, such as
+            // values of other types, so they are left to `match_conditions`
+            if matches_outside_spans(conjunct) {
+                is_exact = false;
+                continue;
+            }
+            let scan = span::extract_spans(conjunct, &[*prop]);
+            if is_full(&scan.spans) {
+                is_exact = false;
+                continue;
+            }
+            is_exact = is_exact && scan.residual.is_none() && is_exact_conjunct(conjunct);
+
+            let predicate = self.next_predicate;
+            self.next_predicate += 1;
+            self.owners.insert(predicate, id);
+            let index = self.props.entry(*prop).or_default();
+            for span in scan.spans {
+                match point_value(&span).and_then(|value| point_key(&value).map(|k| (k, value))) {
+                    Some((key, value)) => index
+                        .points
+                        .entry(key)
+                        .or_default()
+                        .push((predicate, value)),
+                    None => index.spans.push((predicate, span)),
+                }
+            }
+            predicates.push((*prop, predicate));
+        }
+
+        if predicates.is_empty() {
+            self.unindexed.insert(id);
+        }
+        self.subscriptions.insert(
+            id,
+            Subscription {
+                condition,
+                predicates,
+                is_exact,
+            },
+        );
+        id
+    }
+
+    // Unregisters the subscription. Returns false if there is no subscription with the ID.
+    pub fn remove(&mut self, id: SubscriptionId) -> bool {
+        let Some(subscription) = self.subscriptions.remove(&id) else {
+            return false;
+        };
+        self.unindexed.remove(&id);
+        for (prop, predicate) in subscription.predicates {
+            self.owners.remove(&predicate);
+            let Some(index) = self.props.get_mut(&prop) else {
+                continue;
+            };
+            index.points.retain(|_, entries| {
+                entries.retain(|(entry, _)| *entry != predicate);
+                !entries.is_empty()
+            });
+            index.spans.retain(|(entry, _)| *entry != predicate);
+            if index.points.is_empty() && index.spans.is_empty() {
+                self.props.remove(&prop);
+            }
+        }
+        true
+    }
+
+    // Returns the IDs of the subscriptions whose filters match the document, in ascending
+    // order.
+    pub fn matches(&self, doc: &Doc) -> error::Result<Vec<SubscriptionId>> {
+        let mut counts: HashMap<SubscriptionId, usize> = HashMap::new();
+        for (prop, index) in &self.props {
+            let Some(value) = doc.fields.get(*prop).and_then(indexed_value) else {
+                continue;
+            };
+            for predicate in index.satisfied(&value) {
+                *counts.entry(self.owners[&predicate]).or_default() += 1;
+            }
+        }
+
+        let candidates = counts
+            .into_iter()
+            .filter(|(id, count)| self.subscriptions[id].predicates.len() == *count)
+            .map(|(id, _)| id)
+            .chain(self.unindexed.iter().copied())
+            .collect::<BTreeSet<_>>();
+
+        let doc_field = Field::Doc(doc.clone());
+        let mut matched = Vec::new();
+        for id in candidates {
+            let subscription = &self.subscriptions[&id];
+            if subscription.is_exact || cond::match_conditions(&subscription.condition, &doc_field)?
+            {
+                matched.push(id);
+            }
+        }
+        Ok(matched)
+    }
+}
+
+impl PropIndex {
+    // Returns the predicates satisfied by the value.
+    fn satisfied(&self, value: &Field) -> BTreeSet<PredicateId> {
+        let mut satisfied = BTreeSet::new();
+        match point_key(value) {
+            Some(key) => {
+                if let Some(entries) = self.points.get(&key) {
+                    satisfied.extend(entries.iter().map(|(predicate, _)| *predicate));
+                }
+            }
+            // values without a key, such as dates, may equal points of other types
+            None => {
+                for (predicate, point) in self.points.values().flatten() {
+                    if field_ord::compare(value, point) == Some(Ordering::Equal) {
+                        satisfied.insert(*predicate);
+                    }
+                }
+            }
+        }
+        for (predicate, span) in &self.spans {
+            if span.contains(value) {
+                satisfied.insert(*predicate);
+            }
+        }
+        satisfied
+    }
+}
+
+fn flatten_and<'a>(condition: &'a Condition, conjuncts: &mut Vec<&'a Condition>) {
+    match condition {
+        Condition::CompoundOp(CompoundOp::AND, conditions) => {
+            for cond in conditions {
+                flatten_and(cond, conjuncts);
+            }
+        }
+        _ => conjuncts.push(condition),
+    }
+}
+
+// Returns true if the condition may match values outside of its spans.
+fn matches_outside_spans(condition: &Condition) -> bool {
+    match condition {
+        Condition::Op(op, _) => matches!(
+            op,
+            Op::LT | Op::LE | Op::NE | Op::NIN | Op::NLIKE | Op::NILIKE
+        ),
+        Condition::Prop(_, inner) => matches_outside_spans(inner),
+        Condition::CompoundOp(CompoundOp::NOT, _) => true,
+        Condition::CompoundOp(_, conditions) => conditions.iter().any(matches_outside_spans),
+        _ => false,
+    }
+}
+
+// Returns true if the spans of the condition hold exactly the values it matches, which is
+// only the case for EQ, GT and GE. IN, for one, only matches values of the type of the array
+// elements, unlike its spans.
+fn is_exact_conjunct(condition: &Condition) -> bool {
+    match condition {
+        Condition::Op(op, _) => matches!(op, Op::EQ | Op::GT | Op::GE),
+        Condition::Prop(_, inner) => is_exact_conjunct(inner),
+        Condition::CompoundOp(CompoundOp::AND | CompoundOp::OR, conditions) => {
+            conditions.iter().all(is_exact_conjunct)
+        }
+        _ => false,
+    }
+}
+
+fn is_full(spans: &[Span]) -> bool {
+    matches!(
+        spans,
+        [Span {
+            start: Bound::Unbounded,
+            end: Bound::Unbounded,
+        }]
+    )
+}
+
+fn point_value(span: &Span) -> Option<Field> {
+    match &span.start {
+        Bound::Included(value) if span.is_point() => Some(value.clone()),
+        _ => None,
+    }
+}
+
+// Returns the value the prop's predicates are matched against, or `None` if the prop holds an
+// optional without a value, which no condition matches.
+fn indexed_value(field: &Field) -> Option<Field> {
+    match cond::unwrap_optional(field) {
+        Field::Null if !matches!(field, Field::Null) => None,
+        value => Some(value),
+    }
+}
+
+fn point_key(field: &Field) -> Option<PointKey> {
+    // 2^63, the smallest float above every i64
+    const I64_END: f64 = 9_223_372_036_854_775_808.0;
+    match field {
+        Field::Null => Some(PointKey::Null),
+        Field::Bool(val) => Some(PointKey::Bool(*val)),
+        Field::Int(val) => Some(PointKey::Int(*val)),
+        Field::Float(val) if val.is_nan() => None,
+        Field::Float(val) if val.fract() == 0.0 && (-I64_END..I64_END).contains(val) => {
+            Some(PointKey::Int(*val as i64))
+        }
+        Field::Float(val) => Some(PointKey::Float(val.to_bits())),
+        Field::String(val) => Some(PointKey::String(val.clone())),
+        Field::DateTime(val) => Some(PointKey::DateTime(*val)),
+        Field::ZonedDateTime(val) => Some(PointKey::DateTime(val.with_timezone(&Utc))),
+        Field::Blob(val) => Some(PointKey::Blob(val.clone())),
+        _ => None,
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use core::doc::Field;
+
+use crate::cond::{CompoundOp, Condition, Op};
+use crate::error;
+
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "if", "in", "into", "is", "it",
+    "no", "not", "of", "on", "or", "such", "that", "the", "their", "then", "there", "these",
+    "they", "this", "to", "was", "will", "with",
+];
+
+// Controls how quickly repeated occurrences of a term stop adding to the score.
+const TERM_SATURATION: f64 = 1.2;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum QueryMode {
+    // Every term must appear.
+    All,
+    // At least one term must appear.
+    Any,
+    // The terms must appear next to each other in order.
+    Phrase,
+}
+
+// Query is a parsed full-text query. A query wrapped in double quotes is a phrase, a query
+// with terms separated by `OR` matches any of them, and otherwise all terms must match.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    pub terms: Vec<String>,
+    pub mode: QueryMode,
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Self {
+        let trimmed = query.trim();
+        if trimmed.len() >= 2 && trimmed.starts_with('"') && trimmed.ends_with('"') {
+            return Self {
+                terms: tokenize(&trimmed[1..trimmed.len() - 1]),
+                mode: QueryMode::Phrase,
+            };
+        }
+        let is_any = trimmed.split_whitespace().any(|word| word == "OR");
+        Self {
+            terms: tokenize(trimmed),
+            mode: if is_any {
+                QueryMode::Any
+            } else {
+                QueryMode::All
+            },
+        }
+    }
+
+    // Returns true if the texts match the query. Terms may be found in any of the texts, but
+    // a phrase must be found within a single one. Queries without terms match nothing.
+    pub fn matches(&self, texts: &[&str]) -> bool {
+        if self.terms.is_empty() {
+            return false;
+        }
+        let tokens: Vec<Vec<String>> = texts.iter().map(|text| tokenize(text)).collect();
+        let has_term = |term: &String| tokens.iter().any(|tokens| tokens.contains(term));
+        match self.mode {
+            QueryMode::All => self.terms.iter().all(has_term),
+            QueryMode::Any => self.terms.iter().any(has_term),
+            QueryMode::Phrase => tokens.iter().any(|tokens| {
+                tokens
+                    .windows(self.terms.len())
+                    .any(|window| window == self.terms.as_slice())
+            }),
+        }
+    }
+
+    // Returns the relevance of the texts for the query, or 0 if they don't match.
+    // Every occurrence of a term adds to the score with diminishing returns, and matches in
+    // shorter texts score higher.
+    pub fn score(&self, texts: &[&str]) -> f64 {
+        if !self.matches(texts) {
+            return 0.0;
+        }
+        let tokens: Vec<String> = texts.iter().flat_map(|text| tokenize(text)).collect();
+        let length_norm = 1.0 / (1.0 + (tokens.len() as f64).ln());
+        self.terms
+            .iter()
+            .map(|term| {
+                let freq = tokens.iter().filter(|token| *token == term).count() as f64;
+                freq * (TERM_SATURATION + 1.0) / (freq + TERM_SATURATION)
+            })
+            .sum::<f64>()
+            * length_norm
+    }
+}
+
+// Splits the text into lowercase stemmed words, leaving out stop words.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .map(|word| stem(&word))
+        .collect()
+}
+
+// Strips common English suffixes so that different forms of a word share a stem.
+pub fn stem(word: &str) -> String {
+    const MIN_STEM: usize = 3;
+    let strip = |suffix: &str, replacement: &str| -> Option<String> {
+        let stem = word.strip_suffix(suffix)?;
+        if stem.chars().count() < MIN_STEM {
+            return None;
+        }
+        Some(format!("{}{}", stem, replacement))
+    };
+    if word.ends_with("ss") || word.ends_with("us") {
+        return word.to_string();
+    }
+    let strip_verb = |suffix: &str| -> Option<String> {
+        let mut stem = strip(suffix, "")?;
+        // running -> run, but not falling -> fal
+        let mut chars = stem.chars().rev();
+        if let (Some(last), Some(prev)) = (chars.next(), chars.next()) {
+            if last == prev && !"aeioulsz".contains(last) {
+                stem.pop();
+            }
+        }
+        Some(stem)
+    };
+    strip("sses", "ss")
+        .or_else(|| strip("ies", "y"))
+        .or_else(|| strip_verb("ing"))
+        .or_else(|| strip_verb("ed"))
+        .or_else(|| strip("ly", ""))
+        .or_else(|| strip("es", "").filter(|_| word.ends_with("ches") || word.ends_with("xes")))
+        .or_else(|| strip("s", ""))
+        .unwrap_or_else(|| word.to_string())
+}
+
+// Returns the texts of a String or StringArray field.
+fn texts(field: &Field) -> Option<Vec<&str>> {
+    match field {
+        Field::String(val) | Field::OptionalString(Some(val)) => Some(vec![val.as_str()]),
+        Field::StringArray(arr) => Some(arr.iter().map(String::as_str).collect()),
+        Field::OptionalStringArray(arr) => Some(arr.iter().flatten().map(String::as_str).collect()),
+        _ => None,
+    }
+}
+
+pub(crate) fn handle(query: &Field, doc_field: &Field) -> bool {
+    match (query, texts(doc_field)) {
+        (Field::String(query), Some(texts)) => Query::parse(query).matches(&texts),
+        _ => false,
+    }
+}
+
+// Returns the relevance of the document for the MATCH and FUZZY operators of the condition.
+// The scores of all matching operators not under a NOT are summed up, and the best scoring
+// document of a document array counts.
+pub fn score_conditions(condition: &Condition, doc_field: &Field) -> error::Result<f64> {
+    match (condition, doc_field) {
+        (_, Field::DocArray(docs)) => {
+            let mut max = 0.0f64;
+            for doc in docs {
+                max = max.max(score_conditions(condition, &Field::Doc(doc.clone()))?);
+            }
+            Ok(max)
+        }
+        (Condition::Op(Op::MATCH, Field::String(query)), _) => Ok(match texts(doc_field) {
+            Some(texts) => Query::parse(query).score(&texts),
+            None => 0.0,
+        }),
+        (Condition::Fuzzy(fuzzy), _) if fuzzy.matches(doc_field) => {
+            Ok(fuzzy.score(doc_field).unwrap_or(0.0))
+        }
+        (Condition::Prop(index, inner), Field::Doc(doc)) => match doc.fields.get(*index) {
+            Some(prop_data) => score_conditions(inner, prop_data),
+            None => Err(error::Error::OutOfBoundPropIndex),
+        },
+        (Condition::CompoundOp(CompoundOp::AND | CompoundOp::OR, conditions), _) => {
+            let mut sum = 0.0;
+            for cond in conditions {
+                sum += score_conditions(cond, doc_field)?;
+            }
+            Ok(sum)
+        }
+        _ => Ok(0.0),
+    }
+}
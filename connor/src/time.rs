@@ -0,0 +1,243 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, Months, TimeZone, Timelike, Utc};
+
+use crate::cond::{Condition, Range, Target};
+use crate::error;
+
+// Clock provides the current time relative times are resolved against.
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+// SystemClock reads the current time of the system.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+// FixedClock always returns the same time.
+#[derive(Clone, Copy, Debug)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    // Weeks start on Monday.
+    Week,
+    Month,
+    Year,
+}
+
+impl TimeUnit {
+    fn from_char(c: char) -> Option<TimeUnit> {
+        match c {
+            's' => Some(TimeUnit::Second),
+            'm' => Some(TimeUnit::Minute),
+            'h' => Some(TimeUnit::Hour),
+            'd' => Some(TimeUnit::Day),
+            'w' => Some(TimeUnit::Week),
+            'M' => Some(TimeUnit::Month),
+            'y' => Some(TimeUnit::Year),
+            _ => None,
+        }
+    }
+}
+
+// RelativeTime is a time expressed relative to the current time, such as `now-7d` for seven
+// days ago or `now/d` for the start of the current day. Offsets are applied in order before
+// the time is truncated to the start of the rounding unit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RelativeTime {
+    pub offsets: Vec<(i64, TimeUnit)>,
+    pub round_down: Option<TimeUnit>,
+}
+
+impl RelativeTime {
+    pub fn now() -> Self {
+        Self {
+            offsets: Vec::new(),
+            round_down: None,
+        }
+    }
+
+    // Returns the time relative to the given clock.
+    pub fn resolve(&self, clock: &dyn Clock) -> error::Result<DateTime<Utc>> {
+        let mut time = clock.now();
+        for (amount, unit) in &self.offsets {
+            time = add(time, *amount, *unit).ok_or(error::Error::ArithmeticOverflow)?;
+        }
+        match self.round_down {
+            Some(unit) => round_down(time, unit).ok_or(error::Error::ArithmeticOverflow),
+            None => Ok(time),
+        }
+    }
+}
+
+impl FromStr for RelativeTime {
+    type Err = error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || error::Error::InvalidRelativeTime(s.to_string());
+        let mut rest = s.strip_prefix("now").ok_or_else(invalid)?;
+        let mut time = RelativeTime::now();
+        while let Some(sign) = rest.chars().next().filter(|c| *c == '+' || *c == '-') {
+            rest = &rest[1..];
+            let digits = rest.chars().take_while(char::is_ascii_digit).count();
+            let amount: i64 = rest[..digits].parse().map_err(|_| invalid())?;
+            rest = &rest[digits..];
+            let mut chars = rest.chars();
+            let unit = chars
+                .next()
+                .and_then(TimeUnit::from_char)
+                .ok_or_else(invalid)?;
+            rest = chars.as_str();
+            time.offsets
+                .push((if sign == '-' { -amount } else { amount }, unit));
+        }
+        if let Some(unit) = rest.strip_prefix('/') {
+            let mut chars = unit.chars();
+            time.round_down = Some(
+                chars
+                    .next()
+                    .and_then(TimeUnit::from_char)
+                    .ok_or_else(invalid)?,
+            );
+            rest = chars.as_str();
+        }
+        if !rest.is_empty() {
+            return Err(invalid());
+        }
+        Ok(time)
+    }
+}
+
+fn add(time: DateTime<Utc>, amount: i64, unit: TimeUnit) -> Option<DateTime<Utc>> {
+    let duration = match unit {
+        TimeUnit::Second => Duration::try_seconds(amount)?,
+        TimeUnit::Minute => Duration::try_minutes(amount)?,
+        TimeUnit::Hour => Duration::try_hours(amount)?,
+        TimeUnit::Day => Duration::try_days(amount)?,
+        TimeUnit::Week => Duration::try_weeks(amount)?,
+        TimeUnit::Month | TimeUnit::Year => {
+            let months = if unit == TimeUnit::Year {
+                amount.checked_mul(12)?
+            } else {
+                amount
+            };
+            let abs_months = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+            return if months < 0 {
+                time.checked_sub_months(abs_months)
+            } else {
+                time.checked_add_months(abs_months)
+            };
+        }
+    };
+    time.checked_add_signed(duration)
+}
+
+fn round_down(time: DateTime<Utc>, unit: TimeUnit) -> Option<DateTime<Utc>> {
+    let (year, month, day) = (time.year(), time.month(), time.day());
+    let start = match unit {
+        TimeUnit::Second => {
+            Utc.with_ymd_and_hms(year, month, day, time.hour(), time.minute(), time.second())
+        }
+        TimeUnit::Minute => Utc.with_ymd_and_hms(year, month, day, time.hour(), time.minute(), 0),
+        TimeUnit::Hour => Utc.with_ymd_and_hms(year, month, day, time.hour(), 0, 0),
+        TimeUnit::Day => Utc.with_ymd_and_hms(year, month, day, 0, 0, 0),
+        TimeUnit::Week => {
+            let days = time.weekday().num_days_from_monday() as i64;
+            let day_start = round_down(time, TimeUnit::Day)?;
+            return day_start.checked_sub_signed(Duration::try_days(days)?);
+        }
+        TimeUnit::Month => Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0),
+        TimeUnit::Year => Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0),
+    };
+    start.single()
+}
+
+// Returns the condition with every relative time replaced by the time relative to the clock.
+pub fn resolve(condition: &Condition, clock: &dyn Clock) -> error::Result<Condition> {
+    Ok(match condition {
+        Condition::TimeOp(op, time) => {
+            Condition::Op(op.clone(), core::doc::Field::DateTime(time.resolve(clock)?))
+        }
+        Condition::Between(range) => Condition::Between(Range {
+            start: resolve_target(&range.start, clock)?,
+            end: resolve_target(&range.end, clock)?,
+            start_inclusive: range.start_inclusive,
+            end_inclusive: range.end_inclusive,
+        }),
+        Condition::Prop(index, inner) => Condition::Prop(*index, Box::new(resolve(inner, clock)?)),
+        Condition::Meta(meta, inner) => {
+            Condition::Meta(meta.clone(), Box::new(resolve(inner, clock)?))
+        }
+        Condition::JsonPath(path, inner) => {
+            Condition::JsonPath(path.clone(), Box::new(resolve(inner, clock)?))
+        }
+        Condition::CompoundOp(op, conditions) => Condition::CompoundOp(
+            op.clone(),
+            conditions
+                .iter()
+                .map(|cond| resolve(cond, clock))
+                .collect::<error::Result<Vec<_>>>()?,
+        ),
+        Condition::Op(_, _)
+        | Condition::FieldOp(_, _)
+        | Condition::ParamOp(_, _)
+        | Condition::ExprOp(_, _, _)
+        | Condition::ArrayOp(_, _)
+        | Condition::Fuzzy(_)
+        | Condition::Within(_)
+        | Condition::Similar(_)
+        | Condition::Custom(_, _)
+        | Condition::JsonExists(_) => condition.clone(),
+    })
+}
+
+fn resolve_target(target: &Target, clock: &dyn Clock) -> error::Result<Target> {
+    match target {
+        Target::Time(time) => Ok(Target::Value(core::doc::Field::DateTime(
+            time.resolve(clock)?,
+        ))),
+        Target::Value(_) => Ok(target.clone()),
+    }
+}
+
+// Returns true if the condition contains a relative time.
+pub fn is_relative(condition: &Condition) -> bool {
+    match condition {
+        Condition::TimeOp(_, _) => true,
+        Condition::Between(range) => {
+            matches!(range.start, Target::Time(_)) || matches!(range.end, Target::Time(_))
+        }
+        Condition::Prop(_, inner) | Condition::Meta(_, inner) | Condition::JsonPath(_, inner) => {
+            is_relative(inner)
+        }
+        Condition::CompoundOp(_, conditions) => conditions.iter().any(is_relative),
+        _ => false,
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use core::doc::{Doc, Field};
+
+use crate::error;
+
+// Metric is the measure used to compare vectors. All measures are undefined for vectors of
+// different dimensions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Metric {
+    // The cosine of the angle between the vectors, from -1 to 1. Undefined for zero vectors.
+    Cosine,
+    // The dot product of the vectors.
+    Dot,
+    // The euclidean distance between the vectors.
+    L2,
+}
+
+impl Metric {
+    // Returns the value of the measure for the vectors.
+    pub fn measure(&self, a: &[f32], b: &[f32]) -> Option<f64> {
+        match self {
+            Metric::Cosine => cosine_similarity(a, b),
+            Metric::Dot => dot_product(a, b),
+            Metric::L2 => l2_distance(a, b),
+        }
+    }
+
+    // Returns a distance derived from the measure, where closer vectors have smaller distances.
+    pub fn distance(&self, a: &[f32], b: &[f32]) -> Option<f64> {
+        let measure = self.measure(a, b)?;
+        Some(match self {
+            Metric::Cosine => 1.0 - measure,
+            Metric::Dot => -measure,
+            Metric::L2 => measure,
+        })
+    }
+
+    // Returns true if the measure is at least as close as the threshold.
+    pub fn within(&self, measure: f64, threshold: f64) -> bool {
+        match self {
+            Metric::Cosine | Metric::Dot => measure >= threshold,
+            Metric::L2 => measure <= threshold,
+        }
+    }
+}
+
+pub fn dot_product(a: &[f32], b: &[f32]) -> Option<f64> {
+    if a.len() != b.len() {
+        return None;
+    }
+    Some(a.iter().zip(b).map(|(a, b)| *a as f64 * *b as f64).sum())
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> Option<f64> {
+    let norms = dot_product(a, a)?.sqrt() * dot_product(b, b)?.sqrt();
+    if norms == 0.0 {
+        return None;
+    }
+    // rounding may push the cosine of parallel vectors slightly out of range
+    Some((dot_product(a, b)? / norms).clamp(-1.0, 1.0))
+}
+
+pub fn l2_distance(a: &[f32], b: &[f32]) -> Option<f64> {
+    if a.len() != b.len() {
+        return None;
+    }
+    let sum: f64 = a
+        .iter()
+        .zip(b)
+        .map(|(a, b)| (*a as f64 - *b as f64).powi(2))
+        .sum();
+    Some(sum.sqrt())
+}
+
+// Threshold matches vectors whose measure against the given vector is within the threshold:
+// a cosine similarity or a dot product of at least the threshold, or an L2 distance of at
+// most the threshold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Threshold {
+    pub vector: Vec<f32>,
+    pub metric: Metric,
+    pub threshold: f64,
+}
+
+impl Threshold {
+    // Returns true if the Vector or OptionalVector field is within the threshold. Fails if the
+    // vector's dimension differs from the threshold's, like the HNSW index does.
+    pub fn matches(&self, field: &Field) -> error::Result<bool> {
+        let val = match vector(field) {
+            Some(val) => val,
+            None => return Ok(false),
+        };
+        if val.len() != self.vector.len() {
+            return Err(error::Error::DimensionMismatch(
+                self.vector.len(),
+                val.len(),
+            ));
+        }
+        match self.metric.measure(&self.vector, val) {
+            Some(measure) => Ok(self.metric.within(measure, self.threshold)),
+            None => Ok(false),
+        }
+    }
+}
+
+// Neighbor is a document found by a nearest-neighbour search.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Neighbor {
+    // The position of the document in the searched collection.
+    pub index: usize,
+    // The measure of the document's vector against the query.
+    pub measure: f64,
+}
+
+// Returns the k documents whose vector field at the prop index is closest to the query,
+// closest first. Documents without a vector of the query's dimension are left out, and
+// documents at the same distance keep their order.
+pub fn top_k(
+    docs: &[Doc],
+    prop_index: usize,
+    query: &[f32],
+    metric: Metric,
+    k: usize,
+) -> Vec<Neighbor> {
+    let mut found: Vec<(f64, Neighbor)> = docs
+        .iter()
+        .enumerate()
+        .filter_map(|(index, doc)| {
+            let val = vector(doc.fields.get(prop_index)?)?;
+            let measure = metric.measure(query, val)?;
+            let distance = metric.distance(query, val)?;
+            Some((distance, Neighbor { index, measure }))
+        })
+        .collect();
+    found.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    found
+        .into_iter()
+        .take(k)
+        .map(|(_, neighbor)| neighbor)
+        .collect()
+}
+
+pub(crate) fn vector(field: &Field) -> Option<&[f32]> {
+    match field {
+        Field::Vector(val) | Field::OptionalVector(Some(val)) => Some(val),
+        _ => None,
+    }
+}
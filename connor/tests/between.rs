@@ -0,0 +1,221 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::*;
+use connor::cond::*;
+use connor::error::Error;
+use connor::span::*;
+use connor::time::*;
+use core::doc::Field;
+
+fn between(start: Field, end: Field, start_inclusive: bool, end_inclusive: bool) -> Condition {
+    Condition::Between(Range {
+        start: Target::Value(start),
+        end: Target::Value(end),
+        start_inclusive,
+        end_inclusive,
+    })
+}
+
+fn relative(time: &str) -> RelativeTime {
+    time.parse().unwrap()
+}
+
+fn clock() -> FixedClock {
+    // a Wednesday
+    FixedClock(Utc.with_ymd_and_hms(2024, 4, 10, 15, 30, 45).unwrap())
+}
+
+fn date_time(year: i32, month: u32, day: u32, hour: u32) -> Field {
+    Field::DateTime(Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap())
+}
+
+#[test]
+fn test_between_bounds() {
+    let value = Field::Int(5);
+    expect(
+        match_conditions(&between(Field::Int(5), Field::Int(9), true, true), &value),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&between(Field::Int(5), Field::Int(9), false, true), &value),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(&between(Field::Int(1), Field::Int(5), true, false), &value),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(
+            &between(Field::Float(4.5), Field::Int(5), true, true),
+            &value,
+        ),
+        true,
+        false,
+    );
+}
+
+#[test]
+fn test_between_types() {
+    let cond = between(
+        Field::String("b".to_string()),
+        Field::String("d".to_string()),
+        true,
+        true,
+    );
+    expect(
+        match_conditions(&cond, &Field::String("c".to_string())),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&cond, &Field::String("e".to_string())),
+        false,
+        false,
+    );
+    expect(match_conditions(&cond, &Field::Int(3)), false, false);
+
+    let cond = between(yesterday(), now(), true, true);
+    expect(match_conditions(&cond, &now()), true, false);
+
+    // nulls and values without order never match
+    expect(match_conditions(&cond, &Field::Null), false, false);
+    expect(
+        match_conditions(&cond, &Field::OptionalDateTime(None)),
+        false,
+        false,
+    );
+    let cond = between(Field::Bool(false), Field::Bool(true), true, true);
+    expect(match_conditions(&cond, &Field::Bool(true)), false, false);
+    let cond = between(Field::Null, Field::Int(1), true, true);
+    expect(match_conditions(&cond, &Field::Int(0)), false, false);
+}
+
+#[test]
+fn test_between_spans() {
+    let scan = extract_spans(&between(Field::Int(1), Field::Int(5), true, false), &[]);
+    assert!(scan.residual.is_none());
+    assert_eq!(scan.spans.len(), 1);
+    assert!(scan.spans[0].contains(&Field::Int(1)));
+    assert!(!scan.spans[0].contains(&Field::Int(5)));
+
+    let scan = extract_spans(&between(Field::Int(5), Field::Int(1), true, true), &[]);
+    assert!(scan.spans.is_empty());
+}
+
+#[test]
+fn test_relative_time_parse() {
+    let time = relative("now-7d+1h/d");
+    assert_eq!(time.offsets, vec![(-7, TimeUnit::Day), (1, TimeUnit::Hour)]);
+    assert_eq!(time.round_down, Some(TimeUnit::Day));
+    assert_eq!(relative("now"), RelativeTime::now());
+
+    for invalid in [
+        "", "today", "now-", "now-d", "now-7x", "now/", "now/d/d", "now 1d",
+    ] {
+        let result = invalid.parse::<RelativeTime>();
+        assert!(
+            matches!(result, Err(Error::InvalidRelativeTime(_))),
+            "Expected error for {}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn test_relative_time_resolve() {
+    let clock = clock();
+    let resolve = |time: &str| relative(time).resolve(&clock).unwrap();
+    let at = |year, month, day, hour, min| {
+        Utc.with_ymd_and_hms(year, month, day, hour, min, 0)
+            .unwrap()
+    };
+
+    assert_eq!(resolve("now/d"), at(2024, 4, 10, 0, 0));
+    assert_eq!(resolve("now-7d/d"), at(2024, 4, 3, 0, 0));
+    assert_eq!(resolve("now/w"), at(2024, 4, 8, 0, 0));
+    assert_eq!(resolve("now-2M/M"), at(2024, 2, 1, 0, 0));
+    assert_eq!(resolve("now+1y/y"), at(2025, 1, 1, 0, 0));
+    assert_eq!(resolve("now-30m/h"), at(2024, 4, 10, 15, 0));
+
+    let result = relative("now-999999999y").resolve(&clock);
+    assert!(matches!(result, Err(Error::ArithmeticOverflow)));
+}
+
+#[test]
+fn test_time_op_with_clock() {
+    // created in the last 7 days
+    let cond = Condition::TimeOp(Op::GE, relative("now-7d"));
+    let clock = clock();
+
+    expect(
+        match_conditions_at(&cond, &date_time(2024, 4, 5, 0), &clock),
+        true,
+        false,
+    );
+    expect(
+        match_conditions_at(&cond, &date_time(2024, 4, 1, 0), &clock),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_between_relative_times() {
+    // yesterday, from its start up to the start of today
+    let cond = Condition::Prop(
+        0,
+        Box::new(Condition::Between(Range {
+            start: Target::Time(relative("now-1d/d")),
+            end: Target::Time(relative("now/d")),
+            start_inclusive: true,
+            end_inclusive: false,
+        })),
+    );
+    let clock = clock();
+    let doc = |field: Field| {
+        let mut doc = get_doc();
+        doc.fields = vec![field];
+        Field::Doc(doc)
+    };
+
+    expect(
+        match_conditions_at(&cond, &doc(date_time(2024, 4, 9, 0)), &clock),
+        true,
+        false,
+    );
+    expect(
+        match_conditions_at(&cond, &doc(date_time(2024, 4, 9, 23)), &clock),
+        true,
+        false,
+    );
+    expect(
+        match_conditions_at(&cond, &doc(date_time(2024, 4, 10, 0)), &clock),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_relative_time_with_system_clock() {
+    let cond = Condition::TimeOp(Op::GT, relative("now-1h"));
+    expect(
+        match_conditions(&cond, &Field::DateTime(Utc::now())),
+        true,
+        false,
+    );
+    expect(match_conditions(&cond, &yesterday()), false, false);
+}
@@ -0,0 +1,180 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use common::*;
+use connor::cond::*;
+use connor::span::*;
+use core::doc::Field;
+
+fn blob(bytes: &[u8]) -> Field {
+    Field::Blob(bytes.to_vec())
+}
+
+fn op(op: Op, target: Field) -> Condition {
+    Condition::Op(op, target)
+}
+
+#[test]
+fn test_blob_equality() {
+    let hash = blob(&[0xde, 0xad, 0xbe, 0xef]);
+
+    expect(
+        match_conditions(&op(Op::EQ, hash.clone()), &hash),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::EQ, blob(&[0xde, 0xad])), &hash),
+        false,
+        false,
+    );
+    expect(match_conditions(&op(Op::NE, blob(&[])), &hash), true, false);
+    expect(
+        match_conditions(
+            &op(Op::EQ, hash.clone()),
+            &Field::OptionalBlob(Some(vec![0xde, 0xad, 0xbe, 0xef])),
+        ),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::EQ, hash.clone()), &Field::OptionalBlob(None)),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::EQ, hash), &Field::String("deadbeef".to_string())),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_blob_in() {
+    let cond = op(Op::IN, Field::BlobArray(vec![vec![1, 2], vec![3]]));
+    expect(match_conditions(&cond, &blob(&[3])), true, false);
+    expect(match_conditions(&cond, &blob(&[1])), false, false);
+
+    let cond = op(Op::NIN, Field::BlobArray(vec![vec![3]]));
+    expect(match_conditions(&cond, &blob(&[3])), false, false);
+}
+
+#[test]
+fn test_blob_comparison() {
+    let value = blob(&[0x01, 0xff]);
+
+    // bytes compare lexicographically, and a prefix sorts before longer blobs
+    expect(
+        match_conditions(&op(Op::GT, blob(&[0x01])), &value),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::GT, blob(&[0x01, 0xff])), &value),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::GE, blob(&[0x01, 0xff])), &value),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::LT, blob(&[0x02])), &value),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::LE, blob(&[0x01, 0xfe, 0xff])), &value),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::GT, Field::Int(0)), &value),
+        false,
+        false,
+    );
+
+    let cond = Condition::Between(Range {
+        start: Target::Value(blob(&[0x01])),
+        end: Target::Value(blob(&[0x02])),
+        start_inclusive: true,
+        end_inclusive: false,
+    });
+    expect(match_conditions(&cond, &value), true, false);
+    expect(match_conditions(&cond, &blob(&[0x02])), false, false);
+}
+
+#[test]
+fn test_prefix() {
+    let value = blob(&[0x12, 0x34, 0x56]);
+
+    expect(
+        match_conditions(&op(Op::PREFIX, blob(&[0x12, 0x34])), &value),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::PREFIX, blob(&[])), &value),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::PREFIX, blob(&[0x34])), &value),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::PREFIX, blob(&[0x12, 0x34, 0x56, 0x78])), &value),
+        false,
+        false,
+    );
+
+    let cond = op(Op::PREFIX, Field::String("ab".to_string()));
+    expect(
+        match_conditions(&cond, &Field::String("abc".to_string())),
+        true,
+        false,
+    );
+    expect(match_conditions(&cond, &blob(b"abc")), false, false);
+}
+
+#[test]
+fn test_blob_arrays() {
+    let values = Field::BlobArray(vec![vec![1], vec![2, 3]]);
+
+    let cond = Condition::ArrayOp(ArrayOp::CONTAINS, Field::BlobArray(vec![vec![2, 3]]));
+    expect(match_conditions(&cond, &values), true, false);
+
+    let cond = Condition::ArrayOp(ArrayOp::LEN_EQ, Field::Int(2));
+    expect(match_conditions(&cond, &values), true, false);
+
+    let cond = op(Op::EQ, Field::BlobArray(vec![vec![1], vec![2, 3]]));
+    expect(match_conditions(&cond, &values), true, false);
+}
+
+#[test]
+fn test_prefix_spans() {
+    let cond = Condition::Prop(1, Box::new(op(Op::PREFIX, blob(&[0x12, 0xff]))));
+    let scan = extract_spans(&cond, &[1]);
+    assert!(scan.residual.is_none());
+    assert_eq!(scan.spans.len(), 1);
+    assert!(scan.spans[0].contains(&blob(&[0x12, 0xff, 0x00])));
+    assert!(scan.spans[0].contains(&blob(&[0x12, 0xff, 0xff, 0xff])));
+    assert!(!scan.spans[0].contains(&blob(&[0x12, 0xfe])));
+    assert!(!scan.spans[0].contains(&blob(&[0x13])));
+
+    // every blob starting with 0xff bytes only sorts after the prefix
+    let cond = op(Op::PREFIX, blob(&[0xff, 0xff]));
+    let scan = extract_spans(&cond, &[]);
+    assert!(matches!(scan.spans[0].end, Bound::Unbounded));
+}
@@ -0,0 +1,192 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use common::*;
+use connor::cond::*;
+use connor::error::Error;
+use connor::expr::*;
+use core::doc::Field;
+
+fn field(index: usize) -> Expr {
+    Expr::Field(FieldRef {
+        parent: 0,
+        path: vec![index],
+    })
+}
+
+fn lit(field: Field) -> Expr {
+    Expr::Literal(field)
+}
+
+fn arith(op: ArithOp, left: Expr, right: Expr) -> Expr {
+    Expr::Arith(op, Box::new(left), Box::new(right))
+}
+
+fn call(func: Func, arg: Expr) -> Expr {
+    Expr::Call(func, Box::new(arg))
+}
+
+fn order_doc() -> Field {
+    let mut doc = get_doc();
+    doc.fields = vec![
+        Field::Int(3),
+        Field::Float(400.0),
+        Field::String("Bob@Example.com".to_string()),
+        now(),
+        Field::OptionalInt(None),
+        Field::Int(i64::MAX),
+    ];
+    Field::Doc(doc)
+}
+
+#[test]
+fn test_expr_arithmetic() {
+    // quantity * price > 1000
+    let cond = Condition::ExprOp(
+        Op::GT,
+        arith(ArithOp::Mul, field(0), field(1)),
+        lit(Field::Int(1000)),
+    );
+    expect(match_conditions(&cond, &order_doc()), true, false);
+
+    let cond = Condition::ExprOp(
+        Op::EQ,
+        arith(
+            ArithOp::Sub,
+            arith(ArithOp::Add, field(0), lit(Field::Int(7))),
+            arith(ArithOp::Div, lit(Field::Int(7)), lit(Field::Int(2))),
+        ),
+        lit(Field::Int(7)),
+    );
+    expect(match_conditions(&cond, &order_doc()), true, false);
+}
+
+#[test]
+fn test_expr_on_either_side() {
+    let cond = Condition::ExprOp(
+        Op::LT,
+        lit(Field::Int(1000)),
+        arith(ArithOp::Mul, field(0), field(1)),
+    );
+    expect(match_conditions(&cond, &order_doc()), true, false);
+}
+
+#[test]
+fn test_expr_string_functions() {
+    let cond = Condition::ExprOp(
+        Op::EQ,
+        call(Func::Lower, field(2)),
+        lit(Field::String("bob@example.com".to_string())),
+    );
+    expect(match_conditions(&cond, &order_doc()), true, false);
+
+    let cond = Condition::ExprOp(Op::LT, call(Func::Length, field(2)), lit(Field::Int(5)));
+    expect(match_conditions(&cond, &order_doc()), false, false);
+
+    let cond = Condition::ExprOp(
+        Op::LIKE,
+        call(Func::Upper, field(2)),
+        lit(Field::String("%EXAMPLE%".to_string())),
+    );
+    expect(match_conditions(&cond, &order_doc()), true, false);
+}
+
+#[test]
+fn test_expr_date_functions() {
+    let cond = Condition::ExprOp(Op::EQ, call(Func::Year, field(3)), lit(Field::Int(2024)));
+    expect(match_conditions(&cond, &order_doc()), true, false);
+
+    let cond = Condition::ExprOp(
+        Op::IN,
+        call(Func::Month, field(3)),
+        lit(Field::IntArray(vec![1, 2, 3])),
+    );
+    expect(match_conditions(&cond, &order_doc()), false, false);
+}
+
+#[test]
+fn test_expr_matched_value() {
+    let cond = Condition::Prop(
+        2,
+        Box::new(Condition::ExprOp(
+            Op::EQ,
+            call(Func::Length, Expr::Value),
+            lit(Field::Int(15)),
+        )),
+    );
+    expect(match_conditions(&cond, &order_doc()), true, false);
+}
+
+#[test]
+fn test_expr_null_propagates() {
+    let cond = Condition::ExprOp(
+        Op::GT,
+        arith(ArithOp::Add, field(4), lit(Field::Int(1))),
+        lit(Field::Int(0)),
+    );
+    expect(match_conditions(&cond, &order_doc()), false, false);
+}
+
+#[test]
+fn test_expr_errors() {
+    let cond = Condition::ExprOp(
+        Op::EQ,
+        arith(ArithOp::Add, field(5), lit(Field::Int(1))),
+        lit(Field::Int(0)),
+    );
+    let result = match_conditions(&cond, &order_doc());
+    assert!(matches!(result, Err(Error::ArithmeticOverflow)));
+
+    let cond = Condition::ExprOp(
+        Op::EQ,
+        arith(ArithOp::Div, field(0), lit(Field::Int(0))),
+        lit(Field::Int(0)),
+    );
+    let result = match_conditions(&cond, &order_doc());
+    assert!(matches!(result, Err(Error::DivisionByZero)));
+
+    let cond = Condition::ExprOp(
+        Op::EQ,
+        arith(ArithOp::Div, field(1), lit(Field::Float(0.0))),
+        lit(Field::Int(0)),
+    );
+    let result = match_conditions(&cond, &order_doc());
+    assert!(matches!(result, Err(Error::DivisionByZero)));
+
+    let cond = Condition::ExprOp(
+        Op::EQ,
+        arith(
+            ArithOp::Mul,
+            lit(Field::Float(f64::MAX)),
+            lit(Field::Int(2)),
+        ),
+        lit(Field::Int(0)),
+    );
+    let result = match_conditions(&cond, &order_doc());
+    assert!(matches!(result, Err(Error::ArithmeticOverflow)));
+
+    let cond = Condition::ExprOp(
+        Op::EQ,
+        arith(ArithOp::Add, field(2), lit(Field::Int(1))),
+        lit(Field::Int(0)),
+    );
+    let result = match_conditions(&cond, &order_doc());
+    assert!(matches!(result, Err(Error::InvalidExprOperand)));
+}
+
+#[test]
+fn test_expr_field_refs() {
+    let expr = arith(ArithOp::Mul, field(0), call(Func::Abs, field(1)));
+    let refs = expr.field_refs();
+    assert_eq!(refs.len(), 2);
+    assert_eq!(refs[1].path, vec![1]);
+}
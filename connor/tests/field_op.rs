@@ -0,0 +1,144 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use common::*;
+use connor::cond::*;
+use connor::error::Error;
+use core::doc::Field;
+
+fn field_op(index: usize, op: Op, parent: usize, path: Vec<usize>) -> Condition {
+    Condition::Prop(
+        index,
+        Box::new(Condition::FieldOp(op, FieldRef { parent, path })),
+    )
+}
+
+fn doc_with(fields: Vec<Field>) -> Field {
+    let mut doc = get_doc();
+    doc.fields = fields;
+    Field::Doc(doc)
+}
+
+#[test]
+fn test_field_op_date_time() {
+    let doc = doc_with(vec![now(), yesterday()]);
+
+    let cond = field_op(0, Op::GT, 0, vec![1]);
+    expect(match_conditions(&cond, &doc), true, false);
+
+    let cond = field_op(1, Op::GT, 0, vec![0]);
+    expect(match_conditions(&cond, &doc), false, false);
+
+    let cond = field_op(1, Op::LE, 0, vec![0]);
+    expect(match_conditions(&cond, &doc), true, false);
+}
+
+#[test]
+fn test_field_op_int_float_coercion() {
+    let doc = doc_with(vec![Field::Int(5), Field::Float(5.0), Field::Float(7.5)]);
+
+    expect(
+        match_conditions(&field_op(0, Op::EQ, 0, vec![1]), &doc),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&field_op(0, Op::LT, 0, vec![2]), &doc),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&field_op(2, Op::NE, 0, vec![0]), &doc),
+        true,
+        false,
+    );
+}
+
+#[test]
+fn test_field_op_optional_fields() {
+    let doc = doc_with(vec![
+        Field::OptionalInt(Some(3)),
+        Field::OptionalInt(Some(4)),
+        Field::OptionalInt(None),
+    ]);
+
+    expect(
+        match_conditions(&field_op(0, Op::LT, 0, vec![1]), &doc),
+        true,
+        false,
+    );
+    // a field without value never matches
+    expect(
+        match_conditions(&field_op(2, Op::NE, 0, vec![0]), &doc),
+        false,
+        false,
+    );
+    // comparisons against a field without value never match
+    expect(
+        match_conditions(&field_op(0, Op::GT, 0, vec![2]), &doc),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_field_op_parent_doc() {
+    let child = doc_with(vec![Field::Int(10)]);
+    let parent = match child.clone() {
+        Field::Doc(child) => doc_with(vec![Field::Int(7), Field::DocArray(vec![child])]),
+        _ => unreachable!(),
+    };
+
+    let cond = Condition::Prop(1, Box::new(field_op(0, Op::GT, 1, vec![0])));
+    expect(match_conditions(&cond, &parent), true, false);
+
+    let cond = Condition::Prop(1, Box::new(field_op(0, Op::LT, 1, vec![0])));
+    expect(match_conditions(&cond, &parent), false, false);
+}
+
+#[test]
+fn test_field_op_nested_path() {
+    let parent = doc_with(vec![Field::Int(7), doc_with(vec![Field::Int(7)])]);
+
+    expect(
+        match_conditions(&field_op(0, Op::EQ, 0, vec![1, 0]), &parent),
+        true,
+        false,
+    );
+    // the path goes through a field that is not a document
+    expect(
+        match_conditions(&field_op(1, Op::EQ, 0, vec![0, 0]), &parent),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_field_op_errors() {
+    let doc = doc_with(vec![Field::Int(5)]);
+
+    let result = match_conditions(&field_op(0, Op::EQ, 0, vec![3]), &doc);
+    assert!(matches!(result, Err(Error::OutOfBoundPropIndex)));
+
+    let result = match_conditions(&field_op(0, Op::EQ, 1, vec![0]), &doc);
+    assert!(matches!(result, Err(Error::UnresolvedFieldRef)));
+
+    let cond = Condition::FieldOp(
+        Op::EQ,
+        FieldRef {
+            parent: 0,
+            path: vec![0],
+        },
+    );
+    let result = match_conditions(&cond, &Field::Int(5));
+    assert!(matches!(result, Err(Error::UnresolvedFieldRef)));
+}
@@ -0,0 +1,111 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use common::*;
+use connor::cond::*;
+use connor::fuzzy::*;
+use connor::text::score_conditions;
+use core::doc::Field;
+
+fn fuzzy(value: &str, similarity: Similarity, case_insensitive: bool) -> Fuzzy {
+    Fuzzy {
+        value: value.to_string(),
+        similarity,
+        case_insensitive,
+    }
+}
+
+fn text(value: &str) -> Field {
+    Field::String(value.to_string())
+}
+
+#[test]
+fn test_levenshtein() {
+    assert_eq!(levenshtein("kitten", "sitting"), 3);
+    assert_eq!(levenshtein("", "abc"), 3);
+    assert_eq!(levenshtein("jonathan", "jonathan"), 0);
+    assert_eq!(levenshtein("über", "uber"), 1);
+}
+
+#[test]
+fn test_trigram_similarity() {
+    assert_eq!(trigram_similarity("word", "word"), 1.0);
+    assert_eq!(trigram_similarity("abc", "xyz"), 0.0);
+    assert_eq!(trigram_similarity("jonathan", "jonathon"), 0.5);
+}
+
+#[test]
+fn test_fuzzy_levenshtein() {
+    let cond = Condition::Fuzzy(fuzzy("jonathan", Similarity::Levenshtein(1), false));
+    expect(match_conditions(&cond, &text("jonathon")), true, false);
+    expect(match_conditions(&cond, &text("johnathon")), false, false);
+    expect(match_conditions(&cond, &text("Jonathan")), true, false);
+    expect(match_conditions(&cond, &text("JONATHAN")), false, false);
+}
+
+#[test]
+fn test_fuzzy_case_insensitive() {
+    let cond = Condition::Fuzzy(fuzzy("Jonathan", Similarity::Levenshtein(1), true));
+    expect(match_conditions(&cond, &text("JONATHON")), true, false);
+}
+
+#[test]
+fn test_fuzzy_trigram() {
+    let cond = Condition::Fuzzy(fuzzy("jonathan", Similarity::Trigram(0.5), false));
+    expect(match_conditions(&cond, &text("jonathon")), true, false);
+    expect(match_conditions(&cond, &text("jon")), false, false);
+}
+
+#[test]
+fn test_fuzzy_field_types() {
+    let cond = Condition::Fuzzy(fuzzy("alice", Similarity::Levenshtein(1), false));
+    expect(
+        match_conditions(&cond, &Field::OptionalString(Some("alicd".to_string()))),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&cond, &Field::OptionalString(None)),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(
+            &cond,
+            &Field::StringArray(vec!["bob".to_string(), "alise".to_string()]),
+        ),
+        true,
+        false,
+    );
+    expect(match_conditions(&cond, &Field::Int(1)), false, false);
+}
+
+#[test]
+fn test_fuzzy_score() {
+    let fuzzy = fuzzy("jonathan", Similarity::Levenshtein(2), false);
+    assert_eq!(fuzzy.score(&text("jonathan")), Some(1.0));
+    assert_eq!(fuzzy.score(&text("jonathon")), Some(1.0 - 1.0 / 8.0));
+    assert_eq!(
+        fuzzy.score(&Field::StringArray(vec![
+            "x".to_string(),
+            "jonathon".to_string()
+        ])),
+        Some(1.0 - 1.0 / 8.0)
+    );
+    assert_eq!(fuzzy.score(&Field::Int(1)), None);
+
+    let mut doc = get_doc();
+    doc.fields[1] = text("jonathon");
+    let cond = Condition::Prop(1, Box::new(Condition::Fuzzy(fuzzy)));
+    let score = score_conditions(&cond, &Field::Doc(doc)).unwrap();
+    assert_eq!(score, 1.0 - 1.0 / 8.0);
+}
@@ -0,0 +1,188 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use common::*;
+use connor::cond::*;
+use connor::geo::*;
+use core::doc::{Field, GeoPoint};
+
+fn point(lat: f64, lon: f64) -> GeoPoint {
+    GeoPoint { lat, lon }
+}
+
+fn paris() -> GeoPoint {
+    point(48.8566, 2.3522)
+}
+
+fn london() -> GeoPoint {
+    point(51.5074, -0.1278)
+}
+
+fn within(shape: Shape) -> Condition {
+    Condition::Within(shape)
+}
+
+fn assert_km(actual_meters: f64, expected_km: f64) {
+    let actual_km = actual_meters / 1000.0;
+    assert!(
+        (actual_km - expected_km).abs() < 1.0,
+        "Expected about {} km, got {} km",
+        expected_km,
+        actual_km
+    );
+}
+
+#[test]
+fn test_reference_distances() {
+    assert_km(distance(&paris(), &london()), 343.6);
+    assert_km(
+        distance(&point(40.7128, -74.0060), &point(34.0522, -118.2437)),
+        3935.7,
+    );
+    // a quarter of a great circle
+    assert_km(distance(&point(0.0, 0.0), &point(0.0, 90.0)), 10007.5);
+    assert_eq!(distance(&paris(), &paris()), 0.0);
+    assert_km(distance(&point(0.0, 0.0), &point(0.0, 180.0)), 20015.1);
+}
+
+#[test]
+fn test_within_radius() {
+    let cond = within(Shape::Radius(london(), 350_000.0));
+    expect(
+        match_conditions(&cond, &Field::GeoPoint(paris())),
+        true,
+        false,
+    );
+
+    let cond = within(Shape::Radius(london(), 340_000.0));
+    expect(
+        match_conditions(&cond, &Field::GeoPoint(paris())),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_within_bbox() {
+    let europe = within(Shape::BBox(point(35.0, -10.0), point(60.0, 30.0)));
+    expect(
+        match_conditions(&europe, &Field::GeoPoint(paris())),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&europe, &Field::GeoPoint(point(40.7128, -74.0060))),
+        false,
+        false,
+    );
+
+    // a box around Fiji crossing the antimeridian
+    let fiji = within(Shape::BBox(point(-21.0, 177.0), point(-12.0, -178.0)));
+    expect(
+        match_conditions(&fiji, &Field::GeoPoint(point(-17.0, 179.5))),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&fiji, &Field::GeoPoint(point(-17.0, -179.5))),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&fiji, &Field::GeoPoint(point(-17.0, 170.0))),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_within_polygon() {
+    // a concave "L" shape
+    let shape = within(Shape::Polygon(vec![
+        point(0.0, 0.0),
+        point(0.0, 4.0),
+        point(1.0, 4.0),
+        point(1.0, 1.0),
+        point(4.0, 1.0),
+        point(4.0, 0.0),
+    ]));
+    expect(
+        match_conditions(&shape, &Field::GeoPoint(point(0.5, 3.0))),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&shape, &Field::GeoPoint(point(3.0, 0.5))),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&shape, &Field::GeoPoint(point(3.0, 3.0))),
+        false,
+        false,
+    );
+
+    let degenerate = within(Shape::Polygon(vec![point(0.0, 0.0), point(1.0, 1.0)]));
+    expect(
+        match_conditions(&degenerate, &Field::GeoPoint(point(0.5, 0.5))),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_within_optional_and_arrays() {
+    let cond = within(Shape::Radius(paris(), 1000.0));
+    expect(
+        match_conditions(&cond, &Field::OptionalGeoPoint(Some(paris()))),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&cond, &Field::OptionalGeoPoint(None)),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(&cond, &Field::GeoPointArray(vec![london(), paris()])),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(
+            &cond,
+            &Field::OptionalGeoPointArray(vec![None, Some(london())]),
+        ),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(&cond, &Field::Float(48.8566)),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_geo_point_equality() {
+    let cond = Condition::Op(Op::EQ, Field::GeoPoint(paris()));
+    expect(
+        match_conditions(&cond, &Field::OptionalGeoPoint(Some(paris()))),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&cond, &Field::GeoPoint(london())),
+        false,
+        false,
+    );
+}
@@ -0,0 +1,201 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use common::*;
+use connor::cond::*;
+use connor::implication::*;
+use core::doc::Field;
+
+fn prop(index: usize, cond: Condition) -> Condition {
+    Condition::Prop(index, Box::new(cond))
+}
+
+fn op(index: usize, op: Op, field: Field) -> Condition {
+    prop(index, Condition::Op(op, field))
+}
+
+fn and(conditions: Vec<Condition>) -> Condition {
+    Condition::CompoundOp(CompoundOp::AND, conditions)
+}
+
+fn or(conditions: Vec<Condition>) -> Condition {
+    Condition::CompoundOp(CompoundOp::OR, conditions)
+}
+
+fn not(condition: Condition) -> Condition {
+    Condition::CompoundOp(CompoundOp::NOT, vec![condition])
+}
+
+#[test]
+fn test_implies_ranges() {
+    let a = op(0, Op::GT, Field::Int(10));
+    let b = op(0, Op::GE, Field::Int(5));
+    assert_eq!(implies(&a, &b), Answer::Yes);
+    assert_eq!(implies(&b, &a), Answer::No);
+
+    let a = and(vec![
+        op(0, Op::GE, Field::Int(3)),
+        op(0, Op::LE, Field::Int(4)),
+    ]);
+    let b = op(0, Op::LT, Field::Float(4.5));
+    assert_eq!(implies(&a, &b), Answer::Yes);
+}
+
+#[test]
+fn test_implies_eq_and_in() {
+    let a = op(0, Op::EQ, Field::Int(5));
+    let b = op(0, Op::IN, Field::IntArray(vec![4, 5, 6]));
+    assert_eq!(implies(&a, &b), Answer::Yes);
+    assert_eq!(implies(&b, &a), Answer::No);
+
+    let a = op(0, Op::IN, Field::IntArray(vec![4, 6]));
+    let b = op(0, Op::NE, Field::Int(5));
+    assert_eq!(implies(&a, &b), Answer::Yes);
+
+    let a = op(0, Op::NIN, Field::IntArray(vec![4, 6]));
+    assert_eq!(implies(&a, &b), Answer::No);
+}
+
+#[test]
+fn test_implies_other_prop_is_not_implied() {
+    let a = op(0, Op::EQ, Field::Int(5));
+    let b = and(vec![
+        op(0, Op::EQ, Field::Int(5)),
+        op(1, Op::EQ, get_field(1)),
+    ]);
+    assert_eq!(implies(&b, &a), Answer::Yes);
+    assert_eq!(implies(&a, &b), Answer::No);
+}
+
+#[test]
+fn test_implies_or() {
+    let a = or(vec![
+        op(0, Op::EQ, Field::Int(1)),
+        op(0, Op::EQ, Field::Int(2)),
+    ]);
+    let b = op(0, Op::LT, Field::Int(3));
+    assert_eq!(implies(&a, &b), Answer::Yes);
+
+    let b = or(vec![
+        op(0, Op::LT, Field::Int(2)),
+        op(0, Op::GT, Field::Int(1)),
+    ]);
+    assert_eq!(implies(&a, &b), Answer::Yes);
+}
+
+#[test]
+fn test_implies_null() {
+    let a = op(0, Op::EQ, Field::Null);
+    let b = op(0, Op::LT, Field::Int(3));
+    assert_eq!(implies(&a, &b), Answer::No);
+    assert_eq!(is_disjoint(&a, &b), Answer::Yes);
+
+    let a = op(0, Op::GT, Field::Int(3));
+    let b = op(0, Op::NE, Field::Null);
+    assert_eq!(implies(&a, &b), Answer::Yes);
+}
+
+#[test]
+fn test_implies_not_is_weaker_than_ne() {
+    // an optional field without a value matches NOT EQ, but never NE
+    let a = not(op(0, Op::EQ, Field::Int(5)));
+    let b = op(0, Op::NE, Field::Int(5));
+    assert_eq!(implies(&b, &a), Answer::Yes);
+    assert_eq!(implies(&a, &b), Answer::No);
+}
+
+#[test]
+fn test_disjoint() {
+    let a = op(0, Op::LT, Field::Int(5));
+    let b = op(0, Op::GE, Field::Int(5));
+    assert_eq!(is_disjoint(&a, &b), Answer::Yes);
+
+    let b = op(0, Op::GE, Field::Int(4));
+    assert_eq!(is_disjoint(&a, &b), Answer::No);
+
+    let a = op(0, Op::EQ, Field::String("a".to_string()));
+    let b = op(0, Op::IN, Field::StringArray(vec!["b".to_string()]));
+    assert_eq!(is_disjoint(&a, &b), Answer::Yes);
+}
+
+#[test]
+fn test_unsatisfiable() {
+    let cond = and(vec![
+        op(0, Op::GT, Field::Int(5)),
+        op(0, Op::LT, Field::Int(3)),
+    ]);
+    assert_eq!(is_unsatisfiable(&cond), Answer::Yes);
+
+    let cond = and(vec![
+        op(0, Op::EQ, Field::Bool(true)),
+        not(op(0, Op::EQ, Field::Bool(true))),
+    ]);
+    assert_eq!(is_unsatisfiable(&cond), Answer::Yes);
+
+    let cond = op(0, Op::GT, Field::Int(5));
+    assert_eq!(is_unsatisfiable(&cond), Answer::No);
+}
+
+#[test]
+fn test_unsatisfiable_needs_value_type() {
+    // only a Float prop can be strictly between 5 and 6
+    let cond = and(vec![
+        op(0, Op::GT, Field::Int(5)),
+        op(0, Op::LT, Field::Int(6)),
+    ]);
+    assert_eq!(is_unsatisfiable(&cond), Answer::Unknown);
+}
+
+#[test]
+fn test_like_is_unknown() {
+    let a = op(1, Op::LIKE, Field::String("%glue%".to_string()));
+    let b = op(1, Op::LIKE, Field::String("%glue".to_string()));
+    assert_eq!(implies(&a, &b), Answer::Unknown);
+    assert_eq!(is_unsatisfiable(&a), Answer::Unknown);
+
+    // the undecidable part doesn't matter if the rest is contradictory
+    let cond = and(vec![
+        a,
+        op(0, Op::EQ, Field::Int(1)),
+        op(0, Op::EQ, Field::Int(2)),
+    ]);
+    assert_eq!(is_unsatisfiable(&cond), Answer::Yes);
+}
+
+#[test]
+fn test_like_prefix() {
+    let a = op(1, Op::LIKE, Field::String("abc%".to_string()));
+    let b = op(1, Op::LIKE, Field::String("ab%".to_string()));
+    assert_eq!(implies(&a, &b), Answer::Yes);
+    assert_eq!(implies(&b, &a), Answer::No);
+}
+
+#[test]
+fn test_implies_nested() {
+    let a = prop(5, op(0, Op::GT, Field::Int(10)));
+    let b = prop(5, op(0, Op::GT, Field::Int(1)));
+    assert_eq!(implies(&a, &b), Answer::Yes);
+    assert_eq!(implies(&b, &a), Answer::No);
+
+    // two conditions on a nested array may be matched by different documents
+    let cond = and(vec![
+        prop(5, op(0, Op::EQ, Field::Int(1))),
+        prop(5, op(0, Op::EQ, Field::Int(2))),
+    ]);
+    assert_eq!(is_unsatisfiable(&cond), Answer::Unknown);
+
+    let cond = and(vec![
+        prop(5, op(0, Op::EQ, Field::Int(1))),
+        not(prop(5, op(0, Op::LT, Field::Int(2)))),
+    ]);
+    assert_eq!(is_unsatisfiable(&cond), Answer::Yes);
+}
@@ -0,0 +1,174 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use common::*;
+use connor::cond::*;
+use connor::error::Error;
+use connor::incremental::*;
+use core::doc::Field;
+
+fn prop(index: usize, cond: Condition) -> Condition {
+    Condition::Prop(index, Box::new(cond))
+}
+
+fn eq_int(index: usize, value: i64) -> Condition {
+    prop(index, Condition::Op(Op::EQ, Field::Int(value)))
+}
+
+#[test]
+fn test_reports_started_and_stopped_transitions() {
+    let mut matches = DocMatches::new(get_doc());
+    assert!(matches.add(1, eq_int(0, 5)).unwrap());
+    assert!(!matches.add(2, eq_int(0, 6)).unwrap());
+    assert!(matches
+        .add(
+            3,
+            prop(1, Condition::Op(Op::EQ, Field::String("str".to_string())))
+        )
+        .unwrap());
+    assert_eq!(matches.matching(), vec![1, 3]);
+
+    let transitions = matches.update(vec![(0, Field::Int(6))]).unwrap();
+
+    assert_eq!(
+        transitions,
+        vec![Transition::Stopped(1), Transition::Started(2)]
+    );
+    assert_eq!(matches.matching(), vec![2, 3]);
+    assert_eq!(matches.is_match(1), Some(false));
+    assert_eq!(matches.is_match(4), None);
+
+    // no transition when the result stays the same
+    let transitions = matches
+        .update(vec![(1, Field::String("str".to_string()))])
+        .unwrap();
+    assert!(transitions.is_empty());
+}
+
+#[test]
+fn test_compound_conditions() {
+    let mut matches = DocMatches::new(get_doc());
+    let and = Condition::CompoundOp(CompoundOp::AND, vec![eq_int(0, 5), eq_int(2, 1)]);
+    let or = Condition::CompoundOp(CompoundOp::OR, vec![eq_int(0, 7), eq_int(3, 1)]);
+    let not = Condition::CompoundOp(CompoundOp::NOT, vec![eq_int(0, 5)]);
+    assert!(!matches.add(1, and).unwrap());
+    assert!(!matches.add(2, or).unwrap());
+    assert!(!matches.add(3, not).unwrap());
+
+    let transitions = matches
+        .update(vec![(2, Field::Int(1)), (0, Field::Int(7))])
+        .unwrap();
+    assert_eq!(
+        transitions,
+        vec![Transition::Started(2), Transition::Started(3)]
+    );
+
+    let transitions = matches.update(vec![(0, Field::Int(5))]).unwrap();
+    assert_eq!(
+        transitions,
+        vec![
+            Transition::Started(1),
+            Transition::Stopped(2),
+            Transition::Stopped(3)
+        ]
+    );
+}
+
+#[test]
+fn test_agrees_with_full_evaluation() {
+    let cond = Condition::CompoundOp(
+        CompoundOp::OR,
+        vec![
+            Condition::CompoundOp(
+                CompoundOp::AND,
+                vec![
+                    prop(0, Condition::Op(Op::GT, Field::Int(3))),
+                    Condition::CompoundOp(CompoundOp::NOT, vec![eq_int(2, 0)]),
+                ],
+            ),
+            // reads prop 2 and prop 0, so it's re-evaluated on every update
+            prop(
+                2,
+                Condition::FieldOp(
+                    Op::EQ,
+                    FieldRef {
+                        parent: 0,
+                        path: vec![0],
+                    },
+                ),
+            ),
+        ],
+    );
+    let mut matches = DocMatches::new(get_doc());
+    matches.add(1, cond.clone()).unwrap();
+
+    let updates = [
+        vec![(2, Field::Int(0))],
+        vec![(0, Field::Int(0))],
+        vec![(2, Field::Int(1))],
+        vec![(2, Field::Int(0))],
+        vec![(0, Field::Int(9))],
+        vec![(2, Field::Int(9))],
+    ];
+    for update in updates {
+        matches.update(update).unwrap();
+        let expected = match_conditions(&cond, &Field::Doc(matches.doc().clone())).unwrap();
+
+        assert_eq!(matches.is_match(1), Some(expected));
+    }
+}
+
+#[test]
+fn test_failed_update_leaves_state_unchanged() {
+    let mut matches = DocMatches::new(get_doc());
+    let expr = Condition::ExprOp(
+        Op::EQ,
+        connor::expr::Expr::Arith(
+            connor::expr::ArithOp::Div,
+            Box::new(connor::expr::Expr::Literal(Field::Int(10))),
+            Box::new(connor::expr::Expr::Value),
+        ),
+        connor::expr::Expr::Literal(Field::Int(2)),
+    );
+    assert!(matches.add(1, prop(0, expr)).unwrap());
+    assert!(matches.add(2, eq_int(2, 0)).is_ok());
+
+    let result = matches.update(vec![(2, Field::Int(0)), (0, Field::Int(0))]);
+    assert!(matches!(result, Err(Error::DivisionByZero)));
+    assert!(matches!(matches.doc().fields[0], Field::Int(5)));
+    assert_eq!(matches.matching(), vec![1]);
+
+    let result = matches.update(vec![(9, Field::Int(0))]);
+    assert!(matches!(result, Err(Error::OutOfBoundPropIndex)));
+}
+
+#[test]
+fn test_errors_decided_by_earlier_branches_are_ignored() {
+    let mut matches = DocMatches::new(get_doc());
+    // prop 9 is out of bound, but it's only evaluated while prop 0 equals 6
+    let cond = Condition::CompoundOp(CompoundOp::AND, vec![eq_int(0, 6), eq_int(9, 1)]);
+    assert!(!matches.add(1, cond).unwrap());
+
+    let result = matches.update(vec![(0, Field::Int(6))]);
+    assert!(matches!(result, Err(Error::OutOfBoundPropIndex)));
+    assert!(matches.update(vec![(0, Field::Int(7))]).unwrap().is_empty());
+}
+
+#[test]
+fn test_remove_subscription() {
+    let mut matches = DocMatches::new(get_doc());
+    matches.add(1, eq_int(0, 5)).unwrap();
+    assert!(matches.remove(1));
+    assert!(!matches.remove(1));
+
+    assert!(matches.update(vec![(0, Field::Int(6))]).unwrap().is_empty());
+}
@@ -0,0 +1,232 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::*;
+use connor::cond::*;
+use connor::json::*;
+use core::doc::Field;
+use core::json::Json;
+
+fn object(entries: Vec<(&str, Json)>) -> Json {
+    Json::Object(
+        entries
+            .into_iter()
+            .map(|(key, val)| (key.to_string(), val))
+            .collect::<BTreeMap<_, _>>(),
+    )
+}
+
+fn string(val: &str) -> Json {
+    Json::String(val.to_string())
+}
+
+// {"meta": {"tags": ["rust", "go"], "stars": 42, "score": 4.5, "first name": "Ada",
+//  "deleted": null}, "items": [{"id": 1}, {"id": 2}]}
+fn get_json() -> Field {
+    Field::Json(object(vec![
+        (
+            "meta",
+            object(vec![
+                ("tags", Json::Array(vec![string("rust"), string("go")])),
+                ("stars", Json::Int(42)),
+                ("score", Json::Float(4.5)),
+                ("first name", string("Ada")),
+                ("deleted", Json::Null),
+            ]),
+        ),
+        (
+            "items",
+            Json::Array(vec![
+                object(vec![("id", Json::Int(1))]),
+                object(vec![("id", Json::Int(2))]),
+            ]),
+        ),
+    ]))
+}
+
+fn path(path: &str) -> JsonPath {
+    path.parse().unwrap()
+}
+
+fn at(path_str: &str, condition: Condition) -> Condition {
+    Condition::JsonPath(path(path_str), Box::new(condition))
+}
+
+fn op(op: Op, target: Field) -> Condition {
+    Condition::Op(op, target)
+}
+
+#[test]
+fn test_parse_path() {
+    assert_eq!(
+        path("meta.tags[0]").segments,
+        vec![
+            PathSegment::Key("meta".to_string()),
+            PathSegment::Key("tags".to_string()),
+            PathSegment::Index(0),
+        ]
+    );
+    assert_eq!(
+        path(r#"meta["first name"][12]"#).segments,
+        vec![
+            PathSegment::Key("meta".to_string()),
+            PathSegment::Key("first name".to_string()),
+            PathSegment::Index(12),
+        ]
+    );
+    assert_eq!(path("[1].id").segments.len(), 2);
+    assert!(path("").segments.is_empty());
+
+    for invalid in [
+        "a..b", ".a", "a.", "a[", "a[x]", "a[-1]", r#"a["b]"#, "a b", "a[0]b",
+    ] {
+        assert!(
+            invalid.parse::<JsonPath>().is_err(),
+            "Expected error for {}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn test_display_path() {
+    for path_str in [
+        "meta.tags[0]",
+        r#"meta["first name"]"#,
+        r#"[0][""].a_b-c"#,
+        "",
+    ] {
+        assert_eq!(path(path_str).to_string(), path_str);
+    }
+}
+
+#[test]
+fn test_get() {
+    let json = match get_json() {
+        Field::Json(json) => json,
+        _ => unreachable!(),
+    };
+    assert_eq!(path("meta.tags[1]").get(&json), Some(&string("go")));
+    assert_eq!(path("items[1].id").get(&json), Some(&Json::Int(2)));
+    assert_eq!(path("meta.deleted").get(&json), Some(&Json::Null));
+    assert_eq!(path("").get(&json), Some(&json));
+    assert_eq!(path("meta.tags[2]").get(&json), None);
+    assert_eq!(path("meta.stars.count").get(&json), None);
+    assert_eq!(path("items.id").get(&json), None);
+}
+
+#[test]
+fn test_path_predicates() {
+    let json = get_json();
+
+    let cond = at(
+        "meta.tags[0]",
+        op(Op::EQ, Field::String("rust".to_string())),
+    );
+    expect(match_conditions(&cond, &json), true, false);
+
+    // numbers compare the way Int and Float fields do
+    let cond = at("meta.stars", op(Op::GT, Field::Float(41.5)));
+    expect(match_conditions(&cond, &json), true, false);
+    let cond = at("meta.score", op(Op::LE, Field::Int(4)));
+    expect(match_conditions(&cond, &json), false, false);
+
+    let cond = at("items[1].id", op(Op::IN, Field::IntArray(vec![2, 3])));
+    expect(match_conditions(&cond, &json), true, false);
+
+    let cond = at(
+        r#"meta["first name"]"#,
+        op(Op::LIKE, Field::String("A%".to_string())),
+    );
+    expect(match_conditions(&cond, &json), true, false);
+
+    let cond = at("meta.deleted", op(Op::EQ, Field::Null));
+    expect(match_conditions(&cond, &json), true, false);
+}
+
+#[test]
+fn test_missing_paths_never_match() {
+    let json = get_json();
+
+    let cond = at("meta.missing", op(Op::EQ, Field::Null));
+    expect(match_conditions(&cond, &json), false, false);
+
+    let cond = at("meta.missing", op(Op::NE, Field::Int(1)));
+    expect(match_conditions(&cond, &json), false, false);
+
+    let cond = Condition::CompoundOp(
+        CompoundOp::NOT,
+        vec![at("meta.missing", op(Op::EQ, Field::Int(1)))],
+    );
+    expect(match_conditions(&cond, &json), true, false);
+
+    let cond = at("", op(Op::EQ, Field::Int(1)));
+    expect(match_conditions(&cond, &Field::Int(1)), false, false);
+}
+
+#[test]
+fn test_exists() {
+    let json = get_json();
+    let exists = |path_str: &str| match_conditions(&Condition::JsonExists(path(path_str)), &json);
+
+    expect(exists("meta.tags[1]"), true, false);
+    expect(exists("meta.deleted"), true, false);
+    expect(exists(""), true, false);
+    expect(exists("meta.tags[2]"), false, false);
+    expect(exists("meta.stars.count"), false, false);
+
+    let cond = Condition::JsonExists(path("a"));
+    expect(
+        match_conditions(&cond, &Field::String("{\"a\":1}".to_string())),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_arrays_and_objects() {
+    let json = get_json();
+
+    let cond = at(
+        "meta.tags",
+        Condition::ArrayOp(
+            ArrayOp::CONTAINS,
+            Field::StringArray(vec!["go".to_string()]),
+        ),
+    );
+    expect(match_conditions(&cond, &json), true, false);
+
+    let cond = at("items", Condition::ArrayOp(ArrayOp::LEN_EQ, Field::Int(2)));
+    expect(match_conditions(&cond, &json), true, false);
+
+    let cond = at(
+        "items[0]",
+        op(Op::EQ, Field::Json(object(vec![("id", Json::Int(1))]))),
+    );
+    expect(match_conditions(&cond, &json), true, false);
+
+    let cond = at(
+        "items[0]",
+        op(Op::EQ, Field::Json(object(vec![("id", Json::Float(1.0))]))),
+    );
+    expect(match_conditions(&cond, &json), false, false);
+}
+
+#[test]
+fn test_json_field_in_doc() {
+    let mut doc = get_doc();
+    doc.fields.push(get_json());
+    let cond = Condition::Prop(5, Box::new(at("meta.stars", op(Op::EQ, Field::Int(42)))));
+    expect(match_conditions(&cond, &Field::Doc(doc)), true, false);
+}
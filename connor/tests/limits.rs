@@ -0,0 +1,206 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::*;
+use connor::cond::*;
+use connor::error::Error;
+use connor::expr::{ArithOp, Expr};
+use connor::limits::*;
+use core::doc::{Doc, Field, Status};
+use core::json::Json;
+
+fn prop(index: usize, cond: Condition) -> Condition {
+    Condition::Prop(index, Box::new(cond))
+}
+
+fn nested_props(depth: usize) -> Condition {
+    let mut cond = Condition::Op(Op::EQ, Field::Int(5));
+    for _ in 1..depth {
+        cond = prop(0, cond);
+    }
+    cond
+}
+
+fn doc(fields: Vec<Field>) -> Doc {
+    Doc {
+        hidden: false,
+        status: Status::Active,
+        fields,
+        schema_version_id: "".to_string(),
+    }
+}
+
+fn assert_exceeds<T: std::fmt::Debug>(result: connor::error::Result<T>, limit: Limit, max: usize) {
+    match result {
+        Err(Error::LimitExceeded(actual, actual_max)) => {
+            assert_eq!(actual, limit);
+            assert_eq!(actual_max, max);
+        }
+        other => panic!("Expected {} to exceed {}, got {:?}", limit, max, other),
+    }
+}
+
+#[test]
+fn test_accepts_ordinary_inputs() {
+    let limits = Limits::default();
+    let cond = Condition::CompoundOp(
+        CompoundOp::AND,
+        vec![
+            prop(0, Condition::Op(Op::EQ, Field::Int(5))),
+            prop(1, Condition::Op(Op::LIKE, Field::String("s%".to_string()))),
+        ],
+    );
+
+    assert!(limits.check_condition(&cond).is_ok());
+    assert!(limits.check_doc(&get_doc()).is_ok());
+    assert!(limits.check_field(&Field::Int(1)).is_ok());
+}
+
+#[test]
+fn test_condition_depth() {
+    let limits = Limits::default().with_max_condition_depth(10);
+
+    assert!(limits.check_condition(&nested_props(10)).is_ok());
+    assert_exceeds(
+        limits.check_condition(&nested_props(11)),
+        Limit::ConditionDepth,
+        10,
+    );
+    // nesting far beyond the limit is rejected without walking all of it
+    assert_exceeds(
+        limits.check_condition(&nested_props(1000)),
+        Limit::ConditionDepth,
+        10,
+    );
+
+    let mut expr = Expr::Value;
+    for _ in 0..20 {
+        expr = Expr::Arith(ArithOp::Add, Box::new(expr), Box::new(Expr::Value));
+    }
+    let cond = Condition::ExprOp(Op::EQ, expr, Expr::Literal(Field::Int(0)));
+    assert_exceeds(limits.check_condition(&cond), Limit::ConditionDepth, 10);
+}
+
+#[test]
+fn test_condition_nodes() {
+    let limits = Limits::default().with_max_condition_nodes(100);
+    let wide = |n: usize| {
+        Condition::CompoundOp(
+            CompoundOp::OR,
+            (0..n)
+                .map(|i| prop(0, Condition::Op(Op::EQ, Field::Int(i as i64))))
+                .collect(),
+        )
+    };
+
+    // the OR and a prop with an op for each branch
+    assert!(limits.check_condition(&wide(49)).is_ok());
+    assert_exceeds(
+        limits.check_condition(&wide(50)),
+        Limit::ConditionNodes,
+        100,
+    );
+}
+
+#[test]
+fn test_array_and_in_list_length() {
+    let limits = Limits::default().with_max_array_len(1000);
+    let in_list = |n: usize| prop(0, Condition::Op(Op::IN, Field::IntArray(vec![0; n])));
+
+    assert!(limits.check_condition(&in_list(1000)).is_ok());
+    assert_exceeds(
+        limits.check_condition(&in_list(1001)),
+        Limit::ArrayLength,
+        1000,
+    );
+    assert_exceeds(
+        limits.check_doc(&doc(vec![Field::Null; 1001])),
+        Limit::ArrayLength,
+        1000,
+    );
+    assert_exceeds(
+        limits.check_field(&Field::Vector(vec![0.0; 1001])),
+        Limit::ArrayLength,
+        1000,
+    );
+}
+
+#[test]
+fn test_string_length() {
+    let limits = Limits::default().with_max_string_len(64);
+    let long = "%".repeat(65);
+
+    assert_exceeds(
+        limits.check_condition(&prop(
+            1,
+            Condition::Op(Op::LIKE, Field::String(long.clone())),
+        )),
+        Limit::StringLength,
+        64,
+    );
+    assert_exceeds(
+        limits.check_doc(&doc(vec![Field::StringArray(vec![
+            "a".to_string(),
+            long.clone(),
+        ])])),
+        Limit::StringLength,
+        64,
+    );
+    let json = Json::Object(BTreeMap::from([(long, Json::Null)]));
+    assert_exceeds(
+        limits.check_field(&Field::Json(json)),
+        Limit::StringLength,
+        64,
+    );
+    assert_exceeds(
+        limits.check_field(&Field::Blob(vec![0; 65])),
+        Limit::StringLength,
+        64,
+    );
+}
+
+#[test]
+fn test_doc_depth() {
+    let limits = Limits::default().with_max_doc_depth(4);
+    let nested_docs = |depth: usize| {
+        let mut doc = doc(vec![Field::Int(1)]);
+        for _ in 1..depth {
+            doc = self::doc(vec![Field::DocArray(vec![doc])]);
+        }
+        doc
+    };
+
+    assert!(limits.check_doc(&nested_docs(4)).is_ok());
+    assert_exceeds(limits.check_doc(&nested_docs(5)), Limit::DocDepth, 4);
+
+    let mut json = Json::Null;
+    for _ in 0..10 {
+        json = Json::Array(vec![json]);
+    }
+    assert_exceeds(
+        limits.check_doc(&doc(vec![Field::Json(json)])),
+        Limit::DocDepth,
+        4,
+    );
+}
+
+#[test]
+fn test_limit_errors_are_descriptive() {
+    let err = Limits::default()
+        .with_max_condition_depth(3)
+        .check_condition(&nested_props(4))
+        .unwrap_err();
+
+    assert_eq!(err.to_string(), "Condition depth exceeds the limit of 3");
+}
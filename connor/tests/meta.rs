@@ -0,0 +1,105 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use common::*;
+use connor::cond::*;
+use connor::partial::*;
+use core::doc::{Field, Status};
+
+fn meta(meta: Meta, op: Op, target: Field) -> Condition {
+    Condition::Meta(meta, Box::new(Condition::Op(op, target)))
+}
+
+fn deleted_doc() -> Field {
+    let mut doc = get_doc();
+    doc.status = Status::Deleted;
+    doc.hidden = true;
+    doc.schema_version_id = "bafkreia2".to_string();
+    Field::Doc(doc)
+}
+
+#[test]
+fn test_meta_status() {
+    let cond = meta(Meta::Status, Op::EQ, Field::String("Deleted".to_string()));
+    expect(match_conditions(&cond, &deleted_doc()), true, false);
+    expect(
+        match_conditions(&cond, &Field::Doc(get_doc())),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_meta_hidden_and_schema_version() {
+    let cond = Condition::CompoundOp(
+        CompoundOp::AND,
+        vec![
+            meta(Meta::Hidden, Op::EQ, Field::Bool(true)),
+            meta(
+                Meta::SchemaVersionId,
+                Op::IN,
+                Field::StringArray(vec!["bafkreia1".to_string(), "bafkreia2".to_string()]),
+            ),
+        ],
+    );
+    expect(match_conditions(&cond, &deleted_doc()), true, false);
+    expect(
+        match_conditions(&cond, &Field::Doc(get_doc())),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_meta_of_nested_docs() {
+    let mut parent = get_doc();
+    parent.fields.push(Field::DocArray(vec![get_doc()]));
+    if let Field::Doc(deleted) = deleted_doc() {
+        if let Field::DocArray(docs) = &mut parent.fields[5] {
+            docs.push(deleted);
+        }
+    }
+
+    let cond = Condition::Prop(
+        5,
+        Box::new(meta(
+            Meta::Status,
+            Op::EQ,
+            Field::String("Deleted".to_string()),
+        )),
+    );
+    expect(match_conditions(&cond, &Field::Doc(parent)), true, false);
+}
+
+#[test]
+fn test_meta_of_non_doc() {
+    let cond = meta(Meta::Hidden, Op::EQ, Field::Bool(false));
+    expect(match_conditions(&cond, &Field::Int(1)), false, false);
+}
+
+#[test]
+fn test_meta_partial() {
+    let cond = Condition::CompoundOp(
+        CompoundOp::AND,
+        vec![
+            meta(Meta::Status, Op::EQ, Field::String("Active".to_string())),
+            Condition::Prop(1, Box::new(Condition::Op(Op::EQ, get_field(1)))),
+        ],
+    );
+    let mut unknown = UnknownFields::new();
+    unknown.add(vec![1]);
+
+    let result = match_conditions_partial(&cond, &deleted_doc(), &unknown).unwrap();
+    assert_eq!(result, Truth::False);
+    let result = match_conditions_partial(&cond, &Field::Doc(get_doc()), &unknown).unwrap();
+    assert_eq!(result, Truth::Unknown);
+}
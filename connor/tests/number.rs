@@ -0,0 +1,304 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use common::*;
+use connor::cond::*;
+use core::doc::Field;
+use core::number::{BigInt, Decimal};
+
+fn dec(val: &str) -> Decimal {
+    val.parse().unwrap()
+}
+
+fn big(val: &str) -> BigInt {
+    val.parse().unwrap()
+}
+
+fn op(op: Op, target: Field) -> Condition {
+    Condition::Op(op, target)
+}
+
+#[test]
+fn test_parse_and_display() {
+    for (input, displayed) in [
+        ("0", "0"),
+        ("-0", "0"),
+        ("+7", "7"),
+        ("1.50", "1.50"),
+        ("-0.001", "-0.001"),
+        (".5", "0.5"),
+        ("007.20", "7.20"),
+        ("1.5e3", "1500"),
+        ("1.25E-3", "0.00125"),
+        ("0.00", "0.00"),
+        (
+            "123456789012345678901234567890.000000001",
+            "123456789012345678901234567890.000000001",
+        ),
+    ] {
+        assert_eq!(
+            dec(input).to_string(),
+            displayed,
+            "Unexpected display of {}",
+            input
+        );
+    }
+    for invalid in [
+        "", "-", ".", "1.2.3", "1e", "e5", "1,5", " 1", "0x10", "1e100001", "NaN",
+    ] {
+        assert!(
+            invalid.parse::<Decimal>().is_err(),
+            "Expected error for {}",
+            invalid
+        );
+    }
+
+    assert_eq!(
+        big("-98765432109876543210").to_string(),
+        "-98765432109876543210"
+    );
+    for invalid in ["1.0", "1e3", "", "+", "--1"] {
+        assert!(
+            invalid.parse::<BigInt>().is_err(),
+            "Expected error for {}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn test_decimal_ordering() {
+    assert_eq!(dec("1.5"), dec("1.50000"));
+    assert_eq!(dec("0"), dec("-0.000"));
+    assert_eq!(dec("1e2"), dec("100"));
+    assert!(dec("0.1") < dec("0.10000000000000000001"));
+    assert!(dec("-2") < dec("-1.99"));
+    assert!(dec("-0.5") < dec("0"));
+    assert!(dec("99") < dec("100"));
+    assert!(dec("0.09") < dec("0.1"));
+    assert!(big("18446744073709551616") > big("9223372036854775807"));
+
+    assert!(dec("12.000").is_integer());
+    assert!(!dec("12.001").is_integer());
+    assert!(dec("1e3").is_integer());
+}
+
+#[test]
+fn test_exact_floats() {
+    assert_eq!(Decimal::from_f64(0.5), Some(dec("0.5")));
+    assert_eq!(Decimal::from_f64(-3.0), Some(dec("-3")));
+    assert_eq!(
+        Decimal::from_f64(0.1),
+        Some(dec(
+            "0.1000000000000000055511151231257827021181583404541015625"
+        ))
+    );
+    assert_eq!(Decimal::from_f64(1e300).map(|d| d.is_integer()), Some(true));
+    assert_eq!(
+        Decimal::from_f64(f64::MIN_POSITIVE / 2.0).map(|d| d > Decimal::zero()),
+        Some(true)
+    );
+    assert_eq!(Decimal::from_f64(f64::NAN), None);
+    assert_eq!(Decimal::from_f64(f64::INFINITY), None);
+}
+
+#[test]
+fn test_exact_equality() {
+    let amount = Field::Decimal(dec("0.10"));
+
+    expect(
+        match_conditions(&op(Op::EQ, Field::Decimal(dec("0.1"))), &amount),
+        true,
+        false,
+    );
+    // 0.1 has no exact float representation
+    expect(
+        match_conditions(&op(Op::EQ, Field::Float(0.1)), &amount),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::NE, Field::Float(0.1)), &amount),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(
+            &op(Op::EQ, Field::Float(0.25)),
+            &Field::Decimal(dec("0.250")),
+        ),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::EQ, Field::Int(42)), &Field::BigInt(big("42"))),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(
+            &op(Op::EQ, Field::BigInt(big("42"))),
+            &Field::Decimal(dec("42.0")),
+        ),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::EQ, Field::Null), &Field::Decimal(dec("0"))),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(
+            &op(Op::EQ, Field::String("1".to_string())),
+            &Field::BigInt(big("1")),
+        ),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_exact_comparisons() {
+    // beyond the range of i64 and the precision of f64
+    let supply = Field::BigInt(big("123456789012345678901234567891"));
+
+    expect(
+        match_conditions(
+            &op(Op::GT, Field::BigInt(big("123456789012345678901234567890"))),
+            &supply,
+        ),
+        true,
+        false,
+    );
+    // the closest float is 123456789012345677877719597056
+    expect(
+        match_conditions(&op(Op::GE, Field::Float(1.2345678901234568e29)), &supply),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::EQ, Field::Float(1.2345678901234568e29)), &supply),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::GT, Field::Int(i64::MAX)), &supply),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::LT, Field::Float(f64::INFINITY)), &supply),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::GT, Field::Float(f64::NAN)), &supply),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::LE, Field::Float(f64::NAN)), &supply),
+        false,
+        false,
+    );
+
+    let price = Field::Decimal(dec("19.99"));
+    expect(
+        match_conditions(&op(Op::LT, Field::Int(20)), &price),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::GE, Field::Decimal(dec("19.990"))), &price),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::LE, Field::Float(19.98)), &price),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(&op(Op::GT, Field::Decimal(dec("10"))), &Field::Int(11)),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(
+            &op(Op::GT, Field::Decimal(dec("10.5"))),
+            &Field::Float(10.25),
+        ),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_exact_in() {
+    let cond = op(Op::IN, Field::DecimalArray(vec![dec("1.10"), dec("2")]));
+    expect(
+        match_conditions(&cond, &Field::Decimal(dec("1.1"))),
+        true,
+        false,
+    );
+    expect(match_conditions(&cond, &Field::Int(2)), true, false);
+    expect(match_conditions(&cond, &Field::Float(1.1)), false, false);
+
+    let cond = op(Op::IN, Field::IntArray(vec![1, 2]));
+    expect(
+        match_conditions(&cond, &Field::BigInt(big("2"))),
+        true,
+        false,
+    );
+
+    let cond = op(
+        Op::NIN,
+        Field::BigIntArray(vec![big("99999999999999999999")]),
+    );
+    expect(
+        match_conditions(&cond, &Field::BigInt(big("99999999999999999999"))),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(&cond, &Field::OptionalBigInt(None)),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_exact_between() {
+    let range = Condition::Between(Range {
+        start: Target::Value(Field::Decimal(dec("0.1"))),
+        end: Target::Value(Field::Int(1)),
+        start_inclusive: false,
+        end_inclusive: true,
+    });
+    expect(
+        match_conditions(&range, &Field::Decimal(dec("0.10"))),
+        false,
+        false,
+    );
+    expect(match_conditions(&range, &Field::Float(0.1)), true, false);
+    expect(
+        match_conditions(&range, &Field::BigInt(big("1"))),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&range, &Field::OptionalDecimal(Some(dec("1.000000001")))),
+        false,
+        false,
+    );
+}
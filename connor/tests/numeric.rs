@@ -0,0 +1,141 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use common::*;
+use connor::cond::*;
+use connor::span::*;
+use core::doc::Field;
+
+const OPS: [Op; 6] = [Op::EQ, Op::NE, Op::GT, Op::GE, Op::LT, Op::LE];
+
+// 2^53, above which not every integer has a float representation.
+const TWO_POW_53: i64 = 9_007_199_254_740_992;
+
+// Asserts the result of every comparison operator applied with the target to the value, in
+// the order EQ, NE, GT, GE, LT, LE.
+fn assert_ops(value: Field, target: Field, expected: [bool; 6]) {
+    for (op, expected) in OPS.into_iter().zip(expected) {
+        let cond = Condition::Op(op.clone(), target.clone());
+        let actual = match_conditions(&cond, &value).unwrap();
+        assert_eq!(
+            actual, expected,
+            "{:?} {:?} {:?} should be {}",
+            value, op, target, expected
+        );
+    }
+}
+
+const EQUAL: [bool; 6] = [true, false, false, true, false, true];
+const GREATER: [bool; 6] = [false, true, true, true, false, false];
+const LESS: [bool; 6] = [false, true, false, false, true, true];
+const UNORDERED: [bool; 6] = [false, true, false, false, false, false];
+
+#[test]
+fn test_int_float_above_float_precision() {
+    let int = |val: i64| Field::Int(val);
+    let float = |val: f64| Field::Float(val);
+
+    assert_ops(int(TWO_POW_53 + 1), float(TWO_POW_53 as f64), GREATER);
+    assert_ops(float(TWO_POW_53 as f64), int(TWO_POW_53 + 1), LESS);
+    assert_ops(int(TWO_POW_53), float(TWO_POW_53 as f64), EQUAL);
+    assert_ops(float(TWO_POW_53 as f64), int(TWO_POW_53), EQUAL);
+    assert_ops(int(-TWO_POW_53 - 1), float(-TWO_POW_53 as f64), LESS);
+}
+
+#[test]
+fn test_int_float_at_i64_bounds() {
+    // i64::MAX rounds up to 2^63, which is beyond every i64
+    assert_ops(Field::Int(i64::MAX), Field::Float(i64::MAX as f64), LESS);
+    assert_ops(Field::Float(i64::MAX as f64), Field::Int(i64::MAX), GREATER);
+    // i64::MIN is -2^63 and has an exact float
+    assert_ops(Field::Int(i64::MIN), Field::Float(i64::MIN as f64), EQUAL);
+    assert_ops(Field::Int(i64::MIN), Field::Float(-9.3e18), GREATER);
+    assert_ops(Field::Int(i64::MAX), Field::Float(1e300), LESS);
+    assert_ops(
+        Field::Int(i64::MIN),
+        Field::Float(f64::NEG_INFINITY),
+        GREATER,
+    );
+    assert_ops(Field::Int(i64::MAX), Field::Float(f64::INFINITY), LESS);
+}
+
+#[test]
+fn test_int_float_fractions() {
+    assert_ops(Field::Int(2), Field::Float(2.5), LESS);
+    assert_ops(Field::Int(3), Field::Float(2.5), GREATER);
+    assert_ops(Field::Int(-2), Field::Float(-2.5), GREATER);
+    assert_ops(Field::Int(-3), Field::Float(-2.5), LESS);
+    assert_ops(Field::Int(0), Field::Float(-0.0), EQUAL);
+    assert_ops(Field::Int(0), Field::Float(f64::MIN_POSITIVE), LESS);
+    assert_ops(Field::Float(-f64::MIN_POSITIVE), Field::Int(0), LESS);
+    assert_ops(Field::Float(2.0), Field::Int(2), EQUAL);
+}
+
+#[test]
+fn test_nan_is_unordered() {
+    let nan = Field::Float(f64::NAN);
+
+    assert_ops(nan.clone(), Field::Float(1.0), UNORDERED);
+    assert_ops(Field::Float(1.0), nan.clone(), UNORDERED);
+    assert_ops(nan.clone(), Field::Int(1), UNORDERED);
+    assert_ops(Field::Int(1), nan.clone(), UNORDERED);
+    assert_ops(nan.clone(), nan.clone(), UNORDERED);
+    assert_ops(nan.clone(), Field::Float(f64::INFINITY), UNORDERED);
+    assert_ops(
+        Field::OptionalFloat(Some(f64::NAN)),
+        Field::Int(0),
+        UNORDERED,
+    );
+
+    let cond = Condition::Op(Op::IN, Field::FloatArray(vec![f64::NAN, 1.0]));
+    expect(match_conditions(&cond, &nan), false, false);
+    let cond = Condition::Op(Op::NIN, Field::FloatArray(vec![f64::NAN]));
+    expect(match_conditions(&cond, &nan), true, false);
+
+    let cond = Condition::Between(Range {
+        start: Target::Value(Field::Float(f64::NEG_INFINITY)),
+        end: Target::Value(Field::Float(f64::INFINITY)),
+        start_inclusive: true,
+        end_inclusive: true,
+    });
+    expect(match_conditions(&cond, &nan), false, false);
+}
+
+#[test]
+fn test_infinities() {
+    let inf = Field::Float(f64::INFINITY);
+    assert_ops(inf.clone(), Field::Float(f64::INFINITY), EQUAL);
+    assert_ops(inf.clone(), Field::Float(f64::MAX), GREATER);
+    assert_ops(Field::Float(f64::NEG_INFINITY), inf, LESS);
+}
+
+#[test]
+fn test_nan_spans_match_nothing() {
+    for op in [Op::EQ, Op::GT, Op::GE, Op::LT, Op::LE] {
+        let cond = Condition::Op(op, Field::Float(f64::NAN));
+        let scan = extract_spans(&cond, &[]);
+        assert!(scan.spans.is_empty());
+    }
+
+    let cond = Condition::Op(Op::IN, Field::FloatArray(vec![f64::NAN, 1.0]));
+    let scan = extract_spans(&cond, &[]);
+    assert_eq!(scan.spans.len(), 1);
+    assert!(scan.spans[0].is_point());
+}
+
+#[test]
+fn test_spans_compare_exactly() {
+    let cond = Condition::Op(Op::GT, Field::Float(TWO_POW_53 as f64));
+    let scan = extract_spans(&cond, &[]);
+    assert!(scan.spans[0].contains(&Field::Int(TWO_POW_53 + 1)));
+    assert!(!scan.spans[0].contains(&Field::Int(TWO_POW_53)));
+}
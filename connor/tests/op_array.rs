@@ -0,0 +1,170 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use common::*;
+use connor::cond::*;
+use core::doc::{Field, GeoPoint};
+
+fn array_op(op: ArrayOp, target: Field) -> Condition {
+    Condition::ArrayOp(op, target)
+}
+
+fn strings(values: &[&str]) -> Field {
+    Field::StringArray(values.iter().map(|v| v.to_string()).collect())
+}
+
+#[test]
+fn test_contains() {
+    let tags = strings(&["rust", "go", "c"]);
+
+    let cond = array_op(ArrayOp::CONTAINS, strings(&["go", "rust"]));
+    expect(match_conditions(&cond, &tags), true, false);
+
+    let cond = array_op(ArrayOp::CONTAINS, strings(&["go", "java"]));
+    expect(match_conditions(&cond, &tags), false, false);
+
+    let cond = array_op(ArrayOp::CONTAINS, strings(&[]));
+    expect(match_conditions(&cond, &tags), true, false);
+}
+
+#[test]
+fn test_overlaps() {
+    let ids = Field::IntArray(vec![1, 2, 3]);
+
+    let cond = array_op(ArrayOp::OVERLAPS, Field::IntArray(vec![3, 4]));
+    expect(match_conditions(&cond, &ids), true, false);
+
+    let cond = array_op(ArrayOp::OVERLAPS, Field::IntArray(vec![4, 5]));
+    expect(match_conditions(&cond, &ids), false, false);
+
+    let cond = array_op(ArrayOp::OVERLAPS, Field::IntArray(vec![]));
+    expect(match_conditions(&cond, &ids), false, false);
+
+    // elements compare like the comparison operators do
+    let cond = array_op(ArrayOp::OVERLAPS, Field::FloatArray(vec![2.0]));
+    expect(match_conditions(&cond, &ids), true, false);
+}
+
+#[test]
+fn test_subset_of() {
+    let cond = array_op(ArrayOp::SUBSET_OF, Field::IntArray(vec![1, 2, 3]));
+    expect(
+        match_conditions(&cond, &Field::IntArray(vec![3, 1])),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&cond, &Field::IntArray(vec![3, 4])),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(&cond, &Field::IntArray(vec![])),
+        true,
+        false,
+    );
+}
+
+#[test]
+fn test_optional_elements() {
+    let values = Field::OptionalIntArray(vec![Some(1), None]);
+
+    // nulls only equal other nulls
+    let cond = array_op(ArrayOp::CONTAINS, Field::OptionalIntArray(vec![None]));
+    expect(match_conditions(&cond, &values), true, false);
+
+    let cond = array_op(ArrayOp::SUBSET_OF, Field::IntArray(vec![1, 2]));
+    expect(match_conditions(&cond, &values), false, false);
+
+    let cond = array_op(
+        ArrayOp::SUBSET_OF,
+        Field::OptionalIntArray(vec![Some(1), None]),
+    );
+    expect(match_conditions(&cond, &values), true, false);
+
+    let cond = array_op(ArrayOp::OVERLAPS, Field::OptionalIntArray(vec![None]));
+    expect(
+        match_conditions(&cond, &Field::IntArray(vec![1])),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_length() {
+    let values = Field::OptionalStringArray(vec![Some("a".to_string()), None]);
+    let len = |op: ArrayOp, len: i64| match_conditions(&array_op(op, Field::Int(len)), &values);
+
+    expect(len(ArrayOp::LEN_EQ, 2), true, false);
+    expect(len(ArrayOp::LEN_NE, 2), false, false);
+    expect(len(ArrayOp::LEN_GT, 1), true, false);
+    expect(len(ArrayOp::LEN_GE, 3), false, false);
+    expect(len(ArrayOp::LEN_LT, 3), true, false);
+    expect(len(ArrayOp::LEN_LE, 1), false, false);
+}
+
+#[test]
+fn test_non_arrays_never_match() {
+    let cond = array_op(ArrayOp::LEN_GE, Field::Int(0));
+    expect(
+        match_conditions(&cond, &Field::String("abc".to_string())),
+        false,
+        false,
+    );
+    expect(match_conditions(&cond, &Field::Null), false, false);
+
+    let cond = array_op(ArrayOp::CONTAINS, Field::Int(1));
+    expect(
+        match_conditions(&cond, &Field::IntArray(vec![1])),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_array_op_in_doc() {
+    let mut doc = get_doc();
+    doc.fields.push(strings(&["a", "b"]));
+    let cond = Condition::Prop(5, Box::new(array_op(ArrayOp::CONTAINS, strings(&["b"]))));
+    expect(match_conditions(&cond, &Field::Doc(doc)), true, false);
+}
+
+#[test]
+fn test_geo_point_arrays() {
+    let point = |lat: f64, lon: f64| GeoPoint { lat, lon };
+    let stops = Field::GeoPointArray(vec![point(48.85, 2.35), point(51.5, -0.12)]);
+
+    let cond = array_op(ArrayOp::LEN_EQ, Field::Int(2));
+    expect(match_conditions(&cond, &stops), true, false);
+
+    let cond = array_op(
+        ArrayOp::CONTAINS,
+        Field::GeoPointArray(vec![point(51.5, -0.12)]),
+    );
+    expect(match_conditions(&cond, &stops), true, false);
+
+    let cond = array_op(
+        ArrayOp::CONTAINS,
+        Field::GeoPointArray(vec![point(40.7, -74.0)]),
+    );
+    expect(match_conditions(&cond, &stops), false, false);
+
+    let optional_stops = Field::OptionalGeoPointArray(vec![Some(point(48.85, 2.35)), None]);
+    let cond = array_op(
+        ArrayOp::OVERLAPS,
+        Field::GeoPointArray(vec![point(48.85, 2.35)]),
+    );
+    expect(match_conditions(&cond, &optional_stops), true, false);
+
+    let cond = array_op(ArrayOp::LEN_GT, Field::Int(1));
+    expect(match_conditions(&cond, &optional_stops), true, false);
+}
@@ -0,0 +1,120 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+
+use common::*;
+use connor::cond::*;
+use connor::error::Error;
+use connor::operator::{self, Operator};
+use core::doc::Field;
+
+fn prop(index: usize, cond: Condition) -> Condition {
+    Condition::Prop(index, Box::new(cond))
+}
+
+fn string(val: &str) -> Field {
+    Field::String(val.to_string())
+}
+
+// Matches IPv4 addresses within a CIDR range such as "10.0.0.0/8".
+struct IpInRange;
+
+impl Operator for IpInRange {
+    fn matches(&self, target: &Field, value: &Field) -> connor::error::Result<bool> {
+        let invalid = || Error::OperatorFailed("IP_IN_RANGE".to_string());
+        let Field::String(range) = target else {
+            return Err(invalid());
+        };
+        let Field::String(addr) = value else {
+            return Ok(false);
+        };
+        let (net, bits) = range.split_once('/').ok_or_else(invalid)?;
+        let net: Ipv4Addr = net.parse().map_err(|_| invalid())?;
+        let bits: u32 = bits.parse().map_err(|_| invalid())?;
+        let Ok(addr) = addr.parse::<Ipv4Addr>() else {
+            return Ok(false);
+        };
+        let mask = u32::MAX.checked_shl(32 - bits).unwrap_or(0);
+        Ok(u32::from(addr) & mask == u32::from(net) & mask)
+    }
+}
+
+#[test]
+fn test_custom_operator() {
+    operator::register("IP_IN_RANGE", Arc::new(IpInRange)).unwrap();
+    let cond = Condition::Custom("IP_IN_RANGE".to_string(), string("10.0.0.0/8"));
+
+    assert!(match_conditions(&cond, &string("10.1.2.3")).unwrap());
+    assert!(!match_conditions(&cond, &string("11.1.2.3")).unwrap());
+    assert!(!match_conditions(&cond, &Field::Int(10)).unwrap());
+    // optionals without a value never reach the operator
+    assert!(!match_conditions(&cond, &Field::OptionalString(None)).unwrap());
+
+    let mut doc = get_doc();
+    doc.fields[1] = string("10.200.0.1");
+    assert!(match_conditions(&prop(1, cond), &Field::Doc(doc)).unwrap());
+
+    let invalid = Condition::Custom("IP_IN_RANGE".to_string(), string("10.0.0.0"));
+    assert!(matches!(
+        match_conditions(&invalid, &string("10.1.2.3")),
+        Err(Error::OperatorFailed(_))
+    ));
+}
+
+#[test]
+fn test_closure_operator() {
+    let semver =
+        |val: &str| -> Option<Vec<u64>> { val.split('.').map(|part| part.parse().ok()).collect() };
+    let at_least = move |target: &Field, value: &Field| match (target, value) {
+        (Field::String(target), Field::String(value)) => Ok(semver(value) >= semver(target)),
+        _ => Ok(false),
+    };
+    operator::register("SEMVER_GE", Arc::new(at_least)).unwrap();
+    let cond = Condition::Custom("SEMVER_GE".to_string(), string("1.2.0"));
+
+    assert!(match_conditions(&cond, &string("1.10.0")).unwrap());
+    assert!(!match_conditions(&cond, &string("1.1.9")).unwrap());
+}
+
+#[test]
+fn test_builtin_ops_are_operators() {
+    let op: Arc<dyn Operator> = Arc::new(Op::GT);
+    assert!(op.matches(&Field::Int(1), &Field::Int(2)).unwrap());
+    assert!(!op.matches(&Field::Int(2), &Field::Int(2)).unwrap());
+}
+
+#[test]
+fn test_register_and_unregister() {
+    let always = |_: &Field, _: &Field| Ok(true);
+    operator::register("ALWAYS", Arc::new(always)).unwrap();
+    assert!(matches!(
+        operator::register("ALWAYS", Arc::new(always)),
+        Err(Error::InvalidOperator(_))
+    ));
+    assert!(matches!(
+        operator::register("", Arc::new(always)),
+        Err(Error::InvalidOperator(_))
+    ));
+    assert!(operator::get("ALWAYS").is_some());
+
+    let cond = Condition::Custom("ALWAYS".to_string(), Field::Null);
+    assert!(match_conditions(&cond, &Field::Int(1)).unwrap());
+
+    assert!(operator::unregister("ALWAYS"));
+    assert!(!operator::unregister("ALWAYS"));
+    assert!(matches!(
+        match_conditions(&cond, &Field::Int(1)),
+        Err(Error::UnknownOperator(_))
+    ));
+}
@@ -0,0 +1,152 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use chrono::{Duration, Utc};
+use common::*;
+use connor::cond::*;
+use connor::error::Error;
+use connor::parallel::*;
+use connor::time::RelativeTime;
+use core::doc::{Doc, Field};
+
+fn prop(index: usize, cond: Condition) -> Condition {
+    Condition::Prop(index, Box::new(cond))
+}
+
+fn docs(count: i64) -> Vec<Field> {
+    (0..count)
+        .map(|i| {
+            let mut doc = get_doc();
+            doc.fields[0] = Field::Int(i);
+            Field::Doc(doc)
+        })
+        .collect()
+}
+
+fn multiple_of(divisor: i64) -> Condition {
+    prop(
+        0,
+        Condition::ExprOp(
+            Op::EQ,
+            connor::expr::Expr::Arith(
+                connor::expr::ArithOp::Sub,
+                Box::new(connor::expr::Expr::Value),
+                Box::new(connor::expr::Expr::Arith(
+                    connor::expr::ArithOp::Mul,
+                    Box::new(connor::expr::Expr::Arith(
+                        connor::expr::ArithOp::Div,
+                        Box::new(connor::expr::Expr::Value),
+                        Box::new(connor::expr::Expr::Literal(Field::Int(divisor))),
+                    )),
+                    Box::new(connor::expr::Expr::Literal(Field::Int(divisor))),
+                )),
+            ),
+            connor::expr::Expr::Literal(Field::Int(0)),
+        ),
+    )
+}
+
+#[test]
+fn test_filter_keeps_document_order() {
+    let docs = docs(1000);
+    let cond = multiple_of(7);
+    let expected = (0..1000).filter(|i| i % 7 == 0).collect::<Vec<usize>>();
+
+    for threads in [1, 2, 4, 16] {
+        for chunk_size in [1, 3, 64, 5000] {
+            let filter = ParallelFilter::new(threads).with_chunk_size(chunk_size);
+
+            assert_eq!(filter.filter(&cond, &docs).unwrap(), expected);
+        }
+    }
+}
+
+#[test]
+fn test_first_n_returns_earliest_matches() {
+    let docs = docs(1000);
+    let cond = multiple_of(3);
+
+    for threads in [1, 4] {
+        let filter = ParallelFilter::new(threads).with_chunk_size(10);
+
+        assert_eq!(
+            filter.first_n(&cond, &docs, 5).unwrap(),
+            vec![0, 3, 6, 9, 12]
+        );
+        assert_eq!(
+            filter.first_n(&cond, &docs, 0).unwrap(),
+            Vec::<usize>::new()
+        );
+        assert_eq!(filter.first_n(&cond, &docs, 1000).unwrap().len(), 334);
+    }
+}
+
+#[test]
+fn test_errors_match_sequential_evaluation() {
+    let mut docs = docs(100);
+    // dividing by the value fails for the document holding 0, placed at index 50
+    docs.swap(0, 50);
+    let cond = prop(
+        0,
+        Condition::ExprOp(
+            Op::GT,
+            connor::expr::Expr::Arith(
+                connor::expr::ArithOp::Div,
+                Box::new(connor::expr::Expr::Literal(Field::Int(100))),
+                Box::new(connor::expr::Expr::Value),
+            ),
+            connor::expr::Expr::Literal(Field::Int(2)),
+        ),
+    );
+
+    for threads in [1, 4] {
+        let filter = ParallelFilter::new(threads).with_chunk_size(4);
+
+        let result = filter.filter(&cond, &docs);
+        assert!(matches!(result, Err(Error::DivisionByZero)));
+        // the first matches come before the failing document
+        assert_eq!(filter.first_n(&cond, &docs, 3).unwrap(), vec![1, 2, 3]);
+    }
+}
+
+#[test]
+fn test_filter_relative_times() {
+    let now = Utc::now();
+    let docs = (0..100)
+        .map(|i| Field::DateTime(now - Duration::days(i % 3)))
+        .collect::<Vec<Field>>();
+    let cond = Condition::TimeOp(Op::GT, "now-1h".parse::<RelativeTime>().unwrap());
+    let expected = (0..100).filter(|i| i % 3 == 0).collect::<Vec<usize>>();
+
+    for threads in [1, 4] {
+        let filter = ParallelFilter::new(threads).with_chunk_size(8);
+        assert_eq!(filter.filter(&cond, &docs).unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_empty_input() {
+    let filter = ParallelFilter::default();
+    assert!(filter.threads() >= 1);
+    assert!(filter
+        .filter(&prop(0, Condition::Op(Op::EQ, Field::Int(1))), &[])
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_docs_and_conditions_are_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Doc>();
+    assert_send_sync::<Condition>();
+    assert_send_sync::<ParallelFilter>();
+}
@@ -0,0 +1,155 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use common::*;
+use connor::cond::*;
+use connor::partial::*;
+use core::doc::Field;
+
+fn eq_prop(index: usize) -> Condition {
+    Condition::Prop(index, Box::new(Condition::Op(Op::EQ, get_field(index))))
+}
+
+fn ne_prop(index: usize) -> Condition {
+    Condition::Prop(index, Box::new(Condition::Op(Op::NE, get_field(index))))
+}
+
+fn unknown(paths: Vec<Vec<usize>>) -> UnknownFields {
+    let mut unknown = UnknownFields::new();
+    for path in paths {
+        unknown.add(path);
+    }
+    unknown
+}
+
+fn assert_partial(cond: &Condition, doc: &Field, unknown: &UnknownFields, expected: Truth) {
+    match match_conditions_partial(cond, doc, unknown) {
+        Ok(result) => assert_eq!(result, expected),
+        Err(e) => panic!("Unexpected error: {}", e),
+    }
+}
+
+#[test]
+fn test_partial_all_known_matches_full_evaluation() {
+    let doc = Field::Doc(get_doc());
+    assert_partial(&eq_prop(0), &doc, &UnknownFields::new(), Truth::True);
+    assert_partial(&ne_prop(0), &doc, &UnknownFields::new(), Truth::False);
+}
+
+#[test]
+fn test_partial_unknown_prop() {
+    let doc = Field::Doc(get_doc());
+    let unknown = unknown(vec![vec![1]]);
+    assert_partial(&eq_prop(1), &doc, &unknown, Truth::Unknown);
+    assert_partial(&eq_prop(0), &doc, &unknown, Truth::True);
+}
+
+#[test]
+fn test_partial_and() {
+    let doc = Field::Doc(get_doc());
+    let unknown = unknown(vec![vec![1]]);
+
+    let cond = Condition::CompoundOp(CompoundOp::AND, vec![eq_prop(0), eq_prop(1)]);
+    assert_partial(&cond, &doc, &unknown, Truth::Unknown);
+
+    let cond = Condition::CompoundOp(CompoundOp::AND, vec![eq_prop(1), ne_prop(0)]);
+    assert_partial(&cond, &doc, &unknown, Truth::False);
+}
+
+#[test]
+fn test_partial_or() {
+    let doc = Field::Doc(get_doc());
+    let unknown = unknown(vec![vec![1]]);
+
+    let cond = Condition::CompoundOp(CompoundOp::OR, vec![eq_prop(1), eq_prop(0)]);
+    assert_partial(&cond, &doc, &unknown, Truth::True);
+
+    let cond = Condition::CompoundOp(CompoundOp::OR, vec![ne_prop(0), eq_prop(1)]);
+    assert_partial(&cond, &doc, &unknown, Truth::Unknown);
+}
+
+#[test]
+fn test_partial_not() {
+    let doc = Field::Doc(get_doc());
+    let unknown = unknown(vec![vec![1]]);
+
+    let cond = Condition::CompoundOp(CompoundOp::NOT, vec![eq_prop(1)]);
+    assert_partial(&cond, &doc, &unknown, Truth::Unknown);
+
+    let cond = Condition::CompoundOp(CompoundOp::NOT, vec![eq_prop(0)]);
+    assert_partial(&cond, &doc, &unknown, Truth::False);
+}
+
+#[test]
+fn test_partial_unknown_out_of_bound_prop() {
+    // an index-only document may hold fewer fields than the full one
+    let mut doc = get_doc();
+    doc.fields.truncate(2);
+    let doc = Field::Doc(doc);
+
+    let unknown = UnknownFields::all_except(&[0, 1], 5);
+    assert_partial(&eq_prop(4), &doc, &unknown, Truth::Unknown);
+    assert!(match_conditions_partial(&eq_prop(4), &doc, &UnknownFields::new()).is_err());
+}
+
+#[test]
+fn test_partial_nested_path() {
+    let mut parent = get_doc();
+    parent.fields.push(Field::Doc(get_doc()));
+    let doc = Field::Doc(parent);
+
+    let nested = Condition::Prop(5, Box::new(eq_prop(1)));
+    assert_partial(&nested, &doc, &unknown(vec![vec![5, 1]]), Truth::Unknown);
+    assert_partial(&nested, &doc, &unknown(vec![vec![5]]), Truth::Unknown);
+    assert_partial(&nested, &doc, &unknown(vec![vec![1]]), Truth::True);
+}
+
+#[test]
+fn test_partial_doc_array() {
+    let mut other = get_doc();
+    other.fields[0] = Field::Int(9);
+    let mut parent = get_doc();
+    parent.fields.push(Field::DocArray(vec![other, get_doc()]));
+    let doc = Field::Doc(parent);
+
+    // the second element matches on the known field
+    let cond = Condition::Prop(
+        5,
+        Box::new(Condition::CompoundOp(
+            CompoundOp::OR,
+            vec![eq_prop(0), eq_prop(1)],
+        )),
+    );
+    assert_partial(&cond, &doc, &unknown(vec![vec![5, 1]]), Truth::True);
+
+    // no element is known to match
+    let cond = Condition::Prop(
+        5,
+        Box::new(Condition::CompoundOp(
+            CompoundOp::AND,
+            vec![eq_prop(0), eq_prop(1)],
+        )),
+    );
+    assert_partial(&cond, &doc, &unknown(vec![vec![5, 1]]), Truth::Unknown);
+}
+
+#[test]
+fn test_truth_kleene_tables() {
+    use Truth::*;
+    assert_eq!(Unknown.and(False), False);
+    assert_eq!(Unknown.and(True), Unknown);
+    assert_eq!(Unknown.or(True), True);
+    assert_eq!(Unknown.or(False), Unknown);
+    assert_eq!(!Unknown, Unknown);
+    assert_eq!(True.decided(), Some(true));
+    assert_eq!(Unknown.decided(), None);
+}
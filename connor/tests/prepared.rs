@@ -0,0 +1,148 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use std::collections::HashMap;
+
+use common::*;
+use connor::cond::*;
+use connor::error::Error;
+use connor::prepared::*;
+use core::doc::Field;
+
+fn param_prop(index: usize, op: Op, name: &str) -> Condition {
+    Condition::Prop(index, Box::new(Condition::ParamOp(op, name.to_string())))
+}
+
+fn params(values: Vec<(&str, Field)>) -> HashMap<String, Field> {
+    values
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect()
+}
+
+#[test]
+fn test_prepared_execute() {
+    let cond = Condition::CompoundOp(
+        CompoundOp::AND,
+        vec![
+            param_prop(0, Op::EQ, "first"),
+            param_prop(1, Op::NE, "second"),
+        ],
+    );
+    let prepared = PreparedCondition::prepare(cond).unwrap();
+    let doc = Field::Doc(get_doc());
+
+    let values = params(vec![("first", get_field(0)), ("second", Field::Null)]);
+    expect(prepared.execute(&values, &doc), true, false);
+
+    let values = params(vec![("first", get_field(0)), ("second", get_field(1))]);
+    expect(prepared.execute(&values, &doc), false, false);
+}
+
+#[test]
+fn test_prepared_param_types() {
+    let cond = Condition::CompoundOp(
+        CompoundOp::OR,
+        vec![
+            param_prop(0, Op::EQ, "value"),
+            param_prop(1, Op::GT, "value"),
+            param_prop(2, Op::IN, "list"),
+            param_prop(3, Op::LIKE, "pattern"),
+        ],
+    );
+    let prepared = PreparedCondition::prepare(cond).unwrap();
+
+    assert_eq!(prepared.params().get("value"), Some(&ParamType::Ordered));
+    assert_eq!(prepared.params().get("list"), Some(&ParamType::Array));
+    assert_eq!(prepared.params().get("pattern"), Some(&ParamType::String));
+}
+
+#[test]
+fn test_prepared_conflicting_param_types() {
+    let cond = Condition::CompoundOp(
+        CompoundOp::AND,
+        vec![
+            param_prop(0, Op::GT, "value"),
+            param_prop(1, Op::LIKE, "value"),
+        ],
+    );
+    let result = PreparedCondition::prepare(cond);
+    assert!(matches!(result, Err(Error::ConflictingParamTypes(name)) if name == "value"));
+}
+
+#[test]
+fn test_prepared_invalid_not() {
+    let cond = Condition::CompoundOp(CompoundOp::NOT, vec![]);
+    let result = PreparedCondition::prepare(cond);
+    assert!(matches!(result, Err(Error::InvalidCondition(_))));
+}
+
+#[test]
+fn test_prepared_bind_errors() {
+    let prepared = PreparedCondition::prepare(param_prop(0, Op::GT, "min")).unwrap();
+    let doc = Field::Doc(get_doc());
+
+    let result = prepared.execute(&params(vec![]), &doc);
+    assert!(matches!(result, Err(Error::UnboundParam(name)) if name == "min"));
+
+    let values = params(vec![("min", Field::Int(1)), ("max", Field::Int(2))]);
+    let result = prepared.execute(&values, &doc);
+    assert!(matches!(result, Err(Error::UnknownParam(name)) if name == "max"));
+
+    let values = params(vec![("min", Field::String("1".to_string()))]);
+    let result = prepared.execute(&values, &doc);
+    assert!(matches!(result, Err(Error::ParamTypeMismatch(name)) if name == "min"));
+}
+
+#[test]
+fn test_prepared_optional_value() {
+    let prepared = PreparedCondition::prepare(param_prop(0, Op::GT, "min")).unwrap();
+    let doc = Field::Doc(get_doc());
+
+    let values = params(vec![("min", Field::OptionalInt(Some(0)))]);
+    expect(prepared.execute(&values, &doc), true, false);
+}
+
+#[test]
+fn test_unbound_param_fails_to_match() {
+    let result = match_conditions(&param_prop(0, Op::EQ, "id"), &Field::Doc(get_doc()));
+    assert!(matches!(result, Err(Error::UnboundParam(_))));
+}
+
+#[test]
+fn test_execute_agrees_with_bind() {
+    let cond = Condition::CompoundOp(
+        CompoundOp::OR,
+        vec![
+            Condition::Meta(
+                Meta::Status,
+                Box::new(Condition::ParamOp(Op::EQ, "status".to_string())),
+            ),
+            Condition::CompoundOp(CompoundOp::NOT, vec![param_prop(0, Op::LT, "min")]),
+        ],
+    );
+    let prepared = PreparedCondition::prepare(cond).unwrap();
+    let doc = Field::Doc(get_doc());
+
+    for (status, min) in [("Deleted", 0), ("Deleted", 9), ("Active", 9)] {
+        let values = params(vec![
+            ("status", Field::String(status.to_string())),
+            ("min", Field::Int(min)),
+        ]);
+        let bound = prepared.bind(&values).unwrap();
+
+        assert_eq!(
+            prepared.execute(&values, &doc).unwrap(),
+            match_conditions(&bound, &doc).unwrap()
+        );
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use common::*;
+use connor::cond::*;
+use connor::span::*;
+use core::doc::Field;
+
+fn prop(index: usize, cond: Condition) -> Condition {
+    Condition::Prop(index, Box::new(cond))
+}
+
+fn assert_int_bound(bound: &Bound, expected: Option<(i64, bool)>) {
+    match (bound, expected) {
+        (Bound::Unbounded, None) => {}
+        (Bound::Included(Field::Int(v)), Some((e, true))) => assert_eq!(*v, e),
+        (Bound::Excluded(Field::Int(v)), Some((e, false))) => assert_eq!(*v, e),
+        _ => panic!("Unexpected bound {:?}, expected {:?}", bound, expected),
+    }
+}
+
+#[test]
+fn test_span_eq() {
+    let scan = extract_spans(&prop(0, Condition::Op(Op::EQ, Field::Int(5))), &[0]);
+    assert_eq!(scan.spans.len(), 1);
+    assert!(scan.spans[0].is_point());
+    assert!(scan.spans[0].contains(&Field::Int(5)));
+    assert!(scan.residual.is_none());
+}
+
+#[test]
+fn test_span_in_merges_duplicates() {
+    let cond = prop(0, Condition::Op(Op::IN, Field::IntArray(vec![7, 5, 7, 6])));
+    let scan = extract_spans(&cond, &[0]);
+    assert_eq!(scan.spans.len(), 3);
+    assert_int_bound(&scan.spans[0].start, Some((5, true)));
+    assert_int_bound(&scan.spans[1].start, Some((6, true)));
+    assert_int_bound(&scan.spans[2].start, Some((7, true)));
+    assert!(scan.residual.is_none());
+}
+
+#[test]
+fn test_span_range_intersection() {
+    let cond = prop(
+        0,
+        Condition::CompoundOp(
+            CompoundOp::AND,
+            vec![
+                Condition::Op(Op::GE, Field::Int(3)),
+                Condition::Op(Op::LT, Field::Int(10)),
+                Condition::Op(Op::GT, Field::Int(1)),
+            ],
+        ),
+    );
+    let scan = extract_spans(&cond, &[0]);
+    assert_eq!(scan.spans.len(), 1);
+    assert_int_bound(&scan.spans[0].start, Some((3, true)));
+    assert_int_bound(&scan.spans[0].end, Some((10, false)));
+    assert!(scan.residual.is_none());
+}
+
+#[test]
+fn test_span_lt_excludes_null() {
+    let scan = extract_spans(&Condition::Op(Op::LT, Field::Int(5)), &[]);
+    assert_eq!(scan.spans.len(), 1);
+    assert!(!scan.spans[0].contains(&Field::Null));
+    assert!(scan.spans[0].contains(&Field::Int(-100)));
+    assert!(!scan.spans[0].contains(&Field::Int(5)));
+}
+
+#[test]
+fn test_span_or_merges_overlapping() {
+    let cond = Condition::CompoundOp(
+        CompoundOp::OR,
+        vec![
+            prop(0, Condition::Op(Op::LE, Field::Int(5))),
+            prop(0, Condition::Op(Op::IN, Field::IntArray(vec![3, 20]))),
+            prop(0, Condition::Op(Op::GT, Field::Int(5))),
+        ],
+    );
+    let scan = extract_spans(&cond, &[0]);
+    assert_eq!(scan.spans.len(), 1);
+    assert!(matches!(scan.spans[0].start, Bound::Excluded(Field::Null)));
+    assert_int_bound(&scan.spans[0].end, None);
+    assert!(scan.residual.is_none());
+}
+
+#[test]
+fn test_span_or_with_unindexed_branch() {
+    let cond = Condition::CompoundOp(
+        CompoundOp::OR,
+        vec![
+            prop(0, Condition::Op(Op::EQ, Field::Int(5))),
+            prop(1, Condition::Op(Op::EQ, get_field(1))),
+        ],
+    );
+    let scan = extract_spans(&cond, &[0]);
+    assert_eq!(scan.spans.len(), 1);
+    assert_int_bound(&scan.spans[0].start, None);
+    assert_int_bound(&scan.spans[0].end, None);
+    assert!(scan.residual.is_some());
+}
+
+#[test]
+fn test_span_disjoint_and_is_empty() {
+    let cond = prop(
+        0,
+        Condition::CompoundOp(
+            CompoundOp::AND,
+            vec![
+                Condition::Op(Op::EQ, Field::Int(5)),
+                Condition::Op(Op::GT, Field::Int(5)),
+            ],
+        ),
+    );
+    let scan = extract_spans(&cond, &[0]);
+    assert!(scan.spans.is_empty());
+}
+
+#[test]
+fn test_span_like_prefix() {
+    let cond = prop(
+        1,
+        Condition::Op(Op::LIKE, Field::String("abc%".to_string())),
+    );
+    let scan = extract_spans(&cond, &[1]);
+    assert_eq!(scan.spans.len(), 1);
+    let span = &scan.spans[0];
+    assert!(span.contains(&Field::String("abc".to_string())));
+    assert!(span.contains(&Field::String("abczzz".to_string())));
+    assert!(!span.contains(&Field::String("abd".to_string())));
+    assert!(!span.contains(&Field::String("ab".to_string())));
+    assert!(scan.residual.is_none());
+
+    let cond = prop(
+        1,
+        Condition::Op(Op::LIKE, Field::String("%abc".to_string())),
+    );
+    let scan = extract_spans(&cond, &[1]);
+    assert_int_bound(&scan.spans[0].start, None);
+    assert!(scan.residual.is_some());
+}
+
+#[test]
+fn test_span_residual_keeps_other_props() {
+    let cond = Condition::CompoundOp(
+        CompoundOp::AND,
+        vec![
+            prop(0, Condition::Op(Op::GE, Field::Int(5))),
+            prop(1, Condition::Op(Op::EQ, get_field(1))),
+            prop(0, Condition::Op(Op::NE, Field::Int(7))),
+        ],
+    );
+    let scan = extract_spans(&cond, &[0]);
+    assert_eq!(scan.spans.len(), 1);
+    assert_int_bound(&scan.spans[0].start, Some((5, true)));
+
+    let residual = scan.residual.expect("Expected residual");
+    expect(
+        match_conditions(&residual, &Field::Doc(get_doc())),
+        true,
+        false,
+    );
+    match residual {
+        Condition::CompoundOp(CompoundOp::AND, conditions) => assert_eq!(conditions.len(), 2),
+        _ => panic!("Expected AND residual"),
+    }
+}
+
+#[test]
+fn test_span_nested_path() {
+    let cond = prop(
+        2,
+        prop(
+            0,
+            Condition::CompoundOp(
+                CompoundOp::AND,
+                vec![
+                    Condition::Op(Op::EQ, Field::Int(4)),
+                    Condition::Op(Op::LIKE, Field::String("%x".to_string())),
+                ],
+            ),
+        ),
+    );
+    let scan = extract_spans(&cond, &[2, 0]);
+    assert_eq!(scan.spans.len(), 1);
+    assert!(scan.spans[0].is_point());
+    match scan.residual {
+        Some(Condition::Prop(2, inner)) => {
+            assert!(matches!(*inner, Condition::Prop(0, _)));
+        }
+        _ => panic!("Expected nested Prop residual"),
+    }
+
+    let scan = extract_spans(&cond, &[2]);
+    assert_int_bound(&scan.spans[0].start, None);
+    assert!(scan.residual.is_some());
+}
@@ -0,0 +1,258 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use common::*;
+use connor::cond::*;
+use connor::subscription::*;
+use core::doc::{Doc, Field, Status};
+
+fn prop(index: usize, cond: Condition) -> Condition {
+    Condition::Prop(index, Box::new(cond))
+}
+
+fn and(conditions: Vec<Condition>) -> Condition {
+    Condition::CompoundOp(CompoundOp::AND, conditions)
+}
+
+fn doc(fields: Vec<Field>) -> Doc {
+    Doc {
+        hidden: false,
+        status: Status::Active,
+        fields,
+        schema_version_id: "".to_string(),
+    }
+}
+
+#[test]
+fn test_matches_eq_in_and_range_filters() {
+    let mut matcher = SubscriptionMatcher::new();
+    let eq = matcher.add(prop(0, Condition::Op(Op::EQ, Field::Int(5))));
+    let eq_float = matcher.add(prop(0, Condition::Op(Op::EQ, Field::Float(5.0))));
+    let other = matcher.add(prop(0, Condition::Op(Op::EQ, Field::Int(6))));
+    let in_strs = matcher.add(prop(
+        1,
+        Condition::Op(
+            Op::IN,
+            Field::StringArray(vec!["a".to_string(), "str".to_string()]),
+        ),
+    ));
+    let range = matcher.add(prop(2, Condition::Op(Op::GT, Field::Float(1.0))));
+    let both = matcher.add(and(vec![
+        prop(0, Condition::Op(Op::GE, Field::Int(5))),
+        prop(1, Condition::Op(Op::EQ, Field::String("x".to_string()))),
+    ]));
+    assert_eq!(matcher.len(), 6);
+
+    let matched = matcher.matches(&get_doc()).unwrap();
+
+    assert_eq!(matched, vec![eq, eq_float, in_strs, range]);
+    assert!(!matched.contains(&other));
+    assert!(!matched.contains(&both));
+}
+
+#[test]
+fn test_evaluates_residual_and_unindexed_filters() {
+    let mut matcher = SubscriptionMatcher::new();
+    let residual = matcher.add(and(vec![
+        prop(0, Condition::Op(Op::EQ, Field::Int(5))),
+        prop(1, Condition::Op(Op::LIKE, Field::String("s%".to_string()))),
+    ]));
+    let failing_residual = matcher.add(and(vec![
+        prop(0, Condition::Op(Op::EQ, Field::Int(5))),
+        prop(1, Condition::Op(Op::LIKE, Field::String("x%".to_string()))),
+    ]));
+    let unindexed = matcher.add(Condition::CompoundOp(
+        CompoundOp::OR,
+        vec![
+            prop(0, Condition::Op(Op::EQ, Field::Int(1))),
+            prop(3, Condition::Op(Op::EQ, Field::Bool(true))),
+        ],
+    ));
+
+    let matched = matcher.matches(&get_doc()).unwrap();
+
+    assert_eq!(matched, vec![residual, unindexed]);
+    assert!(!matched.contains(&failing_residual));
+}
+
+#[test]
+fn test_add_and_remove_at_runtime() {
+    let mut matcher = SubscriptionMatcher::new();
+    let first = matcher.add(prop(0, Condition::Op(Op::EQ, Field::Int(5))));
+    let second = matcher.add(prop(0, Condition::Op(Op::IN, Field::IntArray(vec![4, 5]))));
+    assert_eq!(matcher.matches(&get_doc()).unwrap(), vec![first, second]);
+
+    assert!(matcher.remove(first));
+    assert!(!matcher.remove(first));
+    assert_eq!(matcher.matches(&get_doc()).unwrap(), vec![second]);
+
+    let third = matcher.add(prop(4, Condition::Op(Op::EQ, now())));
+    assert_ne!(third, first);
+    assert_eq!(matcher.matches(&get_doc()).unwrap(), vec![second, third]);
+
+    assert!(matcher.remove(second));
+    assert!(matcher.remove(third));
+    assert!(matcher.is_empty());
+    assert!(matcher.matches(&get_doc()).unwrap().is_empty());
+}
+
+#[test]
+fn test_missing_props_and_empty_optionals_match_nothing() {
+    let mut matcher = SubscriptionMatcher::new();
+    matcher.add(prop(7, Condition::Op(Op::EQ, Field::Int(5))));
+    let null = matcher.add(prop(0, Condition::Op(Op::EQ, Field::Null)));
+    let some = matcher.add(prop(1, Condition::Op(Op::EQ, Field::Int(3))));
+
+    let matched = matcher
+        .matches(&doc(vec![
+            Field::OptionalInt(None),
+            Field::OptionalInt(Some(3)),
+        ]))
+        .unwrap();
+    assert_eq!(matched, vec![some]);
+
+    let matched = matcher.matches(&doc(vec![Field::Null])).unwrap();
+    assert_eq!(matched, vec![null]);
+}
+
+#[test]
+fn test_values_without_keys_compare_against_points() {
+    let mut matcher = SubscriptionMatcher::new();
+    let decimal = matcher.add(prop(
+        0,
+        Condition::Op(Op::EQ, Field::Decimal("5.0".parse().unwrap())),
+    ));
+    let int = matcher.add(prop(0, Condition::Op(Op::EQ, Field::Int(5))));
+
+    let matched = matcher.matches(&doc(vec![Field::Int(5)])).unwrap();
+    assert_eq!(matched, vec![decimal, int]);
+
+    let matched = matcher
+        .matches(&doc(vec![Field::BigInt("5".parse().unwrap())]))
+        .unwrap();
+    assert_eq!(matched, vec![decimal, int]);
+}
+
+// A small deterministic generator, so the comparison with a full scan is reproducible.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % bound
+    }
+}
+
+fn random_leaf(rng: &mut Rng) -> Condition {
+    let value = Field::Int(rng.next(10) as i64);
+    let cond = match rng.next(6) {
+        0 => Condition::Op(Op::EQ, value),
+        1 => Condition::Op(Op::GT, value),
+        2 => Condition::Op(Op::LE, value),
+        3 => Condition::Op(
+            Op::IN,
+            Field::IntArray(vec![rng.next(10) as i64, rng.next(10) as i64]),
+        ),
+        4 => Condition::Op(Op::NE, value),
+        _ => Condition::Op(Op::EQ, Field::Float(rng.next(20) as f64 / 2.0)),
+    };
+    prop(rng.next(3) as usize, cond)
+}
+
+#[test]
+fn test_agrees_with_full_scan() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    let mut matcher = SubscriptionMatcher::new();
+    let mut filters = Vec::new();
+    for _ in 0..2000 {
+        let cond = match rng.next(3) {
+            0 => random_leaf(&mut rng),
+            1 => and(vec![random_leaf(&mut rng), random_leaf(&mut rng)]),
+            _ => Condition::CompoundOp(
+                CompoundOp::OR,
+                vec![random_leaf(&mut rng), random_leaf(&mut rng)],
+            ),
+        };
+        filters.push((matcher.add(cond.clone()), cond));
+    }
+    // remove some filters to make sure removal keeps the index consistent
+    for (id, _) in filters.iter().step_by(7) {
+        assert!(matcher.remove(*id));
+    }
+    let remaining = filters
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| i % 7 != 0)
+        .map(|(_, filter)| filter)
+        .collect::<Vec<_>>();
+
+    for _ in 0..50 {
+        let fields = (0..3)
+            .map(|_| match rng.next(4) {
+                0 => Field::Float(rng.next(20) as f64 / 2.0),
+                1 => Field::OptionalInt(None),
+                _ => Field::Int(rng.next(10) as i64),
+            })
+            .collect();
+        let doc = doc(fields);
+        let doc_field = Field::Doc(doc.clone());
+        let expected = remaining
+            .iter()
+            .filter(|(_, cond)| match_conditions(cond, &doc_field).unwrap())
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        assert_eq!(matcher.matches(&doc).unwrap(), expected, "{:?}", doc.fields);
+    }
+}
+
+#[test]
+fn test_agrees_with_full_scan_on_values_of_other_types() {
+    let mut matcher = SubscriptionMatcher::new();
+    let filters = [
+        prop(0, Condition::Op(Op::LT, Field::Int(5))),
+        prop(0, Condition::Op(Op::LE, Field::Int(5))),
+        prop(0, Condition::Op(Op::NE, Field::Int(5))),
+        prop(0, Condition::Op(Op::GE, Field::Int(5))),
+        prop(0, Condition::Op(Op::EQ, Field::Int(5))),
+        prop(0, Condition::Op(Op::NIN, Field::IntArray(vec![5]))),
+        prop(
+            0,
+            Condition::CompoundOp(CompoundOp::NOT, vec![Condition::Op(Op::EQ, Field::Int(5))]),
+        ),
+        and(vec![
+            prop(0, Condition::Op(Op::LT, Field::Int(5))),
+            prop(1, Condition::Op(Op::EQ, Field::Int(1))),
+        ]),
+    ]
+    .map(|cond| (matcher.add(cond.clone()), cond));
+
+    for value in [
+        Field::Bool(true),
+        Field::String("a".to_string()),
+        Field::Int(3),
+        Field::Int(5),
+        Field::Float(7.5),
+    ] {
+        let doc = doc(vec![value, Field::Int(1)]);
+        let doc_field = Field::Doc(doc.clone());
+        let expected = filters
+            .iter()
+            .filter(|(_, cond)| match_conditions(cond, &doc_field).unwrap())
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        assert_eq!(matcher.matches(&doc).unwrap(), expected, "{:?}", doc.fields);
+    }
+}
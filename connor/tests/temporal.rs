@@ -0,0 +1,143 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use chrono::{FixedOffset, NaiveDate, NaiveTime, TimeZone};
+use common::*;
+use connor::cond::*;
+use connor::expr::*;
+use core::doc::Field;
+
+const OPS: [Op; 6] = [Op::EQ, Op::NE, Op::GT, Op::GE, Op::LT, Op::LE];
+
+// Asserts the result of every comparison operator applied with the target to the value, in
+// the order EQ, NE, GT, GE, LT, LE.
+fn assert_ops(value: Field, target: Field, expected: [bool; 6]) {
+    for (op, expected) in OPS.into_iter().zip(expected) {
+        let cond = Condition::Op(op.clone(), target.clone());
+        let actual = match_conditions(&cond, &value).unwrap();
+        assert_eq!(
+            actual, expected,
+            "{:?} {:?} {:?} should be {}",
+            value, op, target, expected
+        );
+    }
+}
+
+const EQUAL: [bool; 6] = [true, false, false, true, false, true];
+const GREATER: [bool; 6] = [false, true, true, true, false, false];
+const LESS: [bool; 6] = [false, true, false, false, true, true];
+const UNORDERED: [bool; 6] = [false, true, false, false, false, false];
+
+fn zoned(hours: i32, y: i32, mo: u32, d: u32, h: u32, mi: u32, s: u32) -> Field {
+    let offset = FixedOffset::east_opt(hours * 3600).unwrap();
+    Field::ZonedDateTime(offset.with_ymd_and_hms(y, mo, d, h, mi, s).unwrap())
+}
+
+fn date(y: i32, m: u32, d: u32) -> Field {
+    Field::Date(NaiveDate::from_ymd_opt(y, m, d).unwrap())
+}
+
+fn time(h: u32, m: u32, s: u32) -> Field {
+    Field::Time(NaiveTime::from_hms_opt(h, m, s).unwrap())
+}
+
+#[test]
+fn test_zoned_date_time_compares_by_instant() {
+    // now() is 2024-04-08T12:34:56Z
+    assert_ops(zoned(2, 2024, 4, 8, 14, 34, 56), now(), EQUAL);
+    assert_ops(now(), zoned(-5, 2024, 4, 8, 7, 34, 56), EQUAL);
+    assert_ops(zoned(2, 2024, 4, 8, 14, 34, 57), now(), GREATER);
+    assert_ops(
+        zoned(9, 2024, 4, 8, 20, 0, 0),
+        zoned(-5, 2024, 4, 8, 7, 0, 0),
+        LESS,
+    );
+}
+
+#[test]
+fn test_date_compares_with_local_calendar_date() {
+    assert_ops(date(2024, 4, 8), date(2024, 4, 8), EQUAL);
+    assert_ops(date(2024, 4, 9), date(2024, 4, 8), GREATER);
+    // every datetime on the day equals the date
+    assert_ops(now(), date(2024, 4, 8), EQUAL);
+    assert_ops(yesterday(), date(2024, 4, 8), LESS);
+    assert_ops(date(2024, 4, 8), yesterday(), GREATER);
+    // zoned datetimes use the date in their own offset
+    assert_ops(zoned(2, 2024, 4, 9, 1, 0, 0), date(2024, 4, 9), EQUAL);
+    assert_ops(zoned(-5, 2024, 4, 8, 22, 0, 0), date(2024, 4, 9), LESS);
+}
+
+#[test]
+fn test_time_compares_with_local_time_of_day() {
+    assert_ops(time(12, 34, 56), time(12, 0, 0), GREATER);
+    assert_ops(now(), time(12, 34, 56), EQUAL);
+    assert_ops(yesterday(), time(13, 0, 0), LESS);
+    assert_ops(zoned(2, 2024, 4, 8, 14, 34, 56), time(14, 34, 56), EQUAL);
+}
+
+#[test]
+fn test_date_and_time_are_unordered() {
+    assert_ops(date(2024, 4, 8), time(12, 0, 0), UNORDERED);
+    assert_ops(time(12, 0, 0), date(2024, 4, 8), UNORDERED);
+    assert_ops(Field::Null, date(2024, 4, 8), UNORDERED);
+}
+
+#[test]
+fn test_optional_temporal_values() {
+    let cond = Condition::Op(Op::EQ, date(2024, 4, 8));
+    let value = Field::OptionalDate(NaiveDate::from_ymd_opt(2024, 4, 8));
+    assert!(match_conditions(&cond, &value).unwrap());
+    assert!(!match_conditions(&cond, &Field::OptionalDate(None)).unwrap());
+
+    let cond = Condition::Op(Op::LT, time(12, 0, 0));
+    assert!(!match_conditions(&cond, &Field::OptionalTime(None)).unwrap());
+}
+
+#[test]
+fn test_between_dates() {
+    let cond = Condition::Between(Range {
+        start: Target::Value(date(2024, 4, 1)),
+        end: Target::Value(date(2024, 4, 8)),
+        start_inclusive: true,
+        end_inclusive: true,
+    });
+    assert!(match_conditions(&cond, &now()).unwrap());
+    assert!(match_conditions(&cond, &zoned(2, 2024, 4, 1, 0, 30, 0)).unwrap());
+    // 2024-04-09T01:00Z, but still April 8 in its own offset
+    assert!(match_conditions(&cond, &zoned(-2, 2024, 4, 8, 23, 0, 0)).unwrap());
+    assert!(!match_conditions(&cond, &date(2024, 4, 9)).unwrap());
+}
+
+#[test]
+fn test_in_with_zoned_value() {
+    let cond = Condition::Op(
+        Op::IN,
+        Field::DateTimeArray(vec![yesterday_time(), now_time()]),
+    );
+    assert!(match_conditions(&cond, &zoned(2, 2024, 4, 8, 14, 34, 56)).unwrap());
+    assert!(match_conditions(&cond, &date(2024, 4, 7)).unwrap());
+    assert!(!match_conditions(&cond, &date(2024, 4, 6)).unwrap());
+}
+
+#[test]
+fn test_parts_of_temporal_values() {
+    let expr = |func: Func| {
+        Condition::ExprOp(
+            Op::EQ,
+            Expr::Call(func, Box::new(Expr::Value)),
+            Expr::Literal(Field::Int(14)),
+        )
+    };
+    assert!(match_conditions(&expr(Func::Hour), &zoned(2, 2024, 4, 8, 14, 34, 56)).unwrap());
+    assert!(match_conditions(&expr(Func::Hour), &time(14, 0, 0)).unwrap());
+    assert!(!match_conditions(&expr(Func::Day), &date(2024, 4, 8)).unwrap());
+}
@@ -0,0 +1,170 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use common::*;
+use connor::cond::*;
+use connor::text::*;
+use core::doc::Field;
+
+fn text_match(query: &str) -> Condition {
+    Condition::Op(Op::MATCH, Field::String(query.to_string()))
+}
+
+fn text(value: &str) -> Field {
+    Field::String(value.to_string())
+}
+
+const DESCRIPTION: &str = "The quick brown fox jumps over the lazy dogs, running in the fields.";
+
+#[test]
+fn test_tokenize() {
+    assert_eq!(
+        tokenize("The Quick, brown-foxes ARE running!"),
+        vec!["quick", "brown", "fox", "run"]
+    );
+    assert_eq!(stem("classes"), "class");
+    assert_eq!(stem("stories"), "story");
+    assert_eq!(stem("status"), "status");
+    assert_eq!(stem("is"), "is");
+    assert_eq!(stem("stopped"), "stop");
+    assert_eq!(stem("falling"), "fall");
+}
+
+#[test]
+fn test_match_all_terms() {
+    let doc = text(DESCRIPTION);
+    expect(match_conditions(&text_match("lazy dog"), &doc), true, false);
+    expect(
+        match_conditions(&text_match("Foxes RUN"), &doc),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&text_match("jumping fox"), &doc),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&text_match("lazy cat"), &doc),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_match_any_term() {
+    let doc = text(DESCRIPTION);
+    expect(
+        match_conditions(&text_match("cat OR dog"), &doc),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&text_match("cat OR bird"), &doc),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_match_phrase() {
+    let doc = text(DESCRIPTION);
+    expect(
+        match_conditions(&text_match("\"quick brown fox\""), &doc),
+        true,
+        false,
+    );
+    // stop words are left out of phrases too
+    expect(
+        match_conditions(&text_match("\"over the lazy dog\""), &doc),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&text_match("\"brown quick fox\""), &doc),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_match_stop_words_only() {
+    expect(
+        match_conditions(&text_match("the and of"), &text(DESCRIPTION)),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_match_string_array() {
+    let tags = Field::StringArray(vec!["red apples".to_string(), "green pears".to_string()]);
+    expect(
+        match_conditions(&text_match("apple pear"), &tags),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&text_match("\"apples green\""), &tags),
+        false,
+        false,
+    );
+
+    let tags = Field::OptionalStringArray(vec![None, Some("ripe bananas".to_string())]);
+    expect(match_conditions(&text_match("banana"), &tags), true, false);
+}
+
+#[test]
+fn test_match_non_text() {
+    expect(
+        match_conditions(&text_match("5"), &Field::Int(5)),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(&text_match("x"), &Field::OptionalString(None)),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_score() {
+    let query = Query::parse("fox");
+    let short = query.score(&["a fox"]);
+    let long = query.score(&["a fox in a very long sentence about many other animals"]);
+    let repeated = query.score(&["a fox and another fox"]);
+
+    assert!(short > long);
+    assert!(repeated > query.score(&["a fox and another cat"]));
+    assert_eq!(query.score(&["a cat"]), 0.0);
+}
+
+#[test]
+fn test_score_conditions() {
+    let mut first = get_doc();
+    first.fields[1] = text("fox and dog");
+    let mut second = get_doc();
+    second.fields[1] = text("a fox");
+
+    let cond = Condition::Prop(1, Box::new(text_match("fox OR dog")));
+    let first_score = score_conditions(&cond, &Field::Doc(first)).unwrap();
+    let second_score = score_conditions(&cond, &Field::Doc(second.clone())).unwrap();
+    assert!(first_score > second_score);
+    assert!(second_score > 0.0);
+
+    let cond = Condition::CompoundOp(
+        CompoundOp::NOT,
+        vec![Condition::Prop(1, Box::new(text_match("fox")))],
+    );
+    assert_eq!(score_conditions(&cond, &Field::Doc(second)).unwrap(), 0.0);
+}
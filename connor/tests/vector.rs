@@ -0,0 +1,259 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+mod common;
+
+use common::*;
+use connor::cond::*;
+use connor::error::Error;
+use connor::hnsw::HnswIndex;
+use connor::vector::*;
+use core::doc::{Doc, Field};
+
+fn assert_close(actual: Option<f64>, expected: f64) {
+    let actual = actual.expect("Expected a measure");
+    assert!(
+        (actual - expected).abs() < 1e-6,
+        "Expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+fn similar(vector: &[f32], metric: Metric, threshold: f64) -> Condition {
+    Condition::Similar(Threshold {
+        vector: vector.to_vec(),
+        metric,
+        threshold,
+    })
+}
+
+fn doc_with_vector(vector: Field) -> Doc {
+    let mut doc = Doc::new();
+    doc.fields.push(vector);
+    doc
+}
+
+#[test]
+fn test_measures() {
+    let a = [1.0, 2.0, 3.0];
+    let b = [4.0, -5.0, 6.0];
+
+    assert_close(dot_product(&a, &b), 12.0);
+    assert_close(l2_distance(&a, &b), 67.0f64.sqrt());
+    assert_close(
+        cosine_similarity(&a, &b),
+        12.0 / (14.0f64.sqrt() * 77.0f64.sqrt()),
+    );
+    assert_close(cosine_similarity(&a, &[2.0, 4.0, 6.0]), 1.0);
+    assert_close(cosine_similarity(&[1.0, 0.0], &[-1.0, 0.0]), -1.0);
+    assert_close(Metric::Cosine.distance(&[1.0, 0.0], &[0.0, 1.0]), 1.0);
+    assert_close(Metric::Dot.distance(&a, &b), -12.0);
+
+    assert_eq!(cosine_similarity(&a, &[0.0, 0.0, 0.0]), None);
+    assert_eq!(dot_product(&a, &[1.0, 2.0]), None);
+    assert_eq!(l2_distance(&a, &[]), None);
+}
+
+#[test]
+fn test_similar_threshold() {
+    let field = Field::Vector(vec![1.0, 1.0]);
+
+    let cond = similar(&[1.0, 0.0], Metric::Cosine, 0.7);
+    expect(match_conditions(&cond, &field), true, false);
+    let cond = similar(&[1.0, 0.0], Metric::Cosine, 0.75);
+    expect(match_conditions(&cond, &field), false, false);
+
+    let cond = similar(&[2.0, 3.0], Metric::Dot, 5.0);
+    expect(match_conditions(&cond, &field), true, false);
+    let cond = similar(&[2.0, 3.0], Metric::Dot, 5.5);
+    expect(match_conditions(&cond, &field), false, false);
+
+    let cond = similar(&[4.0, 5.0], Metric::L2, 5.0);
+    expect(match_conditions(&cond, &field), true, false);
+    let cond = similar(&[4.0, 5.0], Metric::L2, 4.9);
+    expect(match_conditions(&cond, &field), false, false);
+}
+
+#[test]
+fn test_similar_never_matches_other_fields() {
+    let cond = similar(&[1.0, 0.0], Metric::L2, 100.0);
+    expect(
+        match_conditions(&cond, &Field::OptionalVector(None)),
+        false,
+        false,
+    );
+    expect(
+        match_conditions(&cond, &Field::OptionalVector(Some(vec![1.0, 0.0]))),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&cond, &Field::FloatArray(vec![1.0, 0.0])),
+        false,
+        false,
+    );
+
+    let cond = similar(&[1.0, 0.0], Metric::Cosine, -1.0);
+    expect(
+        match_conditions(&cond, &Field::Vector(vec![0.0, 0.0])),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_similar_rejects_other_dimensions() {
+    let cond = similar(&[1.0, 0.0], Metric::L2, 100.0);
+
+    for field in [
+        Field::Vector(vec![1.0, 0.0, 0.0]),
+        Field::OptionalVector(Some(vec![1.0])),
+    ] {
+        let result = match_conditions(&cond, &field);
+        assert!(
+            matches!(result, Err(Error::DimensionMismatch(2, _))),
+            "{:?}",
+            result
+        );
+    }
+    let result = match_conditions(
+        &Condition::Prop(1, Box::new(cond)),
+        &Field::Doc(doc_with_vector(Field::Vector(vec![]))),
+    );
+    assert!(matches!(result, Err(Error::DimensionMismatch(2, 0))));
+}
+
+#[test]
+fn test_vector_equality() {
+    let cond = Condition::Op(Op::EQ, Field::Vector(vec![0.5, 0.25]));
+    expect(
+        match_conditions(&cond, &Field::OptionalVector(Some(vec![0.5, 0.25]))),
+        true,
+        false,
+    );
+    expect(
+        match_conditions(&cond, &Field::Vector(vec![0.5])),
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_top_k() {
+    let docs = vec![
+        doc_with_vector(Field::Vector(vec![0.0, 3.0])),
+        doc_with_vector(Field::Vector(vec![1.0, 1.0])),
+        doc_with_vector(Field::OptionalVector(None)),
+        doc_with_vector(Field::Vector(vec![1.0, 1.0, 1.0])),
+        doc_with_vector(Field::OptionalVector(Some(vec![0.0, 1.0]))),
+        doc_with_vector(Field::Vector(vec![1.0, 1.0])),
+    ];
+
+    let found = top_k(&docs, 1, &[0.0, 0.0], Metric::L2, 3);
+    let indexes: Vec<usize> = found.iter().map(|n| n.index).collect();
+    assert_eq!(indexes, vec![4, 1, 5]);
+    assert_close(Some(found[0].measure), 1.0);
+    assert_close(Some(found[1].measure), 2.0f64.sqrt());
+
+    // higher dot products are closer
+    let found = top_k(&docs, 1, &[0.0, 1.0], Metric::Dot, 2);
+    let indexes: Vec<usize> = found.iter().map(|n| n.index).collect();
+    assert_eq!(indexes, vec![0, 1]);
+    assert_close(Some(found[0].measure), 3.0);
+
+    assert_eq!(top_k(&docs, 1, &[0.0, 0.0], Metric::L2, 10).len(), 4);
+    assert!(top_k(&docs, 1, &[0.0, 0.0], Metric::L2, 0).is_empty());
+    assert!(top_k(&docs, 7, &[0.0, 0.0], Metric::L2, 3).is_empty());
+}
+
+#[test]
+fn test_binary_encoding() {
+    let vector = vec![1.0, -0.5, 3.25e-7, f32::MAX];
+    let bytes = core::vector::encode(&vector);
+
+    assert_eq!(bytes.len(), 16);
+    assert_eq!(&bytes[..4], &[0x00, 0x00, 0x80, 0x3f]);
+    assert_eq!(core::vector::decode(&bytes), Some(vector));
+    assert_eq!(core::vector::decode(&bytes[..5]), None);
+    assert_eq!(core::vector::decode(&[]), Some(vec![]));
+}
+
+// Returns vectors spread over the unit cube, generated deterministically.
+fn sample_vectors(count: usize, dimension: usize) -> Vec<Vec<f32>> {
+    let mut state = 42u64;
+    let mut next = move || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+        (state >> 40) as f32 / (1u64 << 24) as f32
+    };
+    (0..count)
+        .map(|_| (0..dimension).map(|_| next()).collect())
+        .collect()
+}
+
+#[test]
+fn test_hnsw_recall() {
+    let vectors = sample_vectors(1000, 8);
+    let queries = sample_vectors(1020, 8).split_off(1000);
+    let docs: Vec<Doc> = vectors
+        .iter()
+        .map(|v| doc_with_vector(Field::Vector(v.clone())))
+        .collect();
+
+    for metric in [Metric::L2, Metric::Cosine] {
+        let mut index = HnswIndex::new(metric);
+        for (id, vector) in vectors.iter().enumerate() {
+            assert_eq!(index.insert(vector.clone()).unwrap(), id);
+        }
+        assert_eq!(index.len(), 1000);
+        assert_eq!(index.dimension(), Some(8));
+
+        let mut hits = 0;
+        for query in &queries {
+            let exact: Vec<usize> = top_k(&docs, 1, query, metric, 10)
+                .iter()
+                .map(|n| n.index)
+                .collect();
+            let approx = index.search(query, 10, 64).unwrap();
+            assert_eq!(approx.len(), 10);
+            assert!(approx
+                .windows(2)
+                .all(|w| metric.within(w[0].measure, w[1].measure)));
+            hits += approx.iter().filter(|n| exact.contains(&n.index)).count();
+        }
+        // an approximate search may miss a few of the true nearest neighbours
+        assert!(
+            hits >= 190,
+            "Recall too low for {:?}: {} of 200",
+            metric,
+            hits
+        );
+    }
+}
+
+#[test]
+fn test_hnsw_edge_cases() {
+    let mut index = HnswIndex::new(Metric::L2);
+    assert!(index.is_empty());
+    assert!(index.search(&[1.0, 2.0], 5, 10).unwrap().is_empty());
+
+    index.insert(vec![1.0, 2.0]).unwrap();
+    let found = index.search(&[1.0, 2.0], 5, 10).unwrap();
+    assert_eq!(
+        found,
+        vec![Neighbor {
+            index: 0,
+            measure: 0.0
+        }]
+    );
+
+    assert!(index.insert(vec![1.0]).is_err());
+    assert!(index.search(&[1.0, 2.0, 3.0], 1, 1).is_err());
+}
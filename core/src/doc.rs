@@ -11,6 +11,9 @@
 use chrono;
 use std::clone::Clone;
 
+use crate::json::Json;
+use crate::number::{BigInt, Decimal};
+
 pub const DOC_ID_FIELD_INDEX: usize = 0;
 
 #[derive(Clone, Debug)]
@@ -19,25 +22,58 @@ pub enum Field {
     Bool(bool),
     Int(i64),
     Float(f64),
+    Decimal(Decimal),
+    BigInt(BigInt),
     String(String),
     DateTime(chrono::DateTime<chrono::Utc>),
+    // A datetime that keeps the offset it was written with.
+    ZonedDateTime(chrono::DateTime<chrono::FixedOffset>),
+    Date(chrono::NaiveDate),
+    // A time of day without a date or offset.
+    Time(chrono::NaiveTime),
+    GeoPoint(GeoPoint),
+    // An embedding whose dimension is the length of the vector.
+    Vector(Vec<f32>),
+    // A JSON value of any kind, including null.
+    Json(Json),
+    Blob(Vec<u8>),
     Doc(Doc),
     BoolArray(Vec<bool>),
     IntArray(Vec<i64>),
     FloatArray(Vec<f64>),
+    DecimalArray(Vec<Decimal>),
+    BigIntArray(Vec<BigInt>),
     StringArray(Vec<String>),
     DateTimeArray(Vec<chrono::DateTime<chrono::Utc>>),
+    GeoPointArray(Vec<GeoPoint>),
+    BlobArray(Vec<Vec<u8>>),
     DocArray(Vec<Doc>),
     OptionalBool(Option<bool>),
     OptionalInt(Option<i64>),
     OptionalFloat(Option<f64>),
+    OptionalDecimal(Option<Decimal>),
+    OptionalBigInt(Option<BigInt>),
     OptionalString(Option<String>),
     OptionalDateTime(Option<chrono::DateTime<chrono::Utc>>),
+    OptionalZonedDateTime(Option<chrono::DateTime<chrono::FixedOffset>>),
+    OptionalDate(Option<chrono::NaiveDate>),
+    OptionalTime(Option<chrono::NaiveTime>),
+    OptionalGeoPoint(Option<GeoPoint>),
+    OptionalVector(Option<Vec<f32>>),
+    OptionalBlob(Option<Vec<u8>>),
     OptionalBoolArray(Vec<Option<bool>>),
     OptionalIntArray(Vec<Option<i64>>),
     OptionalFloatArray(Vec<Option<f64>>),
     OptionalStringArray(Vec<Option<String>>),
     OptionalDateTimeArray(Vec<Option<chrono::DateTime<chrono::Utc>>>),
+    OptionalGeoPointArray(Vec<Option<GeoPoint>>),
+}
+
+// GeoPoint is a location on Earth given by its latitude and longitude in degrees.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lon: f64,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -9,4 +9,7 @@
 // licenses/APL.txt.
 
 pub mod doc;
+pub mod json;
 pub mod mapping;
+pub mod number;
+pub mod vector;
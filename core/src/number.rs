@@ -0,0 +1,276 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+// The largest exponent accepted when parsing, which keeps the plain notation of numbers
+// reasonably short.
+const MAX_EXPONENT: i64 = 100_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseNumberError(String);
+
+impl fmt::Display for ParseNumberError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid number: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseNumberError {}
+
+// Decimal is an exact decimal number of any precision, the coefficient times 10 to the power
+// of minus the scale. The scale given when parsing is kept, so `1.50` is displayed as `1.50`,
+// but compares equal to `1.5`.
+#[derive(Clone, Debug)]
+pub struct Decimal {
+    negative: bool,
+    // The decimal digits of the coefficient, most significant first, without leading zeros.
+    coefficient: Vec<u8>,
+    scale: i64,
+}
+
+impl Decimal {
+    pub fn zero() -> Self {
+        Self {
+            negative: false,
+            coefficient: Vec::new(),
+            scale: 0,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficient.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    // Returns true if the number has no fractional part.
+    pub fn is_integer(&self) -> bool {
+        let int_len = self.coefficient.len() as i64 - self.scale;
+        self.coefficient
+            .iter()
+            .skip(int_len.max(0) as usize)
+            .all(|digit| *digit == 0)
+    }
+
+    // Returns the exact value of the float, or `None` if it is NaN or infinite.
+    pub fn from_f64(val: f64) -> Option<Self> {
+        if !val.is_finite() {
+            return None;
+        }
+        let bits = val.to_bits();
+        let negative = bits >> 63 == 1;
+        let biased_exponent = ((bits >> 52) & 0x7ff) as i64;
+        let fraction = bits & ((1 << 52) - 1);
+        // the value is mantissa * 2^exponent
+        let (mantissa, exponent) = if biased_exponent == 0 {
+            (fraction, -1074)
+        } else {
+            (fraction | 1 << 52, biased_exponent - 1075)
+        };
+        let mut digits: Vec<u8> = mantissa
+            .to_string()
+            .bytes()
+            .rev()
+            .map(|b| b - b'0')
+            .collect();
+        // 2^-n is 5^n / 10^n
+        let (factor, times, scale) = if exponent >= 0 {
+            (2, exponent, 0)
+        } else {
+            (5, -exponent, -exponent)
+        };
+        for _ in 0..times {
+            let mut carry = 0;
+            for digit in digits.iter_mut() {
+                let product = *digit * factor + carry;
+                *digit = product % 10;
+                carry = product / 10;
+            }
+            if carry > 0 {
+                digits.push(carry);
+            }
+        }
+        digits.reverse();
+        Some(Self::new(negative, digits, scale))
+    }
+
+    fn new(negative: bool, digits: Vec<u8>, scale: i64) -> Self {
+        let leading_zeros = digits.iter().take_while(|digit| **digit == 0).count();
+        let coefficient = digits[leading_zeros..].to_vec();
+        Self {
+            negative: negative && !coefficient.is_empty(),
+            coefficient,
+            scale,
+        }
+    }
+
+    fn cmp_magnitude(&self, other: &Self) -> Ordering {
+        match (self.is_zero(), other.is_zero()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            _ => {}
+        }
+        // the position of the most significant digit decides, then the digits themselves
+        let self_magnitude = self.coefficient.len() as i64 - self.scale;
+        let other_magnitude = other.coefficient.len() as i64 - other.scale;
+        self_magnitude.cmp(&other_magnitude).then_with(|| {
+            let len = self.coefficient.len().max(other.coefficient.len());
+            let digit = |digits: &[u8], i: usize| digits.get(i).copied().unwrap_or(0);
+            (0..len)
+                .map(|i| digit(&self.coefficient, i).cmp(&digit(&other.coefficient, i)))
+                .find(|ord| *ord != Ordering::Equal)
+                .unwrap_or(Ordering::Equal)
+        })
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => self.cmp_magnitude(other),
+            (true, true) => other.cmp_magnitude(self),
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl From<i64> for Decimal {
+    fn from(val: i64) -> Self {
+        let digits = val
+            .unsigned_abs()
+            .to_string()
+            .bytes()
+            .map(|b| b - b'0')
+            .collect();
+        Self::new(val < 0, digits, 0)
+    }
+}
+
+// Parses numbers such as `-12.50` or `1.2e-3`.
+impl FromStr for Decimal {
+    type Err = ParseNumberError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseNumberError(s.to_string());
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+            Some(pos) => {
+                let exponent: i64 = unsigned[pos + 1..].parse().map_err(|_| invalid())?;
+                if exponent.abs() > MAX_EXPONENT {
+                    return Err(invalid());
+                }
+                (&unsigned[..pos], exponent)
+            }
+            None => (unsigned, 0),
+        };
+        let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if int_part.len() + frac_part.len() == 0 || !is_digits(int_part) || !is_digits(frac_part) {
+            return Err(invalid());
+        }
+        let digits = int_part
+            .bytes()
+            .chain(frac_part.bytes())
+            .map(|b| b - b'0')
+            .collect();
+        Ok(Self::new(
+            negative,
+            digits,
+            frac_part.len() as i64 - exponent,
+        ))
+    }
+}
+
+// Displays the number in plain notation, with as many fractional digits as its scale.
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+        let digits: String = self
+            .coefficient
+            .iter()
+            .map(|d| (b'0' + d) as char)
+            .collect();
+        if self.scale <= 0 {
+            if digits.is_empty() {
+                return write!(f, "0");
+            }
+            return write!(f, "{}{}", digits, "0".repeat(-self.scale as usize));
+        }
+        let scale = self.scale as usize;
+        let padded = format!(
+            "{}{}",
+            "0".repeat((scale + 1).saturating_sub(digits.len())),
+            digits
+        );
+        let (int_part, frac_part) = padded.split_at(padded.len() - scale);
+        write!(f, "{}.{}", int_part, frac_part)
+    }
+}
+
+// BigInt is an exact integer of any size.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BigInt(Decimal);
+
+impl BigInt {
+    pub fn as_decimal(&self) -> &Decimal {
+        &self.0
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(val: i64) -> Self {
+        Self(Decimal::from(val))
+    }
+}
+
+// Parses integers such as `-123`, without fractional digits or exponent.
+impl FromStr for BigInt {
+    type Err = ParseNumberError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.strip_prefix(['-', '+']).unwrap_or(s);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseNumberError(s.to_string()));
+        }
+        Ok(Self(s.parse()?))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,30 @@
+// Copyright 2024 Democratized Data Foundation
+//
+// Use of this software is governed by the Business Source License
+// included in the file licenses/BSL.txt.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0, included in the file
+// licenses/APL.txt.
+
+const ELEMENT_SIZE: usize = std::mem::size_of::<f32>();
+
+// Encodes the vector as consecutive little-endian f32 values.
+pub fn encode(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|val| val.to_le_bytes()).collect()
+}
+
+// Decodes a vector encoded with `encode`, or returns `None` if the length of the bytes is not
+// a multiple of the element size.
+pub fn decode(bytes: &[u8]) -> Option<Vec<f32>> {
+    if !bytes.len().is_multiple_of(ELEMENT_SIZE) {
+        return None;
+    }
+    Some(
+        bytes
+            .chunks_exact(ELEMENT_SIZE)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect(),
+    )
+}